@@ -0,0 +1,49 @@
+//! Reading the bundled data files, and the errors they fail with.
+//!
+//! Both files are RON, read through serde by [`deserialize`]. A mode or a
+//! tile set is a struct in the file as it is in the code, and a set holds its
+//! own arms and tiles, which a flat format could only have shown by where the
+//! lines fell. What serde cannot know, such as a tile naming an arm its set
+//! does not have, each reader checks afterwards. Either way a file is refused
+//! rather than guessed at: a file that half-loads is a game that half-works,
+//! and a bundled file can always be fixed instead.
+
+use std::fmt;
+
+use ron::extensions::Extensions;
+use serde::de::DeserializeOwned;
+
+/// Why a file was refused, and where.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileError {
+    pub file: &'static str,
+    /// One-based; zero for a problem with the file as a whole.
+    pub line: usize,
+    pub reason: String,
+}
+
+impl fmt::Display for FileError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.line == 0 {
+            write!(f, "{}: {}", self.file, self.reason)
+        } else {
+            write!(f, "{}, line {}: {}", self.file, self.line, self.reason)
+        }
+    }
+}
+
+/// Reads a RON file into `T`, or says on which line it stopped.
+///
+/// Each type read this way refuses fields it does not know, since a typo left
+/// alone is a field quietly taking its default. An `Option` field is written
+/// bare, `to_level: 6`, rather than as `Some(6)`.
+pub fn deserialize<T: DeserializeOwned>(file: &'static str, source: &str) -> Result<T, FileError> {
+    ron::Options::default()
+        .with_default_extension(Extensions::IMPLICIT_SOME)
+        .from_str(source)
+        .map_err(|error| FileError {
+            file,
+            line: error.span.start.line,
+            reason: error.code.to_string(),
+        })
+}
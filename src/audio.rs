@@ -31,7 +31,7 @@ use bevy::prelude::*;
 
 use crate::events::InteractionAnimationEvent;
 use crate::feedback::{BannerEvent, BannerKind};
-use crate::storage;
+use crate::storage::{self, StorageBackend};
 use crate::AppState;
 
 const VOLUME_KEY: &str = "color_puzzle.volume";
@@ -120,9 +120,14 @@ impl Volume {
     ///
     /// A returning player who wanted silence hears one round of music and
     /// turns it down again, which is a far smaller harm than being stranded
-    /// with a mute they cannot see and did not mean.
+    /// with a mute they cannot see and did not mean. The flag itself is gone
+    /// since schema 1, which drops it.
     pub fn load() -> Self {
-        match storage::load(VOLUME_KEY).and_then(|v| v.parse::<u8>().ok()) {
+        Self::load_from(storage::backend())
+    }
+
+    pub fn load_from(store: &dyn StorageBackend) -> Self {
+        match store.load(VOLUME_KEY).and_then(|v| v.parse::<u8>().ok()) {
             Some(level) => Self(level.min(VOLUME_STEPS)),
             None => Self::default(),
         }
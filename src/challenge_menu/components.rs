@@ -0,0 +1,17 @@
+use bevy::prelude::Component;
+
+/// Root of the challenge screen. Everything under it is despawned together.
+#[derive(Component)]
+pub struct ChallengeMenu;
+
+/// Plays the code's run back.
+#[derive(Component)]
+pub struct ChallengeWatchButton;
+
+/// Starts a run on the code's boards.
+#[derive(Component)]
+pub struct ChallengePlayButton;
+
+/// Returns to the main menu.
+#[derive(Component)]
+pub struct ChallengeBackButton;
@@ -0,0 +1,38 @@
+//! The screen a pasted share code opens on.
+//!
+//! Reached only once the code has been played through and found to reach its
+//! score, so everything on it can be stated as fact: whose mode, what score,
+//! and the two things to do about it — watch the run, or play its boards.
+
+mod components;
+mod styles;
+mod systems;
+
+use bevy::prelude::*;
+
+use crate::AppState;
+use systems::interactions::*;
+use systems::layout::*;
+
+pub struct ChallengeMenuPlugin;
+
+impl Plugin for ChallengeMenuPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(AppState::Challenge), spawn_challenge_menu)
+            .add_systems(
+                Update,
+                (
+                    interact_with_watch_button,
+                    interact_with_play_against_button,
+                    interact_with_challenge_back_button,
+                )
+                    .run_if(in_state(AppState::Challenge)),
+            )
+            // Tears down live `Button` entities, so it runs after `Update`.
+            .add_systems(
+                PostUpdate,
+                relayout_challenge_menu.run_if(in_state(AppState::Challenge)),
+            )
+            .add_systems(OnExit(AppState::Challenge), despawn_challenge_menu);
+    }
+}
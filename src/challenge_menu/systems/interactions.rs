@@ -0,0 +1,111 @@
+use bevy::prelude::*;
+
+use crate::challenge_menu::components::*;
+use crate::events::TransitionToStateEvent;
+use crate::game::challenge::Challenge;
+use crate::game::practice::Practice;
+use crate::game::puzzle::components::{ColorPuzzle, GameHistory, PowerUps, RunLog, RunSeed};
+use crate::game::replay::components::ReplaySource;
+use crate::pagination::Pagination;
+use crate::theme;
+use crate::AppState;
+
+/// Watches the code's run, coming back here afterwards.
+pub fn interact_with_watch_button(
+    mut button_query: Query<
+        (&Interaction, &mut BackgroundColor),
+        (Changed<Interaction>, With<ChallengeWatchButton>),
+    >,
+    challenge: Res<Challenge>,
+    mut replay_source: ResMut<ReplaySource>,
+    mut transition_to_state_event_writer: MessageWriter<TransitionToStateEvent>,
+) {
+    for (interaction, mut background_color) in button_query.iter_mut() {
+        match *interaction {
+            Interaction::Pressed => {
+                *background_color = theme::BUTTON_PRESSED.into();
+                let Some(code) = challenge.code() else {
+                    continue;
+                };
+                *replay_source = ReplaySource {
+                    log: code.log.clone(),
+                    back_to: AppState::Challenge,
+                };
+                transition_to_state_event_writer.write(TransitionToStateEvent {
+                    state: AppState::Replay,
+                });
+            }
+            Interaction::Hovered => *background_color = theme::BUTTON_HOVERED.into(),
+            Interaction::None => *background_color = theme::BUTTON.into(),
+        }
+    }
+}
+
+/// Starts a run on the code's boards.
+///
+/// What the mode cards do, with the code's seed in place of a fresh one and
+/// none of the daily bookkeeping: a daily code is that day's boards, and
+/// playing them is not the player's attempt at today.
+pub fn interact_with_play_against_button(
+    mut button_query: Query<
+        (&Interaction, &mut BackgroundColor),
+        (Changed<Interaction>, With<ChallengePlayButton>),
+    >,
+    mut challenge: ResMut<Challenge>,
+    mut practice: ResMut<Practice>,
+    mut puzzle: ResMut<ColorPuzzle>,
+    mut game_history: ResMut<GameHistory>,
+    mut pagination: ResMut<Pagination>,
+    mut power_ups: ResMut<PowerUps>,
+    mut run_seed: ResMut<RunSeed>,
+    mut run_log: ResMut<RunLog>,
+    mut transition_to_state_event_writer: MessageWriter<TransitionToStateEvent>,
+) {
+    for (interaction, mut background_color) in button_query.iter_mut() {
+        match *interaction {
+            Interaction::Pressed => {
+                *background_color = theme::BUTTON_PRIMARY_PRESSED.into();
+                let Some(code) = challenge.code() else {
+                    continue;
+                };
+                let game_mode = code.log.game_mode;
+                *run_seed = RunSeed::new(code.log.seed);
+                challenge.begin();
+                practice.leave();
+
+                puzzle.setup(&game_mode);
+                power_ups.clear();
+                run_log.start(&run_seed, &puzzle, *power_ups);
+                game_history.reset();
+                game_history.set_game_mode(game_mode);
+                pagination.reset();
+                transition_to_state_event_writer.write(TransitionToStateEvent {
+                    state: AppState::Game,
+                });
+            }
+            Interaction::Hovered => *background_color = theme::BUTTON_PRIMARY_HOVERED.into(),
+            Interaction::None => *background_color = theme::BUTTON_PRIMARY.into(),
+        }
+    }
+}
+
+pub fn interact_with_challenge_back_button(
+    mut button_query: Query<
+        (&Interaction, &mut BackgroundColor),
+        (Changed<Interaction>, With<ChallengeBackButton>),
+    >,
+    mut transition_to_state_event_writer: MessageWriter<TransitionToStateEvent>,
+) {
+    for (interaction, mut background_color) in button_query.iter_mut() {
+        match *interaction {
+            Interaction::Pressed => {
+                *background_color = theme::BUTTON_PRESSED.into();
+                transition_to_state_event_writer.write(TransitionToStateEvent {
+                    state: AppState::MainMenu,
+                });
+            }
+            Interaction::Hovered => *background_color = theme::BUTTON_HOVERED.into(),
+            Interaction::None => *background_color = theme::BUTTON.into(),
+        }
+    }
+}
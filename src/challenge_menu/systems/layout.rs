@@ -0,0 +1,163 @@
+//! Builds the challenge screen.
+
+use bevy::prelude::*;
+
+use crate::challenge_menu::components::*;
+use crate::challenge_menu::styles::*;
+use crate::game::challenge::Challenge;
+use crate::theme;
+
+pub fn spawn_challenge_menu(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    challenge: Res<Challenge>,
+    window_query: Query<&Window>,
+) {
+    let width = window_query
+        .single()
+        .map(|window| theme::content_width(window.width()))
+        .unwrap_or(theme::CONTENT_MAX_WIDTH);
+
+    build_challenge_menu(&mut commands, &asset_server, &challenge, width);
+}
+
+pub fn build_challenge_menu(
+    commands: &mut Commands,
+    asset_server: &Res<AssetServer>,
+    challenge: &Res<Challenge>,
+    width: f32,
+) -> Entity {
+    commands
+        .spawn((
+            (menu_style(), BackgroundColor(theme::BACKGROUND)),
+            ChallengeMenu,
+        ))
+        .with_children(|parent| {
+            parent.spawn(theme::wrapped_text(
+                "DESAFIO",
+                theme::text_title(asset_server),
+                width,
+            ));
+
+            if let Some(code) = challenge.code() {
+                let game_mode = code.log.game_mode;
+                // A daily code carries its day, which is not necessarily
+                // today: the boards are that day's, whenever it is opened.
+                let mode = match code.day() {
+                    Some(day) => format!(
+                        "{} {}",
+                        game_mode.as_str().to_uppercase(),
+                        crate::clock::date_label(day)
+                    ),
+                    None => game_mode.as_str().to_uppercase(),
+                };
+                parent.spawn(theme::wrapped_text(
+                    mode,
+                    theme::text(asset_server, theme::TEXT_SM, game_mode.accent()),
+                    width,
+                ));
+            }
+
+            parent.spawn(theme::wrapped_text(
+                format!("{}", challenge.score()),
+                theme::text_display(asset_server, theme::ON_SURFACE),
+                width,
+            ));
+            // Said because it is true, and it is the point: the number was
+            // worked out from the moves, not read off the code.
+            parent.spawn(theme::wrapped_text(
+                "PONTOS VERIFICADOS",
+                theme::text(asset_server, theme::TEXT_SM, theme::SUCCESS),
+                width,
+            ));
+
+            parent.spawn(spacer_style());
+
+            spawn_button(
+                parent,
+                asset_server,
+                "JOGAR CONTRA",
+                width,
+                theme::BUTTON_PRIMARY,
+                ChallengePlayButton,
+            );
+            spawn_button(
+                parent,
+                asset_server,
+                "VER REPLAY",
+                width,
+                theme::BUTTON,
+                ChallengeWatchButton,
+            );
+            spawn_button(
+                parent,
+                asset_server,
+                "VOLTAR",
+                width,
+                theme::BUTTON,
+                ChallengeBackButton,
+            );
+        })
+        .id()
+}
+
+fn spawn_button<M: Component>(
+    parent: &mut ChildSpawnerCommands,
+    asset_server: &Res<AssetServer>,
+    label: &str,
+    width: f32,
+    color: Color,
+    marker: M,
+) {
+    parent
+        .spawn((
+            (Button, theme::button_style(width), BackgroundColor(color)),
+            marker,
+        ))
+        .with_children(|parent| {
+            parent.spawn(theme::wrapped_text(
+                label,
+                theme::text_button(asset_server),
+                theme::button_text_width(width),
+            ));
+        });
+}
+
+pub fn despawn_challenge_menu(
+    mut commands: Commands,
+    query: Query<Entity, With<ChallengeMenu>>,
+) {
+    for entity in query.iter() {
+        commands.entity(entity).despawn();
+    }
+}
+
+/// Rebuilds for a window that changed size. Runs in `PostUpdate` for the same
+/// reason every other relayout does: it despawns live `Button` entities.
+pub fn relayout_challenge_menu(
+    mut commands: Commands,
+    mut relayout_events: MessageReader<crate::layout::RelayoutEvent>,
+    asset_server: Res<AssetServer>,
+    challenge: Res<Challenge>,
+    window_query: Query<&Window>,
+    menu_query: Query<Entity, With<ChallengeMenu>>,
+) {
+    if relayout_events.read().next().is_none() {
+        return;
+    }
+
+    let Ok(window) = window_query.single() else {
+        return;
+    };
+
+    for entity in menu_query.iter() {
+        commands.entity(entity).despawn();
+    }
+
+    build_challenge_menu(
+        &mut commands,
+        &asset_server,
+        &challenge,
+        theme::content_width(window.width()),
+    );
+}
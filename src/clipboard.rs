@@ -0,0 +1,66 @@
+//! The system clipboard, for share codes and progress codes.
+//!
+//! Desktop builds talk to it through `arboard`. The browser does not let a
+//! page read the clipboard without a permission prompt of its own and an async
+//! API Bevy's frame cannot wait on, so there a paste is a `prompt()` box the
+//! player pastes into. A copy is left to the page where the page has a way to
+//! do it (`docs/index.html` puts a share code in the text it shares), and is
+//! otherwise a `prompt()` box with the text already in it, selected, for the
+//! player to copy themselves.
+
+#[cfg(not(target_arch = "wasm32"))]
+mod native {
+    use std::sync::{Mutex, OnceLock};
+
+    use arboard::Clipboard;
+
+    /// Kept alive for the whole session. On X11 the copying program *is* the
+    /// clipboard: drop the handle and whatever was copied goes with it.
+    static CLIPBOARD: OnceLock<Option<Mutex<Clipboard>>> = OnceLock::new();
+
+    fn with_clipboard<T>(f: impl FnOnce(&mut Clipboard) -> Option<T>) -> Option<T> {
+        let clipboard = CLIPBOARD.get_or_init(|| Clipboard::new().ok().map(Mutex::new));
+        let mut clipboard = clipboard.as_ref()?.lock().ok()?;
+        f(&mut clipboard)
+    }
+
+    /// Whether the text made it onto the clipboard. A machine with no
+    /// clipboard to speak of (a bare CI box) just says no.
+    pub fn copy(text: &str) -> bool {
+        with_clipboard(|clipboard| clipboard.set_text(text).ok()).is_some()
+    }
+
+    pub fn paste(_prompt: &str) -> Option<String> {
+        with_clipboard(|clipboard| clipboard.get_text().ok())
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+pub use native::{copy, paste};
+
+/// Puts text where the player can take it. True when it is already on the
+/// clipboard; false when it was only shown, or could not be placed at all.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn offer(text: &str, _prompt: &str) -> bool {
+    copy(text)
+}
+
+#[cfg(target_arch = "wasm32")]
+pub fn offer(text: &str, prompt: &str) -> bool {
+    if let Some(window) = web_sys::window() {
+        let _ = window.prompt_with_message_and_default(prompt, text);
+    }
+    false
+}
+
+#[cfg(target_arch = "wasm32")]
+pub fn copy(_text: &str) -> bool {
+    false
+}
+
+/// `prompt` is what the box asks for. Only the browser shows it; the desktop
+/// reads the clipboard without asking.
+#[cfg(target_arch = "wasm32")]
+pub fn paste(prompt: &str) -> Option<String> {
+    web_sys::window()?.prompt_with_message(prompt).ok()?
+}
@@ -0,0 +1,61 @@
+//! The calendar date, for the daily challenge.
+//!
+//! Counted in whole days since 1970-01-01, UTC. UTC rather than the player's
+//! local date because the point of the day's boards is that everyone gets the
+//! same ones: two players a timezone apart must not land on different days for
+//! most of an afternoon. The cost is that the day turns over at 21:00 in
+//! Brasilia, which is a fair trade for a comparison that always holds.
+//!
+//! `std::time::SystemTime` panics on `wasm32-unknown-unknown` — there is no
+//! clock behind it there — so the browser asks JavaScript's `Date` instead.
+
+const MILLIS_PER_DAY: f64 = 86_400_000.0;
+
+#[cfg(target_arch = "wasm32")]
+pub fn today() -> u64 {
+    (js_sys::Date::now() / MILLIS_PER_DAY).max(0.0) as u64
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+pub fn today() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|elapsed| (elapsed.as_millis() as f64 / MILLIS_PER_DAY) as u64)
+        .unwrap_or(0)
+}
+
+/// The day as the player reads it: `18/10/2026`.
+///
+/// Converted by hand (Howard Hinnant's `civil_from_days`) rather than with a
+/// date crate, which would be a dependency for one label.
+pub fn date_label(day: u64) -> String {
+    let z = day as i64 + 719_468;
+    let era = z.div_euclid(146_097);
+    let day_of_era = z.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+    let day_of_month = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+    let month = if shifted_month < 10 {
+        shifted_month + 3
+    } else {
+        shifted_month - 9
+    };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+
+    format!("{:02}/{:02}/{}", day_of_month, month, year)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn days_read_as_calendar_dates() {
+        assert_eq!(date_label(0), "01/01/1970");
+        // A leap day, and the turn of a century that is one.
+        assert_eq!(date_label(11_016), "29/02/2000");
+        assert_eq!(date_label(20_744), "18/10/2026");
+    }
+}
@@ -0,0 +1,22 @@
+use bevy::prelude::Component;
+
+use crate::game::custom::Dial;
+
+/// Root of the custom game screen. Everything under it is despawned together.
+#[derive(Component)]
+pub struct CustomMenu;
+
+/// Turns its dial one step, up or down.
+#[derive(Component)]
+pub struct CustomDialButton {
+    pub dial: Dial,
+    pub up: bool,
+}
+
+/// Starts a run on the dials as they stand.
+#[derive(Component)]
+pub struct CustomPlayButton;
+
+/// Returns to the main menu.
+#[derive(Component)]
+pub struct CustomBackButton;
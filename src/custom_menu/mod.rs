@@ -0,0 +1,38 @@
+//! The custom game screen: every rule of a run, one dial per row.
+//!
+//! Each row is the dial's current value between a button that turns it down
+//! and one that turns it up. The dials are kept as they were left, and
+//! "jogar" starts a run on them; see `game::custom`.
+
+mod components;
+mod styles;
+mod systems;
+
+use bevy::prelude::*;
+
+use crate::AppState;
+use systems::interactions::*;
+use systems::layout::*;
+
+pub struct CustomMenuPlugin;
+
+impl Plugin for CustomMenuPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(AppState::Custom), spawn_custom_menu)
+            .add_systems(
+                Update,
+                (
+                    interact_with_custom_dial_button,
+                    interact_with_custom_play_button,
+                    interact_with_custom_back_button,
+                )
+                    .run_if(in_state(AppState::Custom)),
+            )
+            // Tears down live `Button` entities, so it runs after `Update`.
+            .add_systems(
+                PostUpdate,
+                relayout_custom_menu.run_if(in_state(AppState::Custom)),
+            )
+            .add_systems(OnExit(AppState::Custom), despawn_custom_menu);
+    }
+}
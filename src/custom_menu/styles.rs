@@ -0,0 +1,42 @@
+//! Layout for the custom game screen. Colours and type come from `theme`.
+
+use bevy::prelude::*;
+
+use crate::theme;
+
+/// Share of a row the value between the two buttons takes.
+const VALUE_SHARE: f32 = 0.6;
+
+pub fn menu_style() -> Node {
+    Node {
+        flex_direction: FlexDirection::Column,
+        justify_content: JustifyContent::Center,
+        align_items: AlignItems::Center,
+        width: Val::Percent(100.0),
+        height: Val::Percent(100.0),
+        padding: UiRect::vertical(Val::Px(theme::SPACE_MD)),
+        row_gap: Val::Px(theme::SPACE_XS),
+        ..Node::DEFAULT
+    }
+}
+
+pub fn dial_row_style(width: f32) -> Node {
+    Node {
+        flex_direction: FlexDirection::Row,
+        justify_content: JustifyContent::SpaceBetween,
+        align_items: AlignItems::Center,
+        width: Val::Px(width),
+        ..Node::DEFAULT
+    }
+}
+
+/// Width of the value in the middle of a row.
+pub fn value_width(width: f32) -> f32 {
+    width * VALUE_SHARE
+}
+
+/// Width of each of the two buttons either side of it, so the row, margins
+/// included, takes the room one full-width button would.
+pub fn step_button_width(width: f32) -> f32 {
+    (width * (1.0 - VALUE_SHARE) - theme::SPACE_XS * 4.0) / 2.0
+}
@@ -0,0 +1,95 @@
+use bevy::prelude::*;
+
+use crate::custom_menu::components::*;
+use crate::events::TransitionToStateEvent;
+use crate::game::challenge::Challenge;
+use crate::game::custom::CustomGame;
+use crate::game::practice::Practice;
+use crate::game::puzzle::components::{
+    ColorPuzzle, GameHistory, GameMode, PowerUps, RunLog, RunSeed,
+};
+use crate::pagination::Pagination;
+use crate::theme;
+use crate::AppState;
+
+pub fn interact_with_custom_dial_button(
+    mut button_query: Query<
+        (&Interaction, &mut BackgroundColor, &CustomDialButton),
+        Changed<Interaction>,
+    >,
+    mut custom: ResMut<CustomGame>,
+) {
+    for (interaction, mut background_color, button) in button_query.iter_mut() {
+        match *interaction {
+            // The relayout rebuilds the screen with the new value.
+            Interaction::Pressed => custom.turn(button.dial, button.up),
+            Interaction::Hovered => *background_color = theme::BUTTON_HOVERED.into(),
+            Interaction::None => *background_color = theme::BUTTON.into(),
+        }
+    }
+}
+
+/// Starts a custom run on the dials as they stand.
+///
+/// What the mode cards do, with the rules handed over rather than read off
+/// a mode, and none of the daily bookkeeping.
+pub fn interact_with_custom_play_button(
+    mut button_query: Query<
+        (&Interaction, &mut BackgroundColor),
+        (Changed<Interaction>, With<CustomPlayButton>),
+    >,
+    custom: Res<CustomGame>,
+    mut challenge: ResMut<Challenge>,
+    mut practice: ResMut<Practice>,
+    mut puzzle: ResMut<ColorPuzzle>,
+    mut game_history: ResMut<GameHistory>,
+    mut pagination: ResMut<Pagination>,
+    mut power_ups: ResMut<PowerUps>,
+    mut run_seed: ResMut<RunSeed>,
+    mut run_log: ResMut<RunLog>,
+    mut transition_to_state_event_writer: MessageWriter<TransitionToStateEvent>,
+) {
+    for (interaction, mut background_color) in button_query.iter_mut() {
+        match *interaction {
+            Interaction::Pressed => {
+                *background_color = theme::BUTTON_PRESSED.into();
+                challenge.leave();
+                practice.leave();
+
+                puzzle.setup_custom(custom.rules);
+                *run_seed = RunSeed::for_mode(GameMode::Custom);
+                power_ups.clear();
+                run_log.start(&run_seed, &puzzle, *power_ups);
+                game_history.reset();
+                game_history.set_game_mode(GameMode::Custom);
+                pagination.reset();
+                transition_to_state_event_writer.write(TransitionToStateEvent {
+                    state: AppState::Game,
+                });
+            }
+            Interaction::Hovered => *background_color = theme::BUTTON_HOVERED.into(),
+            Interaction::None => *background_color = theme::ACCENT.into(),
+        }
+    }
+}
+
+pub fn interact_with_custom_back_button(
+    mut button_query: Query<
+        (&Interaction, &mut BackgroundColor),
+        (Changed<Interaction>, With<CustomBackButton>),
+    >,
+    mut transition_to_state_event_writer: MessageWriter<TransitionToStateEvent>,
+) {
+    for (interaction, mut background_color) in button_query.iter_mut() {
+        match *interaction {
+            Interaction::Pressed => {
+                *background_color = theme::BUTTON_PRESSED.into();
+                transition_to_state_event_writer.write(TransitionToStateEvent {
+                    state: AppState::MainMenu,
+                });
+            }
+            Interaction::Hovered => *background_color = theme::BUTTON_HOVERED.into(),
+            Interaction::None => *background_color = theme::BUTTON.into(),
+        }
+    }
+}
@@ -0,0 +1,160 @@
+//! Builds the custom game screen.
+
+use bevy::prelude::*;
+
+use crate::custom_menu::components::*;
+use crate::custom_menu::styles::*;
+use crate::game::custom::{CustomGame, Dial};
+use crate::game::puzzle::components::ColorPuzzle;
+use crate::oklab::Metric;
+use crate::theme;
+
+pub fn spawn_custom_menu(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    custom: Res<CustomGame>,
+    puzzle: Res<ColorPuzzle>,
+    window_query: Query<&Window>,
+) {
+    let width = window_query
+        .single()
+        .map(|window| theme::content_width(window.width()))
+        .unwrap_or(theme::CONTENT_MAX_WIDTH);
+
+    build_custom_menu(&mut commands, &asset_server, &custom, puzzle.metric, width);
+}
+
+pub fn build_custom_menu(
+    commands: &mut Commands,
+    asset_server: &Res<AssetServer>,
+    custom: &Res<CustomGame>,
+    metric: Metric,
+    width: f32,
+) -> Entity {
+    commands
+        .spawn((
+            (menu_style(), BackgroundColor(theme::BACKGROUND)),
+            CustomMenu,
+        ))
+        .with_children(|parent| {
+            parent.spawn(theme::wrapped_text(
+                "PERSONALIZADO",
+                theme::text_title(asset_server),
+                width,
+            ));
+            // Said up front, so nobody dials in an easy game expecting it to
+            // count.
+            parent.spawn(theme::wrapped_text(
+                "SEM RECORDE, SEM METAS",
+                theme::text(asset_server, theme::TEXT_XS, theme::MUTED),
+                width,
+            ));
+
+            for dial in Dial::iter() {
+                parent.spawn(dial_row_style(width)).with_children(|parent| {
+                    spawn_button(
+                        parent,
+                        asset_server,
+                        "-",
+                        step_button_width(width),
+                        theme::BUTTON,
+                        CustomDialButton { dial, up: false },
+                    );
+                    parent.spawn(theme::wrapped_text(
+                        dial.label(&custom.rules, metric),
+                        theme::text(asset_server, theme::TEXT_SM, theme::ON_SURFACE),
+                        value_width(width),
+                    ));
+                    spawn_button(
+                        parent,
+                        asset_server,
+                        "+",
+                        step_button_width(width),
+                        theme::BUTTON,
+                        CustomDialButton { dial, up: true },
+                    );
+                });
+            }
+
+            spawn_button(
+                parent,
+                asset_server,
+                "JOGAR",
+                width,
+                theme::ACCENT,
+                CustomPlayButton,
+            );
+            spawn_button(
+                parent,
+                asset_server,
+                "VOLTAR",
+                width,
+                theme::BUTTON,
+                CustomBackButton,
+            );
+        })
+        .id()
+}
+
+fn spawn_button<M: Component>(
+    parent: &mut ChildSpawnerCommands,
+    asset_server: &Res<AssetServer>,
+    label: &str,
+    width: f32,
+    color: Color,
+    marker: M,
+) {
+    parent
+        .spawn((
+            (Button, theme::button_style(width), BackgroundColor(color)),
+            marker,
+        ))
+        .with_children(|parent| {
+            parent.spawn(theme::wrapped_text(
+                label,
+                theme::text_button(asset_server),
+                theme::button_text_width(width),
+            ));
+        });
+}
+
+pub fn despawn_custom_menu(mut commands: Commands, query: Query<Entity, With<CustomMenu>>) {
+    for entity in query.iter() {
+        commands.entity(entity).despawn();
+    }
+}
+
+/// Rebuilds for a window that changed size, and for a turned dial, which
+/// relabels its row and can move the one it is paired with. Runs in
+/// `PostUpdate` for the same reason every other relayout does: it despawns
+/// live `Button` entities.
+pub fn relayout_custom_menu(
+    mut commands: Commands,
+    mut relayout_events: MessageReader<crate::layout::RelayoutEvent>,
+    asset_server: Res<AssetServer>,
+    custom: Res<CustomGame>,
+    puzzle: Res<ColorPuzzle>,
+    window_query: Query<&Window>,
+    menu_query: Query<Entity, With<CustomMenu>>,
+) {
+    let resized = relayout_events.read().next().is_some();
+    if !resized && !custom.is_changed() {
+        return;
+    }
+
+    let Ok(window) = window_query.single() else {
+        return;
+    };
+
+    for entity in menu_query.iter() {
+        commands.entity(entity).despawn();
+    }
+
+    build_custom_menu(
+        &mut commands,
+        &asset_server,
+        &custom,
+        puzzle.metric,
+        theme::content_width(window.width()),
+    );
+}
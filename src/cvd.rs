@@ -0,0 +1,256 @@
+//! Colour vision deficiency: what a board looks like to someone missing one
+//! kind of cone, and which colour differences they can still see.
+//!
+//! The generators pick hues around the whole wheel and push the answer in any
+//! direction, which is right for typical colour vision and hopeless without
+//! it. To a protanope or deuteranope most of the red-green axis collapses, so
+//! a board whose groups differ by hue can turn into one flat colour, and an
+//! answer moved along that axis is not different at all. Past the first few
+//! levels, where the answer's delta is small and lightness no longer gives it
+//! away, most rounds had no answer anyone with the deficiency could see.
+//!
+//! With a deficiency chosen in the settings, the generators keep to the plane
+//! the simulation leaves intact: lightness, and the one hue axis that
+//! survives. Which hue axis that is is not written down here. It is measured
+//! from the simulation itself (`preserved_hue`), so the constraint and the
+//! model it comes from cannot drift apart. `fairness` then judges every board
+//! through the same simulation, so a board two groups of which look the same
+//! to the player is dealt again like any other unfair board.
+//!
+//! The simulation is Machado, Oliveira and Fernandes (2009) at full severity,
+//! a 3x3 matrix in linear RGB per deficiency. It models dichromacy. Most
+//! people with a red-green deficiency are anomalous trichromats who see more
+//! than that, so a board built for the dichromat is harder for them than it
+//! needs to be, but never impossible.
+
+use std::sync::OnceLock;
+
+use bevy::prelude::*;
+
+use crate::oklab::{self, Metric, Oklab};
+use crate::storage::{self, StorageBackend};
+
+const STORAGE_KEY: &str = "color_puzzle.color_vision";
+
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Eq, Default, Reflect)]
+pub enum ColorVision {
+    #[default]
+    Typical,
+    /// No long-wavelength cones: reds darken and fall in with greens.
+    Protan,
+    /// No medium-wavelength cones: the commonest, red and green confused.
+    Deutan,
+    /// No short-wavelength cones: blues and greens, yellows and pinks.
+    Tritan,
+}
+
+impl ColorVision {
+    pub fn iter() -> impl Iterator<Item = ColorVision> {
+        [
+            ColorVision::Typical,
+            ColorVision::Protan,
+            ColorVision::Deutan,
+            ColorVision::Tritan,
+        ]
+        .into_iter()
+    }
+
+    /// Stable key for storage and share codes. Never change these strings.
+    pub fn storage_key(&self) -> &'static str {
+        match self {
+            ColorVision::Typical => "typical",
+            ColorVision::Protan => "protan",
+            ColorVision::Deutan => "deutan",
+            ColorVision::Tritan => "tritan",
+        }
+    }
+
+    /// The settings button's label. ASCII only, like every label.
+    pub fn label(&self) -> &'static str {
+        match self {
+            ColorVision::Typical => "CORES: TIPICAS",
+            ColorVision::Protan => "CORES: PROTANOPIA",
+            ColorVision::Deutan => "CORES: DEUTERANOPIA",
+            ColorVision::Tritan => "CORES: TRITANOPIA",
+        }
+    }
+
+    /// The next setting round, persisted. One button that cycles, like the
+    /// volume: four choices are too few to be worth a list.
+    pub fn cycle(&mut self) {
+        let all: Vec<ColorVision> = Self::iter().collect();
+        let at = all.iter().position(|vision| vision == self).unwrap_or(0);
+        *self = all[(at + 1) % all.len()];
+        storage::save(STORAGE_KEY, self.storage_key());
+    }
+
+    pub fn load() -> Self {
+        Self::load_from(storage::backend())
+    }
+
+    pub fn load_from(store: &dyn StorageBackend) -> Self {
+        store
+            .load(STORAGE_KEY)
+            .and_then(|key| Self::iter().find(|vision| vision.storage_key() == key.trim()))
+            .unwrap_or_default()
+    }
+
+    fn matrix(&self) -> Option<[[f32; 3]; 3]> {
+        match self {
+            ColorVision::Typical => None,
+            ColorVision::Protan => Some([
+                [0.152286, 1.052583, -0.204868],
+                [0.114503, 0.786281, 0.099216],
+                [-0.003882, -0.048116, 1.051998],
+            ]),
+            ColorVision::Deutan => Some([
+                [0.367322, 0.860646, -0.227968],
+                [0.280085, 0.672501, 0.047413],
+                [-0.011820, 0.042940, 0.968881],
+            ]),
+            ColorVision::Tritan => Some([
+                [1.255528, -0.076749, -0.178779],
+                [-0.078411, 0.930809, 0.147602],
+                [0.004733, 0.691367, 0.303900],
+            ]),
+        }
+    }
+
+    /// How `lab` looks with this deficiency, in Oklab, so distances measured
+    /// between simulated colours mean what the game's distances mean.
+    pub fn simulate_lab(&self, lab: Oklab) -> Oklab {
+        let Some(matrix) = self.matrix() else {
+            return lab;
+        };
+        oklab::from_linear(apply(matrix, oklab::to_linear(lab)))
+    }
+
+    /// How `color` looks with this deficiency.
+    pub fn simulate(&self, color: Color) -> Color {
+        let Some(matrix) = self.matrix() else {
+            return color;
+        };
+        let srgba = color.to_srgba();
+        let [r, g, b] = apply(
+            matrix,
+            [
+                oklab::srgb_to_linear(srgba.red),
+                oklab::srgb_to_linear(srgba.green),
+                oklab::srgb_to_linear(srgba.blue),
+            ],
+        );
+        Color::srgba(
+            oklab::linear_to_srgb(r.clamp(0.0, 1.0)),
+            oklab::linear_to_srgb(g.clamp(0.0, 1.0)),
+            oklab::linear_to_srgb(b.clamp(0.0, 1.0)),
+            srgba.alpha,
+        )
+    }
+
+    /// The distance between two colours as this player sees them.
+    pub fn distance(&self, metric: Metric, a: Oklab, b: Oklab) -> f32 {
+        metric.distance(self.simulate_lab(a), self.simulate_lab(b))
+    }
+
+    /// The hue angle, in the Oklab a-b plane, along which a difference
+    /// survives the simulation best, or `None` with nothing to avoid. The
+    /// opposite angle is the same axis.
+    ///
+    /// Found by sweeping half the wheel around a mid grey and keeping the
+    /// direction whose step comes through largest. Measured once per
+    /// deficiency and kept.
+    pub fn preserved_hue(&self) -> Option<f32> {
+        static HUES: OnceLock<[f32; 3]> = OnceLock::new();
+        let hues = HUES.get_or_init(|| {
+            [ColorVision::Protan, ColorVision::Deutan, ColorVision::Tritan]
+                .map(|vision| vision.measure_preserved_hue())
+        });
+
+        match self {
+            ColorVision::Typical => None,
+            ColorVision::Protan => Some(hues[0]),
+            ColorVision::Deutan => Some(hues[1]),
+            ColorVision::Tritan => Some(hues[2]),
+        }
+    }
+
+    /// Folds a hue drawn anywhere on the wheel onto the preserved axis: the
+    /// first half of the wheel onto one end, the second onto the other. Left
+    /// as it is with nothing to avoid.
+    ///
+    /// Folding a hue that was drawn anyway, rather than drawing a coin for
+    /// the end, leaves the draw where it was. With typical vision a deal
+    /// consumes its generator exactly as it did before there was a setting,
+    /// so codes shared before then still replay.
+    pub fn fold_hue(&self, hue: f32) -> f32 {
+        match self.preserved_hue() {
+            Some(preserved) if hue.rem_euclid(std::f32::consts::TAU) < std::f32::consts::PI => {
+                preserved
+            }
+            Some(preserved) => preserved + std::f32::consts::PI,
+            None => hue,
+        }
+    }
+
+    fn measure_preserved_hue(&self) -> f32 {
+        const STEPS: usize = 180;
+        const STEP: f32 = 0.02;
+        let grey = Oklab::new(0.65, 0.0, 0.0);
+
+        (0..STEPS)
+            .map(|step| {
+                let hue = std::f32::consts::PI * step as f32 / STEPS as f32;
+                let direction = (0.0, hue.cos(), hue.sin());
+                let seen = self.distance(
+                    Metric::Oklab,
+                    grey.offset(direction, STEP),
+                    grey.offset(direction, -STEP),
+                );
+                (hue, seen)
+            })
+            .max_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(hue, _)| hue)
+            .unwrap_or(0.0)
+    }
+}
+
+fn apply(matrix: [[f32; 3]; 3], [r, g, b]: [f32; 3]) -> [f32; 3] {
+    matrix.map(|row| row[0] * r + row[1] * g + row[2] * b)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A red and a green of the same lightness, either end of Oklab's `a`
+    /// axis: one colour to the red-green deficiencies, still two to the
+    /// others.
+    #[test]
+    fn the_confusions_are_the_known_ones() {
+        let red = Oklab::new(0.65, 0.12, 0.0);
+        let green = Oklab::new(0.65, -0.12, 0.0);
+
+        let typical = ColorVision::Typical.distance(Metric::Oklab, red, green);
+        assert!(ColorVision::Deutan.distance(Metric::Oklab, red, green) < typical * 0.4);
+        assert!(ColorVision::Protan.distance(Metric::Oklab, red, green) < typical * 0.5);
+        assert!(ColorVision::Tritan.distance(Metric::Oklab, red, green) > typical * 0.6);
+
+        // Greys are left alone, whatever the deficiency.
+        let grey = Color::srgb(0.4, 0.4, 0.4);
+        for vision in ColorVision::iter() {
+            let seen = vision.simulate(grey).to_srgba();
+            assert!((seen.red - 0.4).abs() < 1e-3 && (seen.blue - 0.4).abs() < 1e-3);
+        }
+    }
+
+    /// Red-green dichromats keep the blue-yellow axis; tritanopes lose it and
+    /// keep something close to red-green.
+    #[test]
+    fn the_preserved_axis_is_measured_where_expected() {
+        let from_b_axis = |hue: f32| (hue - std::f32::consts::FRAC_PI_2).abs();
+        assert!(from_b_axis(ColorVision::Protan.preserved_hue().unwrap()) < 0.6);
+        assert!(from_b_axis(ColorVision::Deutan.preserved_hue().unwrap()) < 0.6);
+        assert!(from_b_axis(ColorVision::Tritan.preserved_hue().unwrap()) > 0.6);
+        assert_eq!(ColorVision::Typical.preserved_hue(), None);
+    }
+}
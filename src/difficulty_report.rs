@@ -0,0 +1,440 @@
+//! `--difficulty-report`: deals thousands of boards per level without opening
+//! a window, and prints what came out.
+//!
+//! The curve functions in `puzzle::components` are tuned by hand, and each one
+//! only says what a level *asks* for. The board a player sees is what is left
+//! once the pattern has clamped the empty share, the palette has walked its
+//! chroma down, the answer has retried its way clear of the other groups, and
+//! `fairness` has sent back any board with two answers.
+//! This runs the same `generate_colors` the game does, reads back the
+//! [`DealReport`] each deal leaves behind, and tabulates it per level, so a
+//! change to a curve can be argued about with numbers.
+//!
+//! ```text
+//! cargo run --release -- --difficulty-report [--boards N] [--levels N]
+//!     [--window WxH] [--seed N] [--metric KEY] [--gamut KEY] [--csv]
+//! ```
+//!
+//! The window matters: it decides how many rows the honeycomb is cut into, so
+//! a phone's tall window deals more cells than a desktop's at the same level.
+//! The seed makes a report reproducible, which is what lets two of them be
+//! compared before and after a change.
+//!
+//! `--metric` deals in `oklab`, `ciede2000` or `cam16ucs` (see `Metric`), and
+//! every distance in the table is then in that metric's units.
+//!
+//! `--gamut` deals in `srgb` or `p3` (see `Gamut`), whatever the screen the
+//! report is run on could show, since nothing is shown. The `sep` columns,
+//! the distance between a board's two closest groups, are where the wider
+//! gamut's extra room turns up.
+//!
+//! Desktop only. It times every deal, and `Instant` has nothing behind it in
+//! the browser.
+
+use std::time::Instant;
+
+use bevy::prelude::*;
+
+use crate::game::puzzle::components::{
+    delta_for_level, palette_size_for_level, score_for_level, ColorPuzzle, DealReport, GameMode,
+    RunSeed,
+};
+use crate::oklab::{Gamut, Metric};
+
+pub const FLAG: &str = "--difficulty-report";
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Options {
+    pub boards: usize,
+    pub levels: usize,
+    pub window: Vec2,
+    pub seed: u64,
+    pub metric: Metric,
+    pub gamut: Gamut,
+    pub csv: bool,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            boards: 2000,
+            levels: 30,
+            // What the desktop build opens at.
+            window: Vec2::new(1280.0, 720.0),
+            seed: 1,
+            metric: Metric::Oklab,
+            gamut: Gamut::Srgb,
+            csv: false,
+        }
+    }
+}
+
+impl Options {
+    /// The report's options, or `None` when the flag is not on the command
+    /// line and the game should start as usual.
+    pub fn from_args(args: impl IntoIterator<Item = String>) -> Result<Option<Self>, String> {
+        let mut args = args.into_iter().skip(1).peekable();
+        if args.peek().map(String::as_str) != Some(FLAG) {
+            return Ok(None);
+        }
+        args.next();
+
+        let mut options = Self::default();
+        while let Some(arg) = args.next() {
+            let mut value = |name: &str| args.next().ok_or(format!("{name} needs a value"));
+            match arg.as_str() {
+                "--boards" => options.boards = parse(&value("--boards")?)?,
+                "--levels" => options.levels = parse(&value("--levels")?)?,
+                "--seed" => options.seed = parse(&value("--seed")?)?,
+                "--window" => {
+                    let raw = value("--window")?;
+                    let (width, height) = raw
+                        .split_once('x')
+                        .ok_or(format!("--window wants WxH, not {raw}"))?;
+                    options.window = Vec2::new(parse(width)?, parse(height)?);
+                }
+                "--metric" => {
+                    let raw = value("--metric")?;
+                    options.metric = Metric::iter()
+                        .find(|metric| metric.storage_key() == raw)
+                        .ok_or(format!("unknown metric {raw}"))?;
+                }
+                "--gamut" => {
+                    let raw = value("--gamut")?;
+                    options.gamut = Gamut::iter()
+                        .find(|gamut| gamut.storage_key() == raw)
+                        .ok_or(format!("unknown gamut {raw}"))?;
+                }
+                "--csv" => options.csv = true,
+                other => return Err(format!("unknown option {other}")),
+            }
+        }
+
+        if options.boards == 0 || options.levels == 0 {
+            return Err("--boards and --levels must be at least 1".to_string());
+        }
+        Ok(Some(options))
+    }
+}
+
+fn parse<T: std::str::FromStr>(raw: &str) -> Result<T, String> {
+    raw.trim().parse().map_err(|_| format!("not a number: {raw}"))
+}
+
+/// Every value one column took over a level's boards.
+#[derive(Debug, Default)]
+struct Samples(Vec<f64>);
+
+impl Samples {
+    fn push(&mut self, value: impl Into<f64>) {
+        self.0.push(value.into());
+    }
+
+    fn finish(&mut self) {
+        self.0.sort_by(f64::total_cmp);
+    }
+
+    fn min(&self) -> f64 {
+        self.0.first().copied().unwrap_or(f64::NAN)
+    }
+
+    fn max(&self) -> f64 {
+        self.0.last().copied().unwrap_or(f64::NAN)
+    }
+
+    fn mean(&self) -> f64 {
+        self.0.iter().sum::<f64>() / self.0.len().max(1) as f64
+    }
+
+    /// The value `share` of the way up. The 5th percentile is the column that
+    /// matters for the distances: the minimum is one unlucky board, the mean
+    /// hides the tail the player actually notices.
+    fn percentile(&self, share: f64) -> f64 {
+        if self.0.is_empty() {
+            return f64::NAN;
+        }
+        let at = ((self.0.len() - 1) as f64 * share).round() as usize;
+        self.0[at]
+    }
+
+    /// Share of samples that were non-zero, for the flags.
+    fn rate(&self) -> f64 {
+        self.0.iter().filter(|value| **value != 0.0).count() as f64 / self.0.len().max(1) as f64
+    }
+}
+
+/// One level's boards, column by column.
+#[derive(Debug, Default)]
+struct LevelStats {
+    cells: Samples,
+    pieces: Samples,
+    groups: Samples,
+    to_group: Samples,
+    to_others: Samples,
+    separation: Samples,
+    micros: Samples,
+    answer_fallback: Samples,
+    grey_groups: Samples,
+    violations_short: Samples,
+    backtracks: Samples,
+    redealt: Samples,
+}
+
+impl LevelStats {
+    fn record(&mut self, deal: DealReport, micros: f64) {
+        self.cells.push(deal.cells as f64);
+        self.pieces.push(deal.pieces as f64);
+        self.groups.push(deal.groups as f64);
+        self.to_group.push(deal.answer_to_group);
+        // A one-group board has no other group to be near. Left out rather
+        // than counted as infinitely far, which would drag the mean to
+        // infinity and say nothing.
+        if deal.answer_to_others.is_finite() {
+            self.to_others.push(deal.answer_to_others);
+        }
+        if deal.group_separation.is_finite() {
+            self.separation.push(deal.group_separation);
+        }
+        self.micros.push(micros);
+        self.answer_fallback.push(u8::from(deal.answer_fallback));
+        self.grey_groups.push(u8::from(deal.grey_groups > 0));
+        self.violations_short.push(u8::from(deal.violations < deal.violations_wanted));
+        self.backtracks.push(deal.backtracks as f64);
+        self.redealt.push(u8::from(deal.attempts > 1));
+    }
+
+    fn finish(&mut self) {
+        for samples in [
+            &mut self.cells,
+            &mut self.pieces,
+            &mut self.groups,
+            &mut self.to_group,
+            &mut self.to_others,
+            &mut self.separation,
+            &mut self.micros,
+            &mut self.backtracks,
+        ] {
+            samples.finish();
+        }
+    }
+}
+
+/// Deals `options.boards` boards at each level in `game_mode`.
+fn measure(options: &Options, game_mode: GameMode) -> Vec<LevelStats> {
+    let mut puzzle = ColorPuzzle::default();
+    puzzle.metric = options.metric;
+    puzzle.gamut = options.gamut;
+    puzzle.setup(&game_mode);
+    puzzle.set_window_size(options.window.x, options.window.y);
+
+    // One seed for the whole report, a round per board: the same options
+    // always deal the same boards.
+    let mut seed = RunSeed::new(options.seed);
+
+    (1..=options.levels)
+        .map(|level| {
+            puzzle.restore_score(score_for_level(level));
+            let mut stats = LevelStats::default();
+            for _ in 0..options.boards {
+                let started = Instant::now();
+                puzzle.generate_colors(&mut seed.rng());
+                let micros = started.elapsed().as_secs_f64() * 1e6;
+                seed.advance();
+
+                stats.record(puzzle.last_deal(), micros);
+            }
+            stats.finish();
+            stats
+        })
+        .collect()
+}
+
+/// Runs the report and prints it to stdout.
+pub fn run(options: &Options) {
+    println!(
+        "# {} boards per level, window {}x{}, seed {}, metric {}, gamut {}",
+        options.boards,
+        options.window.x,
+        options.window.y,
+        options.seed,
+        options.metric.storage_key(),
+        options.gamut.storage_key()
+    );
+
+    // Every mode but `Mosaic` deals its board the same way, so one of them
+    // stands for all.
+    let colours = measure(options, GameMode::Infinite);
+    println!();
+    println!("# colour rounds");
+    print_rows(
+        options.csv,
+        &[
+            "level", "cells", "pieces", "groups", "wanted", "delta", "grp_min", "grp_mean",
+            "oth_min", "oth_p5", "oth_mean", "sep_p5", "sep_mean", "us_mean", "us_max", "fallback%", "grey%",
+            "redeal%",
+        ],
+        colours.iter().enumerate().map(|(index, stats)| {
+            let level = index + 1;
+            vec![
+                level.to_string(),
+                format!("{:.1}", stats.cells.mean()),
+                format!("{:.1}", stats.pieces.mean()),
+                format!("{:.2}", stats.groups.mean()),
+                palette_size_for_level(level).to_string(),
+                format!("{:.4}", delta_for_level(options.metric, level)),
+                format!("{:.4}", stats.to_group.min()),
+                format!("{:.4}", stats.to_group.mean()),
+                format!("{:.4}", stats.to_others.min()),
+                format!("{:.4}", stats.to_others.percentile(0.05)),
+                format!("{:.4}", stats.to_others.mean()),
+                format!("{:.4}", stats.separation.percentile(0.05)),
+                format!("{:.4}", stats.separation.mean()),
+                format!("{:.0}", stats.micros.mean()),
+                format!("{:.0}", stats.micros.max()),
+                format!("{:.2}", stats.answer_fallback.rate() * 100.0),
+                format!("{:.2}", stats.grey_groups.rate() * 100.0),
+                format!("{:.2}", stats.redealt.rate() * 100.0),
+            ]
+        }),
+    );
+
+    let mosaics = measure(options, GameMode::Mosaic);
+    println!();
+    println!("# mosaic rounds");
+    print_rows(
+        options.csv,
+        &[
+            "level", "cells", "pieces_min", "pieces_mean", "us_mean", "us_max", "bt_mean", "bt_max",
+            "short%", "redeal%",
+        ],
+        mosaics.iter().enumerate().map(|(index, stats)| {
+            vec![
+                (index + 1).to_string(),
+                format!("{:.0}", stats.cells.mean()),
+                format!("{:.0}", stats.pieces.min()),
+                format!("{:.1}", stats.pieces.mean()),
+                format!("{:.0}", stats.micros.mean()),
+                format!("{:.0}", stats.micros.max()),
+                format!("{:.1}", stats.backtracks.mean()),
+                format!("{:.0}", stats.backtracks.max()),
+                format!("{:.2}", stats.violations_short.rate() * 100.0),
+                format!("{:.2}", stats.redealt.rate() * 100.0),
+            ]
+        }),
+    );
+}
+
+/// A table, aligned for reading or comma-separated for a spreadsheet.
+fn print_rows(csv: bool, header: &[&str], rows: impl Iterator<Item = Vec<String>>) {
+    let separator = if csv { "," } else { " " };
+    let cell = |text: &str, width: usize| {
+        if csv {
+            text.to_string()
+        } else {
+            format!("{text:>width$}")
+        }
+    };
+    let widths: Vec<usize> = header.iter().map(|name| name.len().max(8)).collect();
+
+    let line: Vec<String> = header
+        .iter()
+        .zip(&widths)
+        .map(|(name, width)| cell(name, *width))
+        .collect();
+    println!("{}", line.join(separator));
+
+    for row in rows {
+        let line: Vec<String> = row
+            .iter()
+            .zip(&widths)
+            .map(|(value, width)| cell(value, *width))
+            .collect();
+        println!("{}", line.join(separator));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(line: &str) -> Vec<String> {
+        std::iter::once("bevy-tetris")
+            .chain(line.split_whitespace())
+            .map(str::to_string)
+            .collect()
+    }
+
+    #[test]
+    fn options_are_read_only_behind_the_flag() {
+        assert_eq!(Options::from_args(args("")), Ok(None));
+        assert_eq!(Options::from_args(args("--boards 5")), Ok(None));
+
+        let options = Options::from_args(args(
+            "--difficulty-report --boards 50 --window 390x844 --metric ciede2000 --gamut p3 --csv",
+        ))
+        .unwrap()
+        .unwrap();
+        assert_eq!(options.boards, 50);
+        assert_eq!(options.metric, Metric::Ciede2000);
+        assert_eq!(options.gamut, Gamut::DisplayP3);
+        assert_eq!(options.window, Vec2::new(390.0, 844.0));
+        assert!(options.csv);
+        assert_eq!(options.levels, Options::default().levels);
+
+        assert!(Options::from_args(args("--difficulty-report --boards")).is_err());
+        assert!(Options::from_args(args("--difficulty-report --window 390")).is_err());
+        assert!(Options::from_args(args("--difficulty-report --levels 0")).is_err());
+        assert!(Options::from_args(args("--difficulty-report --metric cielab")).is_err());
+        assert!(Options::from_args(args("--difficulty-report --gamut rec2020")).is_err());
+    }
+
+    /// The measurements themselves, on a small run: every colour board has an
+    /// answer near its own group and nothing closer than that elsewhere.
+    #[test]
+    fn a_small_run_measures_every_board() {
+        let options = Options {
+            boards: 20,
+            levels: 3,
+            ..Options::default()
+        };
+
+        let stats = measure(&options, GameMode::Infinite);
+        assert_eq!(stats.len(), 3);
+        for (index, level) in stats.iter().enumerate() {
+            assert_eq!(level.cells.0.len(), 20);
+            assert!(level.pieces.min() > 0.0);
+            let delta = delta_for_level(options.metric, index + 1) as f64;
+            assert!(level.to_group.mean() > delta * 0.5, "level {}", index + 1);
+            assert!(level.to_others.mean() > level.to_group.mean());
+        }
+
+        let mosaics = measure(&options, GameMode::Mosaic);
+        assert!(mosaics.iter().all(|level| level.pieces.min() > 0.0));
+    }
+
+    /// The point of dealing in P3: the groups have more room between them.
+    #[test]
+    fn a_wider_gamut_spreads_the_groups_further() {
+        let srgb = Options {
+            boards: 40,
+            levels: 4,
+            ..Options::default()
+        };
+        let p3 = Options {
+            gamut: Gamut::DisplayP3,
+            ..srgb.clone()
+        };
+
+        let narrow = measure(&srgb, GameMode::Infinite);
+        let wide = measure(&p3, GameMode::Infinite);
+        for (level, (narrow, wide)) in narrow.iter().zip(&wide).enumerate().skip(1) {
+            assert!(
+                wide.separation.mean() > narrow.separation.mean() * 1.1,
+                "level {}: {} against {}",
+                level + 1,
+                wide.separation.mean(),
+                narrow.separation.mean()
+            );
+        }
+    }
+}
@@ -0,0 +1,135 @@
+//! The byte-level pieces the text codes are built from: varints, a checksum
+//! and URL-safe base64.
+//!
+//! Shared by the share codes and the progress export, which are both "some
+//! bytes, made safe to paste into a chat" and have no reason to disagree on
+//! how. Hand-rolled for the same reason the stored formats are: a few dozen
+//! lines against a dependency each.
+
+/// Appends `value` seven bits a byte, low bits first, the high bit set on
+/// every byte but the last.
+pub fn write_varint(bytes: &mut Vec<u8>, mut value: u64) {
+    loop {
+        let low = (value & 0x7f) as u8;
+        value >>= 7;
+        if value == 0 {
+            bytes.push(low);
+            return;
+        }
+        bytes.push(low | 0x80);
+    }
+}
+
+/// Appends a length and then the bytes, so a string can hold anything.
+pub fn write_bytes(bytes: &mut Vec<u8>, value: &[u8]) {
+    write_varint(bytes, value.len() as u64);
+    bytes.extend_from_slice(value);
+}
+
+/// FNV-1a, 32 bits. Not a signature: anyone can recompute it. It is there to
+/// tell a mangled paste from a real code; whatever has to be trusted is
+/// checked some other way.
+pub fn fnv1a(bytes: &[u8]) -> u32 {
+    bytes.iter().fold(0x811c_9dc5, |hash, byte| {
+        (hash ^ *byte as u32).wrapping_mul(0x0100_0193)
+    })
+}
+
+/// Reads back what the `write_` functions wrote. Every read that runs off the
+/// end, or finds something other than what it was asked for, fails with the
+/// one error the reader was made with: to whoever pasted it, a code cut short
+/// and a code with garbage in it are the same thing.
+pub struct Reader<'a, E> {
+    bytes: &'a [u8],
+    at: usize,
+    error: E,
+}
+
+impl<'a, E: Copy> Reader<'a, E> {
+    pub fn new(bytes: &'a [u8], error: E) -> Self {
+        Self {
+            bytes,
+            at: 0,
+            error,
+        }
+    }
+
+    pub fn remaining(&self) -> usize {
+        self.bytes.len() - self.at
+    }
+
+    pub fn skip(&mut self, len: usize) {
+        self.at = (self.at + len).min(self.bytes.len());
+    }
+
+    pub fn take(&mut self, len: usize) -> Result<&'a [u8], E> {
+        let end = self.at.checked_add(len).ok_or(self.error)?;
+        let taken = self.bytes.get(self.at..end).ok_or(self.error)?;
+        self.at = end;
+        Ok(taken)
+    }
+
+    pub fn byte(&mut self) -> Result<u8, E> {
+        Ok(self.take(1)?[0])
+    }
+
+    pub fn f32(&mut self) -> Result<f32, E> {
+        let bytes = self.take(4)?;
+        Ok(f32::from_bits(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]])))
+    }
+
+    pub fn varint(&mut self) -> Result<u64, E> {
+        let mut value = 0u64;
+        for shift in (0..64).step_by(7) {
+            let byte = self.byte()?;
+            value |= ((byte & 0x7f) as u64) << shift;
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+        Err(self.error)
+    }
+
+    /// A string written by `write_bytes`.
+    pub fn string(&mut self) -> Result<String, E> {
+        let len = self.varint()?;
+        let len = usize::try_from(len).map_err(|_| self.error)?;
+        let bytes = self.take(len)?;
+        String::from_utf8(bytes.to_vec()).map_err(|_| self.error)
+    }
+}
+
+const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+
+/// URL-safe base64 without padding.
+pub fn base64_encode(bytes: &[u8]) -> String {
+    let mut text = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let word = chunk
+            .iter()
+            .enumerate()
+            .fold(0u32, |word, (index, byte)| word | (*byte as u32) << (16 - 8 * index));
+        for index in 0..=chunk.len() {
+            text.push(ALPHABET[(word >> (18 - 6 * index) & 0x3f) as usize] as char);
+        }
+    }
+    text
+}
+
+pub fn base64_decode(text: &str) -> Option<Vec<u8>> {
+    let mut bytes = Vec::with_capacity(text.len() * 3 / 4);
+    for chunk in text.as_bytes().chunks(4) {
+        if chunk.len() == 1 {
+            return None;
+        }
+        let mut word = 0u32;
+        for (index, symbol) in chunk.iter().enumerate() {
+            let value = ALPHABET.iter().position(|c| c == symbol)? as u32;
+            word |= value << (18 - 6 * index);
+        }
+        for index in 0..chunk.len() - 1 {
+            bytes.push((word >> (16 - 8 * index)) as u8);
+        }
+    }
+    Some(bytes)
+}
@@ -0,0 +1,413 @@
+//! Whether a dealt round has exactly one defensible answer.
+//!
+//! The generators are built to deal fair boards: `answer_color` looks for a
+//! direction that clears the other groups, `corrupt` looks for a break the
+//! player can tell apart from its neighbours. Both can fail. When they do,
+//! they fall back to the closest thing they found rather than panic, and a
+//! fallback is exactly where a board with two answers would come from. The
+//! player picks one of two equally good cells, the game calls it a miss, and
+//! nothing on screen says why.
+//!
+//! So the invariants the generators' comments promise are checked here, on
+//! the board as it will be drawn, and a board that breaks one is dealt again.
+//! The checks read only what the player sees (the colours as displayed, the
+//! tiles as laid) and not what the generator meant, since a promise kept in
+//! Oklab and broken by the trip to sRGB is still broken.
+//!
+//! Free of game state, like `wfc` and `mosaic_pattern`, so the rules can be
+//! tested on boards built by hand to break them.
+
+use std::fmt;
+
+use bevy::prelude::Color;
+
+use crate::game::puzzle::components::colors_match;
+use crate::oklab::{self, Metric};
+use crate::wfc::{self, Lattice, Mosaic, Tile, TileSet};
+
+/// Deals tried before a round is played as it stands. Each retry draws from
+/// the same generator, so a replay deals the same retries and ends up on the
+/// same board.
+pub const ATTEMPTS: usize = 8;
+
+/// The rule a board broke, and where.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Unfair {
+    /// The answer index points past the board.
+    AnswerOutOfRange { answer: usize, cells: usize },
+    /// Another cell wears exactly the answer's colour, so the ground's last
+    /// step erases both.
+    SharedColour { answer: usize, other: usize },
+    /// The answer wears one of the group colours the ground sweeps through,
+    /// so it vanishes mid-sweep along with that group.
+    GroupColour { group: usize },
+    /// A group other than its own sits within `clearance` of the answer, so
+    /// when the ground settles that group nearly vanishes too.
+    CrowdedAnswer {
+        group: usize,
+        distance: f32,
+        clearance: f32,
+    },
+    /// The `Mosaic` break did not take: the answer fits like every other cell.
+    NoBreak { answer: usize },
+    /// Another `Mosaic` cell has as many bad edges as the answer, so "the
+    /// piece that is wrong" points at two cells.
+    RivalBreak {
+        answer: usize,
+        other: usize,
+        violations: usize,
+    },
+    /// No tile in the answer's place mends the board: something else on it
+    /// is broken too, and the player has two faults to choose between.
+    Unrepairable { answer: usize },
+    /// Swapping another `Mosaic` cell's tile mends the board as well as
+    /// swapping the answer's does.
+    RivalRepair { answer: usize, other: usize },
+    /// A `Repair` board that already fits together: a round won before the
+    /// first tap.
+    NothingToRepair,
+}
+
+impl fmt::Display for Unfair {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Unfair::AnswerOutOfRange { answer, cells } => {
+                write!(f, "answer {answer} is outside a board of {cells} cells")
+            }
+            Unfair::SharedColour { answer, other } => {
+                write!(f, "cell {other} wears the same colour as answer {answer}")
+            }
+            Unfair::GroupColour { group } => {
+                write!(f, "the answer wears group {group}'s colour")
+            }
+            Unfair::CrowdedAnswer {
+                group,
+                distance,
+                clearance,
+            } => write!(
+                f,
+                "group {group} is {distance:.4} from the answer, inside the {clearance:.4} clearance"
+            ),
+            Unfair::NoBreak { answer } => write!(f, "answer {answer} has no broken edge"),
+            Unfair::RivalBreak {
+                answer,
+                other,
+                violations,
+            } => write!(
+                f,
+                "cell {other} has {violations} broken edges, as many as answer {answer}"
+            ),
+            Unfair::Unrepairable { answer } => {
+                write!(f, "no tile in place of answer {answer} mends the board")
+            }
+            Unfair::RivalRepair { answer, other } => {
+                write!(f, "a new tile at cell {other} mends the board, as at answer {answer}")
+            }
+            Unfair::NothingToRepair => write!(f, "the board fits together before a turn"),
+        }
+    }
+}
+
+/// Checks a colour round: `colors` is every piece on the board, `palette` the
+/// group colours the ground sweeps through, and `clearance` how far from the
+/// answer every group but its own must stay, measured in `metric`.
+///
+/// The answer's own group is not named. It is the one group allowed inside the
+/// clearance, and the rule is written that way: at most one group may be that
+/// close, and it may not be wearing the answer's exact colour.
+pub fn check_colours(
+    colors: &[Color],
+    answer: usize,
+    palette: &[Color],
+    clearance: f32,
+    metric: Metric,
+) -> Result<(), Unfair> {
+    let Some(answer_color) = colors.get(answer).copied() else {
+        return Err(Unfair::AnswerOutOfRange {
+            answer,
+            cells: colors.len(),
+        });
+    };
+
+    if let Some(other) = colors
+        .iter()
+        .enumerate()
+        .position(|(index, color)| index != answer && colors_match(*color, answer_color))
+    {
+        return Err(Unfair::SharedColour { answer, other });
+    }
+
+    if let Some(group) = palette
+        .iter()
+        .position(|color| colors_match(*color, answer_color))
+    {
+        return Err(Unfair::GroupColour { group });
+    }
+
+    let answer_lab = oklab::from_color(answer_color);
+    let mut near: Vec<(usize, f32)> = palette
+        .iter()
+        .enumerate()
+        .map(|(group, color)| (group, metric.distance(answer_lab, oklab::from_color(*color))))
+        .filter(|(_, distance)| *distance <= clearance)
+        .collect();
+
+    if near.len() > 1 {
+        // The nearest is taken to be its own group; the next is the crowd.
+        near.sort_by(|a, b| a.1.total_cmp(&b.1));
+        let (group, distance) = near[1];
+        return Err(Unfair::CrowdedAnswer {
+            group,
+            distance,
+            clearance,
+        });
+    }
+
+    Ok(())
+}
+
+/// Checks a `Mosaic` round: the broken cell must be broken, no other cell
+/// may have as many bad edges as it does, and putting the right tile in the
+/// broken cell, and only there, must leave a board that agrees everywhere.
+/// See `wfc::corrupt` for why "most bad edges" is the rule, rather than "the
+/// only bad edges".
+///
+/// The counts are what the player goes by; the last part is what settles an
+/// argument about them. A second fault far from the answer, with one bad
+/// edge to the answer's three, passes every count and still leaves two
+/// pieces that do not belong.
+pub fn check_mosaic(mosaic: &Mosaic) -> Result<(), Unfair> {
+    let answer = mosaic.broken;
+    if answer >= mosaic.tiles.len() {
+        return Err(Unfair::AnswerOutOfRange {
+            answer,
+            cells: mosaic.tiles.len(),
+        });
+    }
+
+    let broken = mosaic.violations_at(answer);
+    if broken == 0 {
+        return Err(Unfair::NoBreak { answer });
+    }
+
+    for other in (0..mosaic.tiles.len()).filter(|index| *index != answer) {
+        let violations = mosaic.violations_at(other);
+        if violations >= broken {
+            return Err(Unfair::RivalBreak {
+                answer,
+                other,
+                violations,
+            });
+        }
+    }
+
+    let repairs = mosaic.repairs();
+    if !repairs.contains(&answer) {
+        return Err(Unfair::Unrepairable { answer });
+    }
+    if let Some(other) = repairs.into_iter().find(|index| *index != answer) {
+        return Err(Unfair::RivalRepair { answer, other });
+    }
+
+    Ok(())
+}
+
+/// Checks a `Repair` round: there has to be something to turn. Whether it can
+/// be turned back is not in question, since the board was cut whole and
+/// only turned.
+pub fn check_repair(set: &TileSet, lattice: &Lattice, tiles: &[Tile]) -> Result<(), Unfair> {
+    if wfc::bad_edges(set, lattice, tiles) == 0 {
+        return Err(Unfair::NothingToRepair);
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cvd::ColorVision;
+    use crate::game::puzzle::components::{score_for_level, ColorPuzzle, GameMode, RunSeed};
+    use crate::oklab::Gamut;
+    use crate::tile_sets;
+    use crate::wfc::SolveStats;
+
+    fn grey(level: f32) -> Color {
+        Color::srgb(level, level, level)
+    }
+
+    #[test]
+    fn a_colour_board_with_two_answers_is_caught() {
+        let palette = [grey(0.2), grey(0.5), grey(0.8)];
+        let fair = [grey(0.2), grey(0.52), grey(0.5), grey(0.8)];
+        assert_eq!(check_colours(&fair, 1, &palette, 0.03, Metric::Oklab), Ok(()));
+
+        let twin = [grey(0.2), grey(0.52), grey(0.52), grey(0.8)];
+        assert_eq!(
+            check_colours(&twin, 1, &palette, 0.03, Metric::Oklab),
+            Err(Unfair::SharedColour { answer: 1, other: 2 })
+        );
+
+        let hidden = [grey(0.2), grey(0.5), grey(0.8)];
+        assert_eq!(
+            check_colours(&hidden, 1, &palette, 0.03, Metric::Oklab),
+            Err(Unfair::GroupColour { group: 1 })
+        );
+
+        // Two groups either side of the answer, both inside the clearance.
+        let crowded = [grey(0.48), grey(0.5), grey(0.52)];
+        assert!(matches!(
+            check_colours(&crowded, 1, &[grey(0.48), grey(0.52)], 0.1, Metric::Oklab),
+            Err(Unfair::CrowdedAnswer { .. })
+        ));
+
+        assert!(matches!(
+            check_colours(&fair, 9, &palette, 0.03, Metric::Oklab),
+            Err(Unfair::AnswerOutOfRange { .. })
+        ));
+    }
+
+    #[test]
+    fn a_mosaic_with_an_ambiguous_break_is_caught() {
+        let set = tile_sets::table().get(0);
+        let empty = set.tile("empty", 0).unwrap();
+        // A straight lying along a row of three hexes: arms west and east,
+        // which run off the board at one end and into a blank at the other.
+        let straight = set.tile("straight", 1).unwrap();
+
+        let mut mosaic = Mosaic {
+            set: set.clone(),
+            lattice: Lattice::new(&[(0, 0), (1, 0), (2, 0)]),
+            tiles: vec![empty, empty, empty],
+            broken: 1,
+            violations: 0,
+            stats: SolveStats::default(),
+        };
+        assert_eq!(check_mosaic(&mosaic), Err(Unfair::NoBreak { answer: 1 }));
+
+        // Arms into both neighbours: two bad edges at the answer, one at each
+        // neighbour. Fair.
+        mosaic.tiles[1] = straight;
+        assert_eq!(check_mosaic(&mosaic), Ok(()));
+
+        // A pair of straights at one end: the first runs off the board, the
+        // second into the blank. One bad edge each, and no way to say which
+        // of the two is the odd one out.
+        mosaic.tiles = vec![straight, straight, empty];
+        mosaic.broken = 0;
+        assert!(matches!(
+            check_mosaic(&mosaic),
+            Err(Unfair::RivalBreak { other: 1, .. })
+        ));
+    }
+
+    #[test]
+    fn a_mosaic_broken_in_two_places_is_caught() {
+        let set = tile_sets::table().get(0);
+        let empty = set.tile("empty", 0).unwrap();
+        let straight = set.tile("straight", 1).unwrap();
+
+        // The answer reaches into both blanks beside it, two bad edges. At
+        // the far end of the row a pair of straights runs off the board on
+        // one side and into a blank on the other: one bad edge each, fewer
+        // than the answer's, so every count says cell 1.
+        let cells: Vec<(usize, usize)> = (0..7).map(|column| (column, 0)).collect();
+        let mut mosaic = Mosaic {
+            set: set.clone(),
+            lattice: Lattice::new(&cells),
+            tiles: vec![empty, straight, empty, empty, empty, straight, straight],
+            broken: 1,
+            violations: 2,
+            stats: SolveStats::default(),
+        };
+        assert_eq!(check_mosaic(&mosaic), Err(Unfair::Unrepairable { answer: 1 }));
+
+        // Mend the far end and the answer is the only piece out of place.
+        mosaic.tiles[5] = empty;
+        mosaic.tiles[6] = empty;
+        assert_eq!(check_mosaic(&mosaic), Ok(()));
+    }
+
+    /// What the game deals passes, at every level and in both kinds of round.
+    #[test]
+    fn dealt_boards_are_fair() {
+        for game_mode in [GameMode::Infinite, GameMode::Mosaic, GameMode::Repair] {
+            let mut puzzle = ColorPuzzle::default();
+            puzzle.setup(&game_mode);
+            puzzle.set_window_size(390.0, 844.0);
+            let mut seed = RunSeed::new(7);
+
+            for level in [1, 3, 8, 15, 30] {
+                puzzle.restore_score(score_for_level(level));
+                for _ in 0..40 {
+                    puzzle.generate_colors(&mut seed.rng());
+                    seed.advance();
+                    assert_eq!(puzzle.check_fairness(), Ok(()), "{game_mode:?} level {level}");
+                }
+            }
+        }
+    }
+
+    /// And in every metric, each with its own clearance.
+    #[test]
+    fn dealt_boards_are_fair_in_every_metric() {
+        for metric in Metric::iter() {
+            let mut puzzle = ColorPuzzle::default();
+            puzzle.metric = metric;
+            puzzle.setup(&GameMode::Infinite);
+            puzzle.set_window_size(390.0, 844.0);
+            let mut seed = RunSeed::new(23);
+
+            for level in [1, 8, 30] {
+                puzzle.restore_score(score_for_level(level));
+                for _ in 0..40 {
+                    puzzle.generate_colors(&mut seed.rng());
+                    seed.advance();
+                    assert_eq!(puzzle.check_fairness(), Ok(()), "{metric:?} level {level}");
+                }
+            }
+        }
+    }
+
+    /// And in every gamut, the wider one's colours read back as displayed.
+    #[test]
+    fn dealt_boards_are_fair_in_every_gamut() {
+        for gamut in Gamut::iter() {
+            let mut puzzle = ColorPuzzle::default();
+            puzzle.gamut = gamut;
+            puzzle.setup(&GameMode::Infinite);
+            puzzle.set_window_size(390.0, 844.0);
+            let mut seed = RunSeed::new(29);
+
+            for level in [1, 8, 30] {
+                puzzle.restore_score(score_for_level(level));
+                for _ in 0..40 {
+                    puzzle.generate_colors(&mut seed.rng());
+                    seed.advance();
+                    assert_eq!(puzzle.check_fairness(), Ok(()), "{gamut:?} level {level}");
+                }
+            }
+        }
+    }
+
+    /// And with each deficiency, judged as the player would see the board.
+    #[test]
+    fn dealt_boards_are_fair_to_every_colour_vision() {
+        for vision in ColorVision::iter() {
+            let mut puzzle = ColorPuzzle::default();
+            puzzle.setup(&GameMode::Infinite);
+            puzzle.set_window_size(390.0, 844.0);
+            puzzle.color_vision = vision;
+            let mut seed = RunSeed::new(19);
+
+            for level in [1, 8, 30] {
+                puzzle.restore_score(score_for_level(level));
+                for _ in 0..40 {
+                    puzzle.generate_colors(&mut seed.rng());
+                    seed.advance();
+                    assert_eq!(puzzle.check_fairness(), Ok(()), "{vision:?} level {level}");
+                }
+            }
+        }
+    }
+}
@@ -256,6 +256,8 @@ pub enum BannerKind {
     LevelUp,
     PowerUp,
     Achievement,
+    /// A menu's answer to a button: a code copied, a code refused.
+    Notice,
 }
 
 /// A short, loud, centered announcement: "NIVEL 3", "+VIDA".
@@ -299,6 +301,16 @@ impl BannerEvent {
             kind: BannerKind::Achievement,
         }
     }
+
+    /// A menu's answer to a button. Small, and in the color of the news.
+    pub fn notice(text: impl Into<String>, color: Color) -> Self {
+        Self {
+            text: text.into(),
+            color,
+            size: theme::TEXT_LG,
+            kind: BannerKind::Notice,
+        }
+    }
 }
 
 #[derive(Component)]
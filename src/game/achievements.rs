@@ -14,7 +14,7 @@ use bevy::prelude::*;
 use crate::feedback::BannerEvent;
 use crate::game::puzzle::components::{ColorPuzzle, GameHistory, GameMode};
 use crate::game::score::resources::BestScores;
-use crate::storage;
+use crate::storage::{self, StorageBackend};
 
 const STORAGE_KEY: &str = "color_puzzle.achievements";
 const MODES_KEY: &str = "color_puzzle.modes_played";
@@ -104,7 +104,7 @@ impl Achievement {
             Achievement::Level25 => "Chegue ao nivel 25.",
             Achievement::Score100 => "100 pontos numa partida.",
             Achievement::Score500 => "500 pontos numa partida.",
-            Achievement::AllModes => "Jogue os cinco modos.",
+            Achievement::AllModes => "Jogue todos os modos.",
             Achievement::RecordEveryMode => "Pontue em todos os modos.",
         }
     }
@@ -129,7 +129,7 @@ impl Achievement {
 /// Which goals are reached, and which modes have been played at all.
 ///
 /// The modes are tracked here rather than derived from `BestScores`, because a
-/// mode can be played without scoring in it, and "jogue os cinco modos" should
+/// mode can be played without scoring in it, and "jogue todos os modos" should
 /// mean playing them.
 #[derive(Resource, Debug, Default)]
 pub struct Achievements {
@@ -171,7 +171,7 @@ impl Achievements {
         self.persist();
     }
 
-    fn modes_played_count(&self) -> usize {
+    pub fn modes_played_count(&self) -> usize {
         self.modes_played.len()
     }
 
@@ -197,9 +197,14 @@ impl Achievements {
     }
 
     pub fn load() -> Self {
+        Self::load_from(storage::backend())
+    }
+
+    pub fn load_from(store: &dyn StorageBackend) -> Self {
         // Unknown keys are skipped rather than fatal, so a build that drops a
         // goal does not wipe the rest of the record.
-        let unlocked = storage::load(STORAGE_KEY)
+        let unlocked = store
+            .load(STORAGE_KEY)
             .map(|raw| {
                 raw.split(',')
                     .filter_map(|key| {
@@ -209,7 +214,8 @@ impl Achievements {
             })
             .unwrap_or_default();
 
-        let modes_played = storage::load(MODES_KEY)
+        let modes_played = store
+            .load(MODES_KEY)
             .map(|raw| {
                 raw.split(',')
                     .filter_map(|key| GameMode::iter().find(|m| m.storage_key() == key.trim()))
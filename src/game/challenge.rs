@@ -0,0 +1,120 @@
+//! Playing against a pasted share code.
+//!
+//! A challenge is someone else's run, opened from the main menu. It can be
+//! watched, or played: the challenger gets the same seed and the same window
+//! the sender's boards were cut against, so every round is the board the
+//! sender had — until the two runs part ways, as the round only moves on with
+//! a pick and a miss deals the next board just like a hit does.
+//!
+//! A challenge run is kept out of everything a normal run feeds: the stored run
+//! a card offers to continue, the day's one scored attempt and the personal
+//! bests. The boards were not dealt fresh, so none of those would mean what
+//! they say.
+
+use bevy::prelude::*;
+
+use crate::game::puzzle::components::ColorPuzzle;
+use crate::share_code::ShareCode;
+use crate::AppState;
+
+#[derive(Resource, Debug, Default)]
+pub struct Challenge {
+    code: Option<ShareCode>,
+    /// The score the code was verified at.
+    score: usize,
+    playing: bool,
+}
+
+impl Challenge {
+    /// Holds a code that has passed `verify`, at the score it reached.
+    pub fn open(&mut self, code: ShareCode, score: usize) {
+        *self = Self {
+            code: Some(code),
+            score,
+            playing: false,
+        };
+    }
+
+    pub fn code(&self) -> Option<&ShareCode> {
+        self.code.as_ref()
+    }
+
+    pub fn score(&self) -> usize {
+        self.score
+    }
+
+    /// Marks the run about to start as one against the code.
+    pub fn begin(&mut self) {
+        self.playing = self.code.is_some();
+    }
+
+    /// Marks the run about to start as the player's own.
+    pub fn leave(&mut self) {
+        self.playing = false;
+    }
+
+    pub fn is_playing(&self) -> bool {
+        self.playing
+    }
+
+    /// The score to beat, while a run against it is on.
+    pub fn target(&self) -> Option<usize> {
+        self.playing.then_some(self.score)
+    }
+
+    /// The seed to deal from, while a run against the code is on.
+    pub fn seed(&self) -> Option<u64> {
+        let code = self.code.as_ref().filter(|_| self.playing)?;
+        Some(code.log.seed)
+    }
+
+    /// The window to cut boards against instead of the real one, while a run
+    /// against the code is on.
+    pub fn board_size(&self) -> Option<Vec2> {
+        self.code.as_ref().filter(|_| self.playing)?.board_size()
+    }
+}
+
+/// Zooms the camera out until the board fits the window.
+///
+/// A board is cut against a window size, and a challenge or a code's replay
+/// brings along a size that is not this window's. Scaling the view, rather
+/// than cutting the board again, keeps every piece where the seed put it, and
+/// the pick is still judged in world units because `viewport_to_world_2d`
+/// goes through the same projection. Never zooms in: a board smaller than the
+/// window is shown at its own size. Off the board screens the view goes back
+/// to one to one.
+pub fn fit_board_to_window(
+    state: Res<State<AppState>>,
+    puzzle: Res<ColorPuzzle>,
+    window_query: Query<&Window>,
+    mut projection_query: Query<&mut Projection, With<Camera2d>>,
+) {
+    let Ok(window) = window_query.single() else {
+        return;
+    };
+    let Ok(mut projection) = projection_query.single_mut() else {
+        return;
+    };
+
+    let on_board = matches!(
+        state.get(),
+        AppState::Game | AppState::Paused | AppState::Replay
+    );
+    let scale = if on_board && window.width() > 0.0 && window.height() > 0.0 {
+        (puzzle.width / window.width())
+            .max(puzzle.height / window.height())
+            .max(1.0)
+    } else {
+        1.0
+    };
+
+    if let Projection::Orthographic(orthographic) = projection.as_ref() {
+        if orthographic.scale == scale {
+            return;
+        }
+    }
+    if let Projection::Orthographic(orthographic) = projection.as_mut() {
+        orthographic.scale = scale;
+    }
+}
@@ -0,0 +1,347 @@
+//! A run set up by hand.
+//!
+//! Every built-in mode is a fixed set of rules: a clock or lives, what a miss
+//! costs, whether the board hides, and a board that grows with the level. The
+//! custom game screen lets the player pick each of those for themselves, and
+//! holds the board at one size and one distance for the whole run. What was
+//! picked is a [`RunRules`], so the run is dealt and played by the same code
+//! as every other; nothing here knows how a board is made.
+//!
+//! The dials are kept between sessions, so a player who found the game they
+//! like does not dial it in again every time. A custom run keeps a history
+//! like any other, but no best score and no goals: see `record_run_outcome`.
+
+use bevy::prelude::*;
+
+use crate::board;
+use crate::game::puzzle::components::{BoardDials, GameMode, RunRules};
+use crate::oklab::Metric;
+use crate::storage::{self, StorageBackend};
+
+const STORAGE_KEY: &str = "color_puzzle.custom";
+
+/// The level the dials start from before the player touches them: a board
+/// with something to it, but not yet a hard one.
+const STARTING_LEVEL: usize = 5;
+
+const PALETTE_SIZES: (usize, usize) = (2, 8);
+const EMPTY_SHARES: (f32, f32) = (0.0, 0.6);
+const EMPTY_SHARE_STEP: f32 = 0.05;
+/// In Oklab. The floor is the adaptive staircase's, the hardest distance the
+/// game ever deals on its own.
+const COLOR_DELTAS: (f32, f32) = (0.005, 0.050);
+const COLOR_DELTA_STEP: f32 = 0.005;
+const TIMER_SECONDS: f32 = 180.0;
+const TIMER_STEP: f32 = 15.0;
+const MAX_LIVES: usize = 9;
+const MAX_MISS_PENALTY: f32 = 10.0;
+
+/// One setting on the custom game screen, a row of its own there.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Dial {
+    Columns,
+    Palette,
+    EmptyShare,
+    Delta,
+    Timer,
+    Lives,
+    Penalty,
+    Hide,
+}
+
+impl Dial {
+    pub fn iter() -> impl Iterator<Item = Dial> {
+        [
+            Dial::Columns,
+            Dial::Palette,
+            Dial::EmptyShare,
+            Dial::Delta,
+            Dial::Timer,
+            Dial::Lives,
+            Dial::Penalty,
+            Dial::Hide,
+        ]
+        .into_iter()
+    }
+
+    /// The row's text. ASCII only — the display font has no accents.
+    ///
+    /// The distance is shown in the run's metric, the one the player has
+    /// seen in the pause screen's history, though it is kept in Oklab.
+    pub fn label(&self, rules: &RunRules, metric: Metric) -> String {
+        let board = rules.board.unwrap_or(BoardDials::for_level(STARTING_LEVEL));
+        match self {
+            Dial::Columns => format!("COLUNAS: {}", board.columns),
+            Dial::Palette => format!("CORES: {}", board.palette_size),
+            Dial::EmptyShare => format!("VAZIOS: {:.0}%", board.empty_share * 100.0),
+            Dial::Delta => format!(
+                "DISTANCIA: {:.*}",
+                metric.decimals(),
+                board.color_delta * metric.per_oklab_unit()
+            ),
+            Dial::Timer if !rules.is_timed() => "RELOGIO: NENHUM".to_string(),
+            Dial::Timer => format!("RELOGIO: {:.0}S", rules.timer_seconds),
+            Dial::Lives if rules.lives == 0 => "VIDAS: NENHUMA".to_string(),
+            Dial::Lives => format!("VIDAS: {}", rules.lives),
+            // A penalty with no clock to take it from does nothing, and
+            // saying a number would suggest it did.
+            Dial::Penalty if !rules.is_timed() => "MULTA POR ERRO: --".to_string(),
+            Dial::Penalty => format!("MULTA POR ERRO: {:.0}S", rules.miss_penalty_seconds),
+            Dial::Hide if rules.hides_colors => "ESCONDER CORES: SIM".to_string(),
+            Dial::Hide => "ESCONDER CORES: NAO".to_string(),
+        }
+    }
+
+    /// Moves the dial one step up or down, stopping at either end.
+    ///
+    /// The clock and the lives never both reach zero: a run with neither
+    /// could only end by the player giving up, which is the bug
+    /// `every_mode_has_one_way_to_run_out` was written for. The one being
+    /// turned down stops short instead.
+    pub fn step(&self, rules: &mut RunRules, up: bool) {
+        let board = rules
+            .board
+            .get_or_insert(BoardDials::for_level(STARTING_LEVEL));
+        match self {
+            Dial::Columns => {
+                board.columns =
+                    step_count(board.columns, up, board::MIN_COLUMNS, board::MAX_COLUMNS);
+            }
+            Dial::Palette => {
+                board.palette_size =
+                    step_count(board.palette_size, up, PALETTE_SIZES.0, PALETTE_SIZES.1);
+            }
+            Dial::EmptyShare => {
+                board.empty_share =
+                    step_float(board.empty_share, up, EMPTY_SHARE_STEP, EMPTY_SHARES);
+            }
+            Dial::Delta => {
+                board.color_delta =
+                    step_float(board.color_delta, up, COLOR_DELTA_STEP, COLOR_DELTAS);
+            }
+            Dial::Timer => {
+                let floor = if rules.lives == 0 { TIMER_STEP } else { 0.0 };
+                rules.timer_seconds =
+                    step_float(rules.timer_seconds, up, TIMER_STEP, (floor, TIMER_SECONDS));
+            }
+            Dial::Lives => {
+                let floor = usize::from(!rules.is_timed());
+                rules.lives = step_count(rules.lives, up, floor, MAX_LIVES);
+            }
+            Dial::Penalty => {
+                rules.miss_penalty_seconds =
+                    step_float(rules.miss_penalty_seconds, up, 1.0, (0.0, MAX_MISS_PENALTY));
+            }
+            Dial::Hide => set_hidden(rules, !rules.hides_colors),
+        }
+    }
+}
+
+/// Hides the board or shows it, and takes `Memory`'s short sweep into a round
+/// along with it: see `RunRules::for_mode`.
+fn set_hidden(rules: &mut RunRules, hides_colors: bool) {
+    let like = if hides_colors {
+        GameMode::Memory
+    } else {
+        GameMode::Infinite
+    };
+    rules.hides_colors = hides_colors;
+    rules.transition_seconds = RunRules::for_mode(like).transition_seconds;
+}
+
+fn step_count(value: usize, up: bool, min: usize, max: usize) -> usize {
+    if up {
+        (value + 1).min(max)
+    } else {
+        value.saturating_sub(1).max(min)
+    }
+}
+
+/// Steps in whole multiples of `step`, so a value read back from storage as
+/// `0.35000002` does not drift a little further on every press.
+fn step_float(value: f32, up: bool, step: f32, (min, max): (f32, f32)) -> f32 {
+    let steps = (value / step).round() + if up { 1.0 } else { -1.0 };
+    (steps * step).clamp(min, max)
+}
+
+#[derive(Resource, Debug, Clone, PartialEq)]
+pub struct CustomGame {
+    pub rules: RunRules,
+}
+
+impl Default for CustomGame {
+    fn default() -> Self {
+        Self {
+            rules: RunRules::custom(BoardDials::for_level(STARTING_LEVEL)),
+        }
+    }
+}
+
+impl CustomGame {
+    /// Moves `dial` one step, and stores the result.
+    pub fn turn(&mut self, dial: Dial, up: bool) {
+        dial.step(&mut self.rules, up);
+        storage::save(STORAGE_KEY, &self.serialize());
+    }
+
+    pub fn load() -> Self {
+        Self::load_from(storage::backend())
+    }
+
+    pub fn load_from(store: &dyn StorageBackend) -> Self {
+        store
+            .load(STORAGE_KEY)
+            .map(|raw| Self::deserialize(&raw))
+            .unwrap_or_default()
+    }
+
+    /// `key=value` pairs separated by `;`, in the same spirit as
+    /// `BestScores`. The distance is written in Oklab.
+    fn serialize(&self) -> String {
+        let rules = &self.rules;
+        let board = rules.board.unwrap_or(BoardDials::for_level(STARTING_LEVEL));
+        format!(
+            "columns={};palette={};empty={};delta={};timer={};lives={};penalty={};hide={}",
+            board.columns,
+            board.palette_size,
+            board.empty_share,
+            board.color_delta,
+            rules.timer_seconds,
+            rules.lives,
+            rules.miss_penalty_seconds,
+            u8::from(rules.hides_colors),
+        )
+    }
+
+    /// Unknown keys and unreadable values are skipped, leaving the default
+    /// for that dial, and whatever was read is brought back inside the
+    /// dials' ranges: a stored value is not trusted to be one the screen
+    /// could have set.
+    fn deserialize(raw: &str) -> Self {
+        let mut custom = Self::default();
+        let rules = &mut custom.rules;
+        let mut board = rules.board.unwrap_or(BoardDials::for_level(STARTING_LEVEL));
+
+        for entry in raw.split(';') {
+            let Some((key, value)) = entry.split_once('=') else {
+                continue;
+            };
+            let value = value.trim();
+            let count = || value.parse::<usize>().ok();
+            let number = || {
+                value
+                    .parse::<f32>()
+                    .ok()
+                    .filter(|number| number.is_finite())
+            };
+            match key.trim() {
+                "columns" => {
+                    if let Some(columns) = count() {
+                        board.columns = columns.clamp(board::MIN_COLUMNS, board::MAX_COLUMNS);
+                    }
+                }
+                "palette" => {
+                    if let Some(size) = count() {
+                        board.palette_size = size.clamp(PALETTE_SIZES.0, PALETTE_SIZES.1);
+                    }
+                }
+                "empty" => {
+                    if let Some(share) = number() {
+                        board.empty_share = share.clamp(EMPTY_SHARES.0, EMPTY_SHARES.1);
+                    }
+                }
+                "delta" => {
+                    if let Some(delta) = number() {
+                        board.color_delta = delta.clamp(COLOR_DELTAS.0, COLOR_DELTAS.1);
+                    }
+                }
+                "timer" => {
+                    if let Some(seconds) = number() {
+                        rules.timer_seconds = seconds.clamp(0.0, TIMER_SECONDS);
+                    }
+                }
+                "lives" => {
+                    if let Some(lives) = count() {
+                        rules.lives = lives.min(MAX_LIVES);
+                    }
+                }
+                "penalty" => {
+                    if let Some(seconds) = number() {
+                        rules.miss_penalty_seconds = seconds.clamp(0.0, MAX_MISS_PENALTY);
+                    }
+                }
+                "hide" => {
+                    if let Some(hide) = count() {
+                        set_hidden(rules, hide != 0);
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        // The one rule across dials. See `Dial::step`.
+        if rules.lives == 0 && !rules.is_timed() {
+            rules.lives = 1;
+        }
+        rules.board = Some(board);
+        custom
+    }
+}
+
+/// Loads the dials at startup, populating the already-initialised resource so
+/// no system can observe a frame where it does not exist.
+pub fn load_custom_game(mut custom: ResMut<CustomGame>) {
+    *custom = CustomGame::load();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dials_survive_a_round_trip_and_stay_in_range() {
+        let mut custom = CustomGame::default();
+        for _ in 0..30 {
+            Dial::Columns.step(&mut custom.rules, true);
+            Dial::EmptyShare.step(&mut custom.rules, false);
+        }
+        Dial::Delta.step(&mut custom.rules, true);
+        Dial::Timer.step(&mut custom.rules, true);
+        Dial::Hide.step(&mut custom.rules, true);
+
+        let board = custom.rules.board.unwrap();
+        assert_eq!(board.columns, board::MAX_COLUMNS);
+        assert_eq!(board.empty_share, 0.0);
+        assert_eq!(custom.rules.timer_seconds, TIMER_STEP);
+
+        let loaded = CustomGame::deserialize(&custom.serialize());
+        assert_eq!(loaded, custom);
+
+        let damaged = CustomGame::deserialize("columns=99;delta=-1;lives=x;hide=1;oops");
+        let board = damaged.rules.board.unwrap();
+        assert_eq!(board.columns, board::MAX_COLUMNS);
+        assert_eq!(board.color_delta, COLOR_DELTAS.0);
+        assert!(damaged.rules.hides_colors);
+    }
+
+    /// Turning both down leaves one of them standing, in either order.
+    #[test]
+    fn a_custom_run_always_has_a_way_to_end() {
+        for lives_first in [true, false] {
+            let mut rules = CustomGame::default().rules;
+            for _ in 0..20 {
+                if lives_first {
+                    Dial::Lives.step(&mut rules, false);
+                    Dial::Timer.step(&mut rules, false);
+                } else {
+                    Dial::Timer.step(&mut rules, false);
+                    Dial::Lives.step(&mut rules, false);
+                }
+            }
+            assert!(rules.lives > 0 || rules.is_timed());
+        }
+
+        let loaded = CustomGame::deserialize("timer=0;lives=0");
+        assert!(loaded.rules.lives > 0);
+    }
+}
@@ -1,13 +1,22 @@
 pub mod achievements;
+pub mod challenge;
+pub mod custom;
+pub mod practice;
 pub mod puzzle;
+pub mod replay;
 pub mod score;
 pub mod ui;
 
 use puzzle::PuzzlePlugin;
+use replay::ReplayPlugin;
 use score::ScorePlugin;
 use ui::GameUIPlugin;
 
 use achievements::{check_achievements, load_achievements, note_mode_played, Achievements};
+use challenge::{fit_board_to_window, Challenge};
+use custom::{load_custom_game, CustomGame};
+use practice::{load_practice, record_practice_pick, Practice};
+use puzzle::components::ColorPuzzle;
 use crate::AppState;
 
 use bevy::prelude::*;
@@ -17,13 +26,32 @@ pub struct GamePlugin;
 impl Plugin for GamePlugin {
     fn build(&self, app: &mut App) {
         app
-            .add_plugins((GameUIPlugin, ScorePlugin, PuzzlePlugin))
+            .add_plugins((GameUIPlugin, ScorePlugin, PuzzlePlugin, ReplayPlugin))
             .init_resource::<Achievements>()
-            .add_systems(Startup, load_achievements)
-            .add_systems(OnEnter(AppState::Game), note_mode_played)
+            .init_resource::<Challenge>()
+            .init_resource::<Practice>()
+            .init_resource::<CustomGame>()
+            .add_systems(Startup, (load_achievements, load_practice, load_custom_game))
+            .add_systems(
+                OnEnter(AppState::Game),
+                note_mode_played.run_if(not(is_off_the_books)),
+            )
+            .add_systems(
+                Update,
+                check_achievements.run_if(in_state(AppState::Game).and_then(not(is_off_the_books))),
+            )
             .add_systems(
                 Update,
-                check_achievements.run_if(in_state(AppState::Game)),
-            );
+                record_practice_pick.run_if(in_state(AppState::Game)),
+            )
+            // Ungated: leaving the board screens is what puts the view back.
+            .add_systems(Update, fit_board_to_window);
     }
 }
+
+/// Goals are for runs that climbed on the game's own rules. Not a practice
+/// run, which was put at its level (see `practice`), and not a custom one,
+/// whose rules could be dialled down until every goal was a formality.
+fn is_off_the_books(practice: Res<Practice>, puzzle: Res<ColorPuzzle>) -> bool {
+    practice.is_playing() || puzzle.game_mode.is_custom()
+}
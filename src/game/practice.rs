@@ -0,0 +1,244 @@
+//! Rehearsing one level.
+//!
+//! Every run starts at nothing and climbs, so the only way to see level
+//! twenty again is to play the nineteen before it. Practice skips the climb:
+//! the player picks a mode and a level from the practice screen, and plays
+//! rounds at that level for as long as they like. A hit does not move the
+//! score and a miss costs nothing, so the level stays where it was put. See
+//! `ColorPuzzle::pin_level`.
+//!
+//! What it keeps instead of a score is how often each level was answered
+//! right, per mode. That is the number worth having when rehearsing: whether
+//! level twenty is still a coin flip, not how many of them were played.
+//!
+//! Like a challenge, a practice run is kept out of everything a normal run
+//! feeds: the stored run, the personal bests and the goals. A level reached
+//! by choosing it was not reached, and a streak of rounds that cost nothing
+//! to miss is not the streak the goals mean.
+
+use std::collections::BTreeMap;
+
+use bevy::prelude::*;
+
+use crate::game::puzzle::components::{GameMode, LastInteractionEvent};
+use crate::storage::{self, StorageBackend};
+
+const STORAGE_KEY: &str = "color_puzzle.practice";
+
+/// Levels the practice screen offers. There is no last level, but past here
+/// the curve has flattened and one more row of buttons would show nothing new.
+pub const PRACTICE_LEVELS: usize = 30;
+
+/// Hits out of picks, at one level of one mode.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Tally {
+    pub hits: usize,
+    pub picks: usize,
+}
+
+impl Tally {
+    /// Whole percent answered right, or `None` before the first pick.
+    pub fn percent(&self) -> Option<usize> {
+        (self.picks > 0).then(|| (self.hits * 100 + self.picks / 2) / self.picks)
+    }
+}
+
+#[derive(Resource, Debug, Clone, PartialEq)]
+pub struct Practice {
+    /// The mode the practice screen is showing, and the one a run is in.
+    mode: GameMode,
+    level: usize,
+    playing: bool,
+    /// Keyed by the mode's storage key, since that is what is written down
+    /// and `GameMode` has no order of its own.
+    tallies: BTreeMap<(&'static str, usize), Tally>,
+}
+
+impl Default for Practice {
+    fn default() -> Self {
+        Self {
+            mode: GameMode::Infinite,
+            level: 1,
+            playing: false,
+            tallies: BTreeMap::new(),
+        }
+    }
+}
+
+impl Practice {
+    /// The modes a level can be practised in. Not `Daily`: its boards are the
+    /// day's, and practising them would be playing today's attempt early. Not
+    /// `Repair` either, whose every board is finished sooner or later: there
+    /// is no accuracy to keep, and no miss to learn from.
+    pub fn modes() -> impl Iterator<Item = GameMode> {
+        GameMode::iter().filter(|mode| !mode.is_daily() && !mode.is_repair())
+    }
+
+    pub fn mode(&self) -> GameMode {
+        self.mode
+    }
+
+    /// Moves the practice screen on to the next mode.
+    pub fn cycle_mode(&mut self) {
+        let modes: Vec<GameMode> = Self::modes().collect();
+        let at = modes
+            .iter()
+            .position(|mode| *mode == self.mode)
+            .unwrap_or(0);
+        self.mode = modes[(at + 1) % modes.len()];
+    }
+
+    /// Marks the run about to start as practice at `level`.
+    pub fn begin(&mut self, level: usize) {
+        self.level = level.max(1);
+        self.playing = true;
+    }
+
+    /// Marks the run about to start as a scored one.
+    pub fn leave(&mut self) {
+        self.playing = false;
+    }
+
+    pub fn is_playing(&self) -> bool {
+        self.playing
+    }
+
+    pub fn tally(&self, mode: GameMode, level: usize) -> Tally {
+        self.tallies
+            .get(&(mode.storage_key(), level))
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// The tally for the level being practised.
+    pub fn current(&self) -> Tally {
+        self.tally(self.mode, self.level)
+    }
+
+    /// Counts a pick at the level being practised, and stores it.
+    pub fn record(&mut self, scored: bool) {
+        self.count(scored);
+        storage::save(STORAGE_KEY, &self.serialize());
+    }
+
+    fn count(&mut self, scored: bool) {
+        let tally = self
+            .tallies
+            .entry((self.mode.storage_key(), self.level))
+            .or_default();
+        tally.picks += 1;
+        tally.hits += usize::from(scored);
+    }
+
+    /// Picks made across every level and mode, for the import preview.
+    pub fn picks(&self) -> usize {
+        self.tallies.values().map(|tally| tally.picks).sum()
+    }
+
+    pub fn load() -> Self {
+        Self::load_from(storage::backend())
+    }
+
+    pub fn load_from(store: &dyn StorageBackend) -> Self {
+        store
+            .load(STORAGE_KEY)
+            .map(|raw| Self::deserialize(&raw))
+            .unwrap_or_default()
+    }
+
+    /// `mode:level=hits/picks` entries separated by `;`, in the same spirit
+    /// as `BestScores`. Levels never practised are not written.
+    fn serialize(&self) -> String {
+        self.tallies
+            .iter()
+            .map(|((mode, level), tally)| format!("{mode}:{level}={}/{}", tally.hits, tally.picks))
+            .collect::<Vec<_>>()
+            .join(";")
+    }
+
+    fn deserialize(raw: &str) -> Self {
+        let mut practice = Self::default();
+
+        for entry in raw.split(';') {
+            let Some((key, tally)) = entry.split_once('=') else {
+                continue;
+            };
+            let Some((mode, level)) = key.split_once(':') else {
+                continue;
+            };
+            let Some((hits, picks)) = tally.split_once('/') else {
+                continue;
+            };
+            let (Ok(level), Ok(hits), Ok(picks)) = (
+                level.trim().parse::<usize>(),
+                hits.trim().parse::<usize>(),
+                picks.trim().parse::<usize>(),
+            ) else {
+                continue;
+            };
+            // Unknown modes are skipped, as they are in `BestScores`, and so
+            // is a tally claiming more hits than picks.
+            let Some(mode) = Self::modes().find(|m| m.storage_key() == mode.trim()) else {
+                continue;
+            };
+            if level == 0 || hits > picks {
+                continue;
+            }
+
+            practice
+                .tallies
+                .insert((mode.storage_key(), level), Tally { hits, picks });
+        }
+
+        practice
+    }
+}
+
+/// Loads the tallies at startup, populating the already-initialised resource
+/// so no system can observe a frame where it does not exist.
+pub fn load_practice(mut practice: ResMut<Practice>) {
+    *practice = Practice::load();
+}
+
+/// Counts every pick of a practice run against its level.
+///
+/// Read off the same event the pause screen's history is built from, so a
+/// pick is counted exactly when it is written down there.
+pub fn record_practice_pick(
+    mut last_interaction_events: MessageReader<LastInteractionEvent>,
+    mut practice: ResMut<Practice>,
+) {
+    for event in last_interaction_events.read() {
+        if practice.is_playing() {
+            practice.record(event.scored());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tallies_survive_a_round_trip_and_a_damaged_entry() {
+        let mut practice = Practice::default();
+        practice.begin(12);
+        for scored in [true, true, false, true] {
+            practice.count(scored);
+        }
+        practice.cycle_mode();
+        practice.begin(3);
+        practice.count(false);
+
+        assert_eq!(practice.tally(GameMode::Infinite, 12).percent(), Some(75));
+        assert_eq!(practice.current(), Tally { hits: 0, picks: 1 });
+        assert_eq!(practice.tally(GameMode::Infinite, 3).percent(), None);
+
+        let raw = practice.serialize();
+        let loaded = Practice::deserialize(&raw);
+        assert_eq!(loaded.tallies, practice.tallies);
+
+        let damaged = format!("daily:2=1/1;infinite:4=5/2;oops;{raw}");
+        assert_eq!(Practice::deserialize(&damaged).tallies, practice.tallies);
+    }
+}
@@ -3,9 +3,14 @@ use std::time::Duration;
 use rand::prelude::*;
 
 use crate::board::{self, Piece};
+use crate::cvd::ColorVision;
+use crate::fairness::{self, Unfair};
+use crate::modes;
 use crate::mosaic_pattern;
-use crate::oklab::{self, Oklab};
+use crate::oklab::{self, Gamut, Metric, Oklab};
+use crate::staircase::Staircase;
 use crate::theme;
+use crate::tile_sets;
 use crate::wfc::{self, Tile};
 
 #[derive(Component)]
@@ -58,6 +63,16 @@ pub struct NewGameEvent {
 #[derive(Message)]
 pub struct StartLevelEvent;
 
+/// A `Repair` piece was turned, and is now `tile`.
+///
+/// The piece is redrawn where it stands rather than the board dealt again,
+/// which would play the round's sweep in for every tap.
+#[derive(Message)]
+pub struct PieceTurnedEvent {
+    pub index: usize,
+    pub tile: Tile,
+}
+
 /// Asks the puzzle systems to spend a power-up.
 ///
 /// An event rather than a direct call so the HUD never touches the board: the
@@ -77,6 +92,21 @@ pub enum GameMode {
     Memory,
     /// A tiled pattern with one piece that does not fit its neighbours.
     Mosaic,
+    /// The same boards for everyone on the same day, and one scored attempt
+    /// at them.
+    Daily,
+    /// Untimed with lives, like `Infinite`, but the colour distance follows
+    /// the player's picks rather than the level. See `staircase`.
+    Adaptive,
+    /// A `Mosaic` tiling with pieces turned out of true, turned back a tap at
+    /// a time until every edge agrees. Against a clock that a finished board
+    /// winds back and every turn past the board's par runs down.
+    Repair,
+    /// Dialled in by hand on the custom game screen: every rule of the run is
+    /// the player's, and lives in its [`RunRules`] rather than here. Not in
+    /// `iter`, which lists the modes that have a card, a best score and a
+    /// share code of their own; a custom run has none of the three.
+    Custom,
 }
 
 impl GameMode {
@@ -87,45 +117,28 @@ impl GameMode {
             GameMode::TimeTrial,
             GameMode::Memory,
             GameMode::Mosaic,
+            GameMode::Daily,
+            GameMode::Adaptive,
+            GameMode::Repair,
         ]
         .iter()
         .copied()
     }
 
+    /// The mode's name on the menu and in the history. See `modes`.
     pub fn as_str(&self) -> &'static str {
-        match self {
-            GameMode::Infinite => "Infinito",
-            GameMode::AgainstTheClock => "Contra o Tempo",
-            GameMode::TimeTrial => "Soma de Tempo",
-            GameMode::Memory => "Memoria",
-            GameMode::Mosaic => "Mosaico",
-        }
+        &modes::definition(*self).name
     }
 
     /// One line telling the player what they are choosing, so the mode select
     /// is an informed choice rather than four unlabelled doors.
     pub fn description(&self) -> &'static str {
-        match self {
-            // Kept short on purpose: the card gives a description about 25
-            // characters of room before the type has to shrink past reading
-            // size. Say the one thing that distinguishes the mode.
-            GameMode::Infinite => "3 vidas. No seu ritmo.",
-            GameMode::AgainstTheClock => "60s. Cada erro custa 3s.",
-            GameMode::TimeTrial => "30s. +3s certo, -2s erro.",
-            GameMode::Memory => "As cores somem. 3 vidas.",
-            GameMode::Mosaic => "A peca que nao encaixa.",
-        }
+        &modes::definition(*self).description
     }
 
     /// The mode's identity color, used for its marker on the menu.
     pub fn accent(&self) -> Color {
-        match self {
-            GameMode::Infinite => theme::PRIMARY,
-            GameMode::AgainstTheClock => theme::SUCCESS,
-            GameMode::TimeTrial => theme::LIME,
-            GameMode::Memory => theme::INFO,
-            GameMode::Mosaic => theme::PINK,
-        }
+        modes::definition(*self).accent
     }
 
     /// Stable key for persisted best scores. Never change these strings without
@@ -137,41 +150,57 @@ impl GameMode {
             GameMode::TimeTrial => "time_trial",
             GameMode::Memory => "memory",
             GameMode::Mosaic => "mosaic",
+            GameMode::Daily => "daily",
+            GameMode::Adaptive => "adaptive",
+            GameMode::Repair => "repair",
+            GameMode::Custom => "custom",
         }
     }
 
-    /// Whether a run in this mode can ever end on its own.
-    ///
-    /// Every untimed mode has to be listed here. A mode left out gets a
-    /// zero-length timer from `setup`, which reads as finished on its first
-    /// frame and ends the run before the player sees the board.
+    /// Whether a run in this mode can ever end on its own: whether its
+    /// definition has a clock. There is no list of untimed modes to leave one
+    /// out of; a mode with no clock says so, and one with neither a clock nor
+    /// lives is refused when the definitions are read. A custom run asks its
+    /// own rules instead, through `ColorPuzzle::is_timed`.
     pub fn is_timed(&self) -> bool {
-        !matches!(
-            self,
-            GameMode::Infinite | GameMode::Memory | GameMode::Mosaic
-        )
-    }
-
-    /// Whether the board blanks out before the pick.
-    pub fn hides_colors(&self) -> bool {
-        matches!(self, GameMode::Memory)
+        modes::definition(*self).timer_seconds > 0.0
     }
 
     /// Whether the round is a tiled pattern rather than a field of colors.
     pub fn is_mosaic(&self) -> bool {
-        matches!(self, GameMode::Mosaic)
+        matches!(self, GameMode::Mosaic | GameMode::Repair)
     }
 
-    /// How long a missed board stays up before the next round.
+    /// Whether a tap turns the piece under it rather than picking it. See
+    /// [`ColorPuzzle::turn_piece`].
+    pub fn is_repair(&self) -> bool {
+        matches!(self, GameMode::Repair)
+    }
+
+    /// Whether the colour distance is set by the player's picks rather than
+    /// by the level.
+    pub fn is_adaptive(&self) -> bool {
+        matches!(self, GameMode::Adaptive)
+    }
+
+    /// Whether a run in this mode deals the day's boards rather than its own.
+    pub fn is_daily(&self) -> bool {
+        matches!(self, GameMode::Daily)
+    }
+
+    /// Whether the run's rules were set by hand. See [`GameMode::Custom`].
+    pub fn is_custom(&self) -> bool {
+        matches!(self, GameMode::Custom)
+    }
+
+    /// Whether a run left in the middle can be picked up from the menu.
     ///
-    /// Shorter when there is a clock, because the hold is charged twice there:
-    /// once in the point, once in the seconds it eats.
-    pub fn hold_seconds(&self) -> f32 {
-        if self.is_timed() {
-            0.45
-        } else {
-            0.7
-        }
+    /// Not a daily one: a resumed run deals from a fresh seed, so it would no
+    /// longer be the day's boards — and leaving and resuming would become a way
+    /// to skip the hard ones. Not a custom one either, whose card is the
+    /// builder and has no run to offer.
+    pub fn is_resumable(&self) -> bool {
+        !self.is_daily() && !self.is_custom()
     }
 
     /// How many lives a run in this mode starts with, or `None` when the mode
@@ -179,15 +208,10 @@ impl GameMode {
     ///
     /// Deliberately one or the other, never both: two resources that can each
     /// end a run means the player has to watch two things at once, and neither
-    /// reads clearly. So the untimed modes — which until now could only end by
-    /// the player pressing "encerrar partida" — get lives, and the timed ones
-    /// charge a miss in seconds (`miss_penalty_seconds`).
+    /// reads clearly. `modes` refuses a definition with both.
     pub fn starting_lives(&self) -> Option<usize> {
-        if self.is_timed() {
-            None
-        } else {
-            Some(3)
-        }
+        let lives = modes::definition(*self).lives;
+        (lives > 0).then_some(lives)
     }
 
     /// What a wrong pick costs in a timed mode.
@@ -197,12 +221,98 @@ impl GameMode {
     /// strictly better than looking, which is the wrong lesson for a game about
     /// looking carefully.
     pub fn miss_penalty_seconds(&self) -> f32 {
-        match self {
-            GameMode::AgainstTheClock => 3.0,
-            // Lighter, because a miss here already costs the three seconds the
-            // pick would have earned — the mode charges twice on its own.
-            GameMode::TimeTrial => 2.0,
-            _ => 0.0,
+        modes::definition(*self).miss_penalty_seconds
+    }
+}
+
+/// Everything about a run that is not its level: the clock, the lives, what
+/// a miss costs, whether the board hides, and, for a custom run, the board.
+///
+/// A built-in mode's rules come from `for_mode`, which reads them off its
+/// definition in `assets/modes.ron`; a custom run's are whatever the player dialled in. Either way
+/// `ColorPuzzle` reads them from here, so a rule is asked for in one place
+/// and the custom game goes through exactly the code every other run does.
+#[derive(Debug, Clone, Copy, PartialEq, Reflect)]
+pub struct RunRules {
+    /// Length of the run clock. Zero for a run without one, which is the
+    /// only way a run is untimed: there is no list to leave a mode out of.
+    pub timer_seconds: f32,
+    /// Seconds a hit adds to the clock.
+    pub seconds_added_per_success: f32,
+    /// How long the ground takes to sweep into a new round.
+    pub transition_seconds: f32,
+    /// Lives a run starts with. Zero for a run that has none.
+    pub lives: usize,
+    /// Seconds a miss takes off the clock.
+    pub miss_penalty_seconds: f32,
+    /// Whether the board blanks out before the pick, as in `Memory`.
+    pub hides_colors: bool,
+    /// The board, held fixed. `None` follows the level's curve.
+    pub board: Option<BoardDials>,
+}
+
+/// A board held the same for a whole run, in place of the level's curve.
+#[derive(Debug, Clone, Copy, PartialEq, Reflect)]
+pub struct BoardDials {
+    /// Within `board::MIN_COLUMNS..=board::MAX_COLUMNS`.
+    pub columns: usize,
+    pub palette_size: usize,
+    pub empty_share: f32,
+    /// In Oklab, whatever the run is measured in, so a stored custom game
+    /// means the same board after the metric setting changes.
+    pub color_delta: f32,
+}
+
+impl BoardDials {
+    /// The curve's board at `level`, as a starting point to dial from.
+    pub fn for_level(level: usize) -> Self {
+        Self {
+            columns: columns_for_level(level),
+            palette_size: palette_size_for_level(level),
+            empty_share: empty_share_for_level(level),
+            color_delta: color_delta_for_level(level),
+        }
+    }
+}
+
+impl RunRules {
+    /// The mode's rules as `assets/modes.ron` defines them, on the level's
+    /// curve.
+    pub fn for_mode(game_mode: GameMode) -> Self {
+        let definition = modes::definition(game_mode);
+        Self {
+            timer_seconds: definition.timer_seconds,
+            seconds_added_per_success: definition.seconds_added_per_success,
+            transition_seconds: definition.transition_seconds,
+            lives: definition.lives,
+            miss_penalty_seconds: definition.miss_penalty_seconds,
+            hides_colors: definition.hides_colors,
+            board: None,
+        }
+    }
+
+    /// A custom run's rules before any dial is touched: the custom game's
+    /// definition, on a fixed board.
+    pub fn custom(board: BoardDials) -> Self {
+        Self {
+            board: Some(board),
+            ..Self::for_mode(GameMode::Custom)
+        }
+    }
+
+    pub fn is_timed(&self) -> bool {
+        self.timer_seconds > 0.0
+    }
+
+    /// How long a missed board stays up before the next round.
+    ///
+    /// Shorter when there is a clock, because the hold is charged twice there:
+    /// once in the point, once in the seconds it eats.
+    pub fn hold_seconds(&self) -> f32 {
+        if self.is_timed() {
+            0.45
+        } else {
+            0.7
         }
     }
 }
@@ -325,7 +435,228 @@ impl PowerUps {
 }
 
 
-#[derive(Resource, Debug, Reflect)]
+/// The seed a run's boards are dealt from.
+///
+/// Every round draws from its own generator, built from the run's seed and the
+/// number of picks made so far, rather than from one stream shared by the whole
+/// run. Counted in picks, not in boards generated, because a board is also
+/// regenerated whenever the game screen is left and entered again — pausing
+/// would otherwise move every later board along by one, and the same seed
+/// played with the same picks would stop producing the same run. It also means
+/// pausing cannot be used to reroll a board the player does not like.
+///
+/// `StdRng` rather than `SmallRng`: `SmallRng` is a different algorithm on
+/// 32-bit targets, so a seed played on the web would deal other boards natively.
+///
+/// The boards also depend on the window's shape, which decides how many rows
+/// the honeycomb is cut into; a seed reproduces a run on a window of the same
+/// size. The daily run is the exception, and is cut on a play area of its own
+/// whatever the window: see [`DAILY_PLAY_AREA`].
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RunSeed {
+    seed: u64,
+    /// Picks made so far this run, and so the round the next board belongs to.
+    round: u64,
+}
+
+impl Default for RunSeed {
+    fn default() -> Self {
+        Self::random()
+    }
+}
+
+impl RunSeed {
+    pub fn new(seed: u64) -> Self {
+        Self { seed, round: 0 }
+    }
+
+    /// A fresh seed, for a run nobody asked to reproduce.
+    pub fn random() -> Self {
+        Self::new(rand::random())
+    }
+
+    /// The seed everyone plays on `day`. The day number is the seed as it
+    /// stands: there is nothing to hide, since the boards are the same for
+    /// everyone anyway.
+    pub fn daily(day: u64) -> Self {
+        Self::new(day)
+    }
+
+    /// The seed a new run in `game_mode` starts on: today's in the daily
+    /// challenge, a fresh one anywhere else.
+    pub fn for_mode(game_mode: GameMode) -> Self {
+        if game_mode.is_daily() {
+            Self::daily(crate::clock::today())
+        } else {
+            Self::random()
+        }
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Moves on to the next round. Called once per pick, hit or miss, since
+    /// either one is followed by exactly one new board.
+    pub fn advance(&mut self) {
+        self.round = self.round.saturating_add(1);
+    }
+
+    /// The generator for the current round. The same seed and round always
+    /// give the same generator, however many times it is asked for.
+    ///
+    /// The seed and the round fill separate halves of the key rather than being
+    /// mixed into one number, so no two rounds of any two runs can share one.
+    pub fn rng(&self) -> StdRng {
+        let mut key = [0u8; 32];
+        key[..8].copy_from_slice(&self.seed.to_le_bytes());
+        key[8..16].copy_from_slice(&self.round.to_le_bytes());
+        StdRng::from_seed(key)
+    }
+}
+
+/// Something the player did during a run.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RunAction {
+    /// The game screen was entered, on a window this size. Entering deals the
+    /// round in hand again — the board is cut against the window, and coming
+    /// back from the pause screen regenerates it — so a replay has to as well.
+    Window(Vec2),
+    /// A tap on the board, in world space, to the whole unit. The pick is
+    /// judged at the rounded point, so the log holds exactly what decided it.
+    Pick(Vec2),
+    /// A power-up actually spent. A press that did nothing is not a move.
+    PowerUp(PowerUp),
+}
+
+/// A `RunAction` and when it happened.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LoggedAction {
+    /// Seconds into the run, counted only while the game screen is up. Kept to
+    /// the millisecond, which is what a share code can carry.
+    pub seconds: f32,
+    pub action: RunAction,
+}
+
+/// Everything needed to play a run again: how it started, and every move
+/// after that with its time.
+///
+/// The boards are not stored. `RunSeed` deals the same board for the same
+/// round, and the picks decide which rounds are reached and at what score, so
+/// the seed and the log between them *are* the run — and whether each pick hit
+/// is worked out again rather than trusted. That is what `GameHistory` could not
+/// do: it keeps the boards and the outcomes, but not when anything happened.
+///
+/// The clock only runs on the game screen, so time spent paused is not part of
+/// the run and a replay does not sit through it.
+#[derive(Resource, Debug, Clone)]
+pub struct RunLog {
+    pub seed: u64,
+    pub game_mode: GameMode,
+    /// Where a resumed run picked up. Zero and a full complement otherwise.
+    pub start_score: usize,
+    pub start_lives: usize,
+    pub start_power_ups: PowerUps,
+    /// Whose eyes the boards were dealt for. See `cvd`.
+    pub color_vision: ColorVision,
+    /// What the curve was measured in. See [`Metric`].
+    pub metric: Metric,
+    /// What the boards were dealt in. See [`Gamut`].
+    pub gamut: Gamut,
+    /// The run's rules: the mode's own, or what a custom run was dialled to.
+    pub rules: RunRules,
+    pub actions: Vec<LoggedAction>,
+    elapsed: f32,
+}
+
+impl Default for RunLog {
+    fn default() -> Self {
+        Self {
+            seed: 0,
+            game_mode: GameMode::Infinite,
+            start_score: 0,
+            start_lives: 0,
+            start_power_ups: PowerUps::default(),
+            color_vision: ColorVision::Typical,
+            metric: Metric::Oklab,
+            gamut: Gamut::Srgb,
+            rules: RunRules::for_mode(GameMode::Infinite),
+            actions: vec![],
+            elapsed: 0.0,
+        }
+    }
+}
+
+impl RunLog {
+    /// An empty log for a run started from nothing: no score, the mode's full
+    /// lives, nothing in hand.
+    pub fn fresh(seed: u64, game_mode: GameMode) -> Self {
+        Self {
+            seed,
+            game_mode,
+            start_lives: game_mode.starting_lives().unwrap_or(0),
+            rules: RunRules::for_mode(game_mode),
+            ..default()
+        }
+    }
+
+    /// Starts a new log for the run `puzzle` has just been set up for.
+    pub fn start(&mut self, seed: &RunSeed, puzzle: &ColorPuzzle, power_ups: PowerUps) {
+        *self = Self {
+            seed: seed.seed(),
+            game_mode: puzzle.game_mode,
+            start_score: puzzle.get_score(),
+            start_lives: puzzle.lives(),
+            start_power_ups: power_ups,
+            color_vision: puzzle.color_vision,
+            metric: puzzle.metric,
+            gamut: puzzle.gamut,
+            rules: puzzle.rules,
+            actions: vec![],
+            elapsed: 0.0,
+        };
+    }
+
+    pub fn tick(&mut self, seconds: f32) {
+        self.elapsed += seconds;
+    }
+
+    pub fn record(&mut self, action: RunAction) {
+        self.actions.push(LoggedAction {
+            seconds: (self.elapsed * 1000.0).round() / 1000.0,
+            action,
+        });
+    }
+
+    /// How long the run lasted, or has lasted so far.
+    pub fn duration(&self) -> f32 {
+        self.elapsed
+    }
+
+    /// Sets how long a log read back from somewhere else lasted.
+    pub fn finish_at(&mut self, seconds: f32) {
+        self.elapsed = seconds;
+    }
+
+    /// Whether the whole run is in the log. A resumed run's log starts partway
+    /// in, at a score nothing in it accounts for.
+    pub fn is_whole_run(&self) -> bool {
+        self.start_score == 0
+            && self.start_lives == self.rules.lives
+            && self.start_power_ups == PowerUps::default()
+    }
+
+    /// Whether the run got as far as a pick. Entering the game screen is
+    /// logged too, so a run abandoned before its first pick is not empty, but
+    /// there is nothing in it to watch.
+    pub fn has_picks(&self) -> bool {
+        self.actions
+            .iter()
+            .any(|logged| matches!(logged.action, RunAction::Pick(_)))
+    }
+}
+
+#[derive(Resource, Debug, Clone, Reflect)]
 pub struct ColorPuzzle {
     score: usize,
     /// Wrong picks left before the run ends. Always zero in a timed mode, which
@@ -337,26 +668,88 @@ pub struct ColorPuzzle {
     /// One piece per cell in `Mosaic`, empty in every other mode.
     #[reflect(ignore)]
     current_tiles: Vec<Tile>,
-    /// Where this round's pieces sit and what shape they are. Empty in
-    /// `Mosaic`, which is laid out on a grid instead.
+    /// Where this round's pieces sit and what shape they are.
     #[reflect(ignore)]
     current_slots: Vec<Piece>,
-    /// Columns the mosaic was generated on. Only meaningful with `current_tiles`.
-    current_columns: usize,
     /// The round's distinct colours, which the ground sweeps through before it
     /// settles on the answer's.
     current_palette: Vec<Color>,
     correct_color_index: usize,
+    /// What the last deal came out as. See [`DealReport`].
+    #[reflect(ignore)]
+    last_deal: DealReport,
+    /// Pieces turned on this `Repair` board so far, and the turns it was
+    /// dealt needing. Both zero in every other mode.
+    moves: usize,
+    par: usize,
+    /// The `Repair` board as it was dealt, before any turn, so that dealing it
+    /// again can tell it is the same one. Empty in every other mode.
+    #[reflect(ignore)]
+    dealt_tiles: Vec<Tile>,
+    /// Where `Adaptive` has put the colour distance. Kept here, and moved in
+    /// `resolve_pick`, so that a replayed run climbs the same stairs.
+    #[reflect(ignore)]
+    staircase: Staircase,
+    /// Held at one level by `pin_level`, for practice: picks are judged and
+    /// nothing else moves. Cleared by `setup`, like the score.
+    pinned: bool,
+    /// Whose eyes the boards are dealt for. Set from the settings for a live
+    /// run and from the log for a replay, and left alone by `setup`, since it
+    /// belongs to the player rather than to the run.
+    pub color_vision: ColorVision,
+    /// Which distance the curve is stated in. Kept like `color_vision`, and
+    /// for the same reasons.
+    pub metric: Metric,
+    /// Which gamut the colours are dealt in: the setting, narrowed to what
+    /// the window can show. Kept like `color_vision`.
+    pub gamut: Gamut,
     pub game_mode: GameMode,
-    pub seconds_added_per_success: f32,
+    /// Set with the mode by `setup`, or by hand by `setup_custom`.
+    pub rules: RunRules,
     pub shape_size: f32,
-    pub start_seconds: f32,
-    pub transition_seconds: f32,
     pub width: f32,
     pub height: f32,
 }
 
 
+/// What a deal actually produced, measured rather than intended.
+///
+/// The curve functions say what a level asks for. What comes out differs once
+/// the generators have clamped, retried and fallen back, and the difference is
+/// what tuning has to argue about. Nothing in the game reads this; it is
+/// filled in on every deal because that costs a few subtractions, and
+/// `--difficulty-report` tabulates it.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct DealReport {
+    /// Cells the board was cut into, filled or not.
+    pub cells: usize,
+    /// Cells with a piece on them.
+    pub pieces: usize,
+    /// Colour groups on the board. Zero in `Mosaic`.
+    pub groups: usize,
+    /// Oklab distance, as displayed, from the answer to the group it hides in.
+    pub answer_to_group: f32,
+    /// Oklab distance, as displayed, from the answer to the nearest other
+    /// group. Infinite on a board with one group, and in `Mosaic`.
+    pub answer_to_others: f32,
+    /// Distance, as displayed, between the two closest groups: the room the
+    /// palette had to spread in. Infinite on a board with one group.
+    pub group_separation: f32,
+    /// No direction kept the answer clear of the other groups, and it took
+    /// the last candidate tried instead.
+    pub answer_fallback: bool,
+    /// Groups the gamut left with next to no chroma: a hue in name only.
+    pub grey_groups: usize,
+    /// Edges the `Mosaic` break asked for, and how many it got.
+    pub violations_wanted: usize,
+    pub violations: usize,
+    /// Choices the `Mosaic` solver took back on its way to a board.
+    pub backtracks: usize,
+    /// Deals it took to get a board that passed `check_fairness`, or
+    /// `fairness::ATTEMPTS` if none did.
+    pub attempts: usize,
+}
+
 impl Default for ColorPuzzle {
     
     fn default() -> Self {
@@ -449,6 +842,19 @@ pub fn palette_size_for_level(level: usize) -> usize {
     4 + (4.0 * (1.0 - (-steps / 12.0).exp())).round() as usize
 }
 
+/// How far the answer has to stay from every group but its own, in Oklab.
+///
+/// Chroma under which a group counts as grey in a [`DealReport`].
+const GREY_CHROMA: f32 = 0.02;
+
+/// Twice the delta, so the nearest stranger is always further off than the
+/// answer's own group is, and never under 0.03, where two groups start to
+/// look like one as the ground passes between them. In `metric`'s units, like
+/// the delta.
+pub fn answer_clearance(delta: f32, metric: Metric) -> f32 {
+    (delta * 2.0).max(0.03 * metric.per_oklab_unit())
+}
+
 /// Perceptual distance between the answer and the group it hides in, in Oklab
 /// units: about 0.02 is subtle, 0.01 is hard, below 0.005 is a coin flip.
 ///
@@ -465,6 +871,29 @@ pub fn color_delta_for_level(level: usize) -> f32 {
     MIN_COLOR_DELTA + 0.040 * (-steps / 6.0).exp()
 }
 
+/// The curve in another metric's units: the same shape, with its floor and its
+/// start converted. See [`Metric::per_oklab_unit`].
+pub fn delta_for_level(metric: Metric, level: usize) -> f32 {
+    color_delta_for_level(level) * metric.per_oklab_unit()
+}
+
+/// The staircase `Adaptive` starts a run on, in `metric`'s units. It starts at level one's delta,
+/// which is as easy as the curve ever gets, and may go down to half the curve's
+/// floor: 0.005 is where most players are guessing, so a staircase that gets
+/// there has found someone the curve was never going to test.
+pub fn adaptive_staircase(metric: Metric) -> Staircase {
+    Staircase::new(
+        MIN_COLOR_DELTA / 2.0 * metric.per_oklab_unit(),
+        delta_for_level(metric, 1),
+    )
+}
+
+impl Default for Staircase {
+    fn default() -> Self {
+        adaptive_staircase(Metric::Oklab)
+    }
+}
+
 /// How long the board stays visible in `Memory` before it blanks, by level.
 ///
 /// Shrinks with the level so the mode gets harder in the dimension it is about
@@ -474,31 +903,30 @@ pub fn preview_seconds_for_level(level: usize) -> f32 {
     (1.7 - steps * 0.12).max(0.7)
 }
 
-/// Grid a `Mosaic` round is played on, by level.
+/// Columns of the honeycomb a `Mosaic` round is played on, by level.
 ///
-/// Fixed dimensions rather than a count, because the generator reasons about
-/// neighbours: it needs to know the shape of the board, and a ragged last row
-/// would leave pieces with nothing to disagree with.
-pub fn mosaic_dimensions_for_level(level: usize) -> (usize, usize) {
+/// Far fewer than the colour modes get at the same level, because the question
+/// is different: there the eye compares each piece with its neighbours' colour,
+/// here it has to follow every arm to the piece it meets. A board is cut about
+/// as tall as it is wide — see [`ColorPuzzle::cut_mosaic`] — so four columns is
+/// a dozen-odd pieces and six is over thirty.
+pub fn mosaic_columns_for_level(level: usize) -> usize {
     match level {
-        1 => (2, 3),
-        2 => (3, 3),
-        3 | 4 => (3, 4),
-        5 | 6 => (4, 4),
-        7 | 8 => (4, 5),
-        _ => (5, 5),
+        1..=3 => 4,
+        4..=7 => 5,
+        _ => 6,
     }
 }
 
-/// How many of the odd piece's four edges disagree with their surroundings.
+/// How many of the odd piece's six edges disagree with their surroundings.
 ///
 /// Only four and two are used, and that is a constraint of the tile set rather
-/// than a preference. Three edges away from a piece with two arms or fewer is
-/// always a three-armed piece, and one edge away from a legal piece — against
-/// the board's outer edge, the only place a lone violation is fair — is also
-/// always a three-armed piece. So those settings made the impostor a T every
-/// single time, which is a tell the player learns in two rounds and never
-/// unlearns. Two gives all four shapes; four gives the two-armed ones.
+/// than a preference. The fork is the only piece with an odd number of arms,
+/// and a coherent board around a cell presents an even number of arms to it
+/// far more often than not, so an odd count of bad edges nearly always lands
+/// on a fork. Those settings made the impostor a fork almost every time, which
+/// is a tell the player learns in two rounds and never unlearns. Two and four
+/// both give every shape with two arms or fewer.
 ///
 /// The difficulty past the first level therefore rides on the size of the
 /// board, not on the number of broken edges.
@@ -510,6 +938,17 @@ pub fn mosaic_violations_for_level(level: usize) -> usize {
     }
 }
 
+/// How many pieces a `Repair` board has turned, by level.
+///
+/// The board grows with the level as `Mosaic`'s does, and this grows inside
+/// it. Three is enough that the first board is a puzzle rather than a single
+/// misplaced piece to spot, and the cap keeps a late board from being mostly
+/// turned: past half the pieces there is no pattern left to read the right
+/// way round off, only a lock to pick.
+pub fn repair_turns_for_level(level: usize) -> usize {
+    (2 + level.div_ceil(2)).min(10)
+}
+
 // --- Board grid ------------------------------------------------------------
 //
 // The board used to be squares dropped at random positions with rejection
@@ -524,58 +963,18 @@ pub fn mosaic_violations_for_level(level: usize) -> usize {
 /// button.
 pub const HUD_RESERVED_HEIGHT: f32 = 132.0;
 
-/// Gap between neighbouring cells. Big enough to read as separate squares,
-/// small enough that adjacent colors can still be compared edge to edge.
-pub const BOARD_GAP: f32 = 10.0;
-
 /// The board never touches the window edge.
 pub const BOARD_MARGIN: f32 = 16.0;
 
-/// Cells stop growing here, so a four-square round on a desktop window does not
-/// turn into four billboards.
-pub const MAX_CELL_SIZE: f32 = 160.0;
-
-/// Where every square of a round goes.
-#[derive(Debug, Clone, Copy)]
-pub struct BoardGrid {
-    pub columns: usize,
-    pub rows: usize,
-    pub count: usize,
-    pub cell_size: f32,
-    /// World position of the bottom-left corner of the bottom-left cell.
-    pub origin: Vec2,
-}
-
-impl BoardGrid {
-    /// How many squares sit on `row` (0 = top). Only the last row can be short.
-    fn items_in_row(&self, row: usize) -> usize {
-        let remaining = self.count.saturating_sub(row * self.columns);
-        remaining.min(self.columns)
-    }
-
-    /// Bottom-left corner of the square at `index`, filling left to right and
-    /// top to bottom.
-    pub fn cell_position(&self, index: usize) -> Vec2 {
-        let column = index % self.columns;
-        let row = index / self.columns;
-        let step = self.cell_size + BOARD_GAP;
-
-        // A short last row is centered under the others; left-aligning it makes
-        // the whole board look accidentally off-center.
-        let row_width = self.items_in_row(row) as f32 * self.cell_size
-            + BOARD_GAP * self.items_in_row(row).saturating_sub(1) as f32;
-        let full_width = self.columns as f32 * self.cell_size
-            + BOARD_GAP * self.columns.saturating_sub(1) as f32;
-        let row_offset = (full_width - row_width) / 2.0;
-
-        Vec2::new(
-            self.origin.x + row_offset + column as f32 * step,
-            // Rows are counted from the top, but y grows upward.
-            self.origin.y + (self.rows.saturating_sub(1) - row) as f32 * step,
-        )
-    }
-}
-
+/// The play area every daily board is cut on: a 390x844 phone's, less the HUD
+/// strip and the margins.
+///
+/// Rows come from the play area's shape and cells stop growing at a fixed
+/// size, so a board cut on the window itself would have as many cells as that
+/// window makes room for, and a phone and a desktop would be dealt different
+/// days. The daily board is laid out on this instead and then scaled, whole,
+/// to fit the window, which changes how big it is drawn and nothing else.
+pub const DAILY_PLAY_AREA: Vec2 = Vec2::new(358.0, 680.0);
 
 impl ColorPuzzle {
    pub  fn new() -> Self {
@@ -588,70 +987,53 @@ impl ColorPuzzle {
             base_color: Color::srgb(0.5, 0.5, 0.5),
             current_tiles: vec![],
             current_slots: vec![],
-            current_columns: 0,
             current_palette: vec![],
             correct_color_index: 0,
+            last_deal: DealReport::default(),
+            moves: 0,
+            par: 0,
+            dealt_tiles: vec![],
+            staircase: Staircase::default(),
+            pinned: false,
+            color_vision: ColorVision::Typical,
+            metric: Metric::Oklab,
+            gamut: Gamut::Srgb,
             game_mode: GameMode::TimeTrial,
-            seconds_added_per_success: 3.0,
+            rules: RunRules::for_mode(GameMode::TimeTrial),
             shape_size: 200.0,
-            start_seconds: 60.0,
-            transition_seconds: 1.,
             width: 800.0,
             height: 600.0,
         };
 
         puzzle.setup(&GameMode::TimeTrial);
 
-        puzzle.generate_colors();
+        puzzle.generate_colors(&mut RunSeed::random().rng());
 
         puzzle
     }
 
     pub fn setup(&mut self, game_mode: &GameMode) {
-        self.reset();
+        self.setup_with(*game_mode, RunRules::for_mode(*game_mode));
+    }
 
-        match game_mode {
-            GameMode::Infinite => {
-                self.start_seconds = 0.0;
-                self.transition_seconds = 1.0;
-                self.game_mode = GameMode::Infinite;
-            },
-            GameMode::AgainstTheClock => {
-                self.start_seconds = 60.0;
-                self.transition_seconds = 1.0;
-                self.game_mode = GameMode::AgainstTheClock;
-            },
-            GameMode::TimeTrial => {
-                self.start_seconds = 30.0;
-                self.transition_seconds = 1.0;
-                self.seconds_added_per_success = 3.0;
-                self.game_mode = GameMode::TimeTrial;
-            },
-            GameMode::Mosaic => {
-                // Untimed, like Memory: reading a pattern is slower than
-                // comparing two colors, and a clock would only push the player
-                // to guess.
-                self.start_seconds = 0.0;
-                self.transition_seconds = 0.35;
-                self.game_mode = GameMode::Mosaic;
-            },
-            GameMode::Memory => {
-                // No clock: the pressure in this mode is the preview running
-                // out, and stacking a run timer on top of it only punishes the
-                // player twice for the same thing.
-                self.start_seconds = 0.0;
-                // The board is hidden a beat after it appears, so the round
-                // cannot spend a full second fading the background in first.
-                self.transition_seconds = 0.35;
-                self.game_mode = GameMode::Memory;
-            },
-        }
-
-        // Last, because it reads the mode the match above just set. Doing it
-        // here rather than at the three places a run begins is what keeps the
-        // menu's play button, "jogar novamente" and a resumed run from each
-        // having to remember to do it.
-        self.lives = self.game_mode.starting_lives().unwrap_or(0);
+    /// Sets up a custom run, on rules dialled in by hand.
+    pub fn setup_custom(&mut self, rules: RunRules) {
+        self.setup_with(GameMode::Custom, rules);
+    }
+
+    fn setup_with(&mut self, game_mode: GameMode, rules: RunRules) {
+        self.game_mode = game_mode;
+        self.rules = rules;
+        self.pinned = false;
+        // After the rules, which is what makes this the one place a run's
+        // lives are seeded. Doing it here rather than at the three places a
+        // run begins is what keeps the menu's play button, "jogar novamente"
+        // and a resumed run from each having to remember to do it.
+        self.reset();
+        // Every run climbs from the top, a resumed one included: the score is
+        // stored with a run, but where the stairs stood is not, and starting
+        // easy costs a resumed run a few rounds rather than a life.
+        self.staircase = adaptive_staircase(self.metric);
     }
 
     pub fn set_window_size(&mut self, width: f32, height: f32) {
@@ -677,77 +1059,199 @@ impl ColorPuzzle {
 
 
 
-    /// Grid with the dimensions fixed by the caller.
+    pub fn last_deal(&self) -> DealReport {
+        self.last_deal
+    }
+
+    pub fn get_correct_color_index(&self) -> usize {
+        self.correct_color_index
+    }
+
+
+    /// Deals the next board from `rng`.
+    ///
+    /// Everything random about a round comes from here, so the same generator
+    /// — see [`RunSeed::rng`] — always deals the same board at the same score.
     ///
-    /// `Mosaic` needs this: its generator reasoned about a particular number of
-    /// columns and rows, so the board has to be drawn on exactly those.
-    pub fn grid_for_dimensions(&self, columns: usize, rows: usize) -> BoardGrid {
-        let columns = columns.max(1);
-        let rows = rows.max(1);
-        let available = self.play_area();
+    /// A board that fails [`Self::check_fairness`] is dealt again from the
+    /// same generator, so the retries replay too. Each failure is logged with
+    /// what broke; one that is still unfair after the last attempt is played
+    /// as it stands and logged as an error, since a round has to be dealt.
+    ///
+    /// The board is dealt again whenever the game screen is entered, and a
+    /// `Repair` board is the one board that the player changes before it is
+    /// answered. Dealt again as it stands, coming back from the pause screen
+    /// would undo every turn and set the count back under par, for penalties
+    /// already charged. So a `Repair` board part way through keeps its pieces
+    /// and its count when the same board is dealt again; only a different one,
+    /// the next round's or one cut for a resized window, starts over.
+    pub fn generate_colors(&mut self, rng: &mut impl Rng) {
+        let in_hand = self.unfinished_repair();
+
+        self.deal_fairly(rng);
+
+        if let Some((dealt, tiles, moves)) = in_hand {
+            if dealt == self.dealt_tiles {
+                self.current_tiles = tiles;
+                self.moves = moves;
+            }
+        }
+    }
 
-        let width = (available.x - BOARD_GAP * (columns - 1) as f32) / columns as f32;
-        let height = (available.y - BOARD_GAP * (rows - 1) as f32) / rows as f32;
+    /// The `Repair` board in hand as it was dealt, as it stands and the turns
+    /// made on it, if any have been and it is not yet mended.
+    fn unfinished_repair(&self) -> Option<(Vec<Tile>, Vec<Tile>, usize)> {
+        let unfinished = self.game_mode.is_repair()
+            && self.moves > 0
+            && wfc::bad_edges(
+                self.mosaic_tile_set(),
+                &self.mosaic_lattice(),
+                &self.current_tiles,
+            ) > 0;
 
-        self.grid_from(columns, rows, columns * rows, width.min(height))
+        unfinished.then(|| {
+            (
+                self.dealt_tiles.clone(),
+                self.current_tiles.clone(),
+                self.moves,
+            )
+        })
     }
 
-    /// Sizes and centers a grid of known shape.
-    fn grid_from(&self, columns: usize, rows: usize, count: usize, cell_size: f32) -> BoardGrid {
-        let cell_size = cell_size.min(MAX_CELL_SIZE).max(8.0);
+    fn deal_fairly(&mut self, rng: &mut impl Rng) {
+        let level = self.level();
 
-        let grid_width = columns as f32 * cell_size + BOARD_GAP * (columns - 1) as f32;
-        let grid_height = rows as f32 * cell_size + BOARD_GAP * (rows - 1) as f32;
+        for attempt in 1..=fairness::ATTEMPTS {
+            if self.game_mode.is_repair() {
+                self.generate_repair(level, rng);
+            } else if self.game_mode.is_mosaic() {
+                self.generate_mosaic(level, rng);
+            } else {
+                self.deal_colours(level, rng);
+            }
+            self.last_deal.attempts = attempt;
 
-        // Center the grid in the play area rather than in the window: the HUD
-        // takes its space off the top, so window-centered would sit low.
-        let play_top = self.height / 2.0 - HUD_RESERVED_HEIGHT - BOARD_MARGIN;
-        let play_bottom = -self.height / 2.0 + BOARD_MARGIN;
-        let center_y = (play_top + play_bottom) / 2.0;
+            let Err(problem) = self.check_fairness() else {
+                return;
+            };
 
-        BoardGrid {
-            columns,
-            rows,
-            count,
-            cell_size,
-            origin: Vec2::new(-grid_width / 2.0, center_y - grid_height / 2.0),
+            if attempt < fairness::ATTEMPTS {
+                warn!(
+                    target: "fairness",
+                    mode = ?self.game_mode,
+                    level,
+                    attempt,
+                    "unfair board dealt again: {problem}"
+                );
+            } else {
+                error!(
+                    target: "fairness",
+                    mode = ?self.game_mode,
+                    level,
+                    attempt,
+                    "unfair board kept after {} attempts: {problem}",
+                    fairness::ATTEMPTS
+                );
+            }
         }
     }
 
-    pub fn get_correct_color_index(&self) -> usize {
-        self.correct_color_index
-    }
+    /// Whether the board on the table has exactly one defensible answer. See
+    /// `fairness` for the rules.
+    pub fn check_fairness(&self) -> Result<(), Unfair> {
+        if self.game_mode.is_repair() {
+            return fairness::check_repair(
+                self.mosaic_tile_set(),
+                &self.mosaic_lattice(),
+                &self.current_tiles,
+            );
+        }
 
+        if self.game_mode.is_mosaic() {
+            return fairness::check_mosaic(&wfc::Mosaic {
+                set: self.mosaic_tile_set().clone(),
+                lattice: self.mosaic_lattice(),
+                tiles: self.current_tiles.clone(),
+                broken: self.correct_color_index,
+                violations: self.last_deal.violations,
+                // Not kept past the deal, and not part of what makes a board
+                // fair.
+                stats: wfc::SolveStats::default(),
+            });
+        }
 
-    pub fn generate_colors(&mut self) {
-        let mut rng = rand::thread_rng();
+        // Judged as the player sees the board: two groups that only differ
+        // along an axis the player is missing are one group to them.
+        let seen = |colors: &[Color]| -> Vec<Color> {
+            colors.iter().map(|color| self.color_vision.simulate(*color)).collect()
+        };
+        fairness::check_colours(
+            &seen(&self.current_colors),
+            self.correct_color_index,
+            &seen(&self.current_palette),
+            answer_clearance(self.color_delta(), self.metric),
+            self.metric,
+        )
+    }
 
-        let level = self.level();
-        if self.game_mode.is_mosaic() {
-            self.generate_mosaic(level, &mut rng);
-            return;
+    /// How far the answer sits from its group this round: where the staircase
+    /// stands in `Adaptive`, where the dial was left in a custom run, the
+    /// level's point on the curve everywhere else. The board's size follows
+    /// the level either way, unless it too was dialled. In the run's metric.
+    pub fn color_delta(&self) -> f32 {
+        if self.game_mode.is_adaptive() {
+            self.staircase.delta()
+        } else if let Some(board) = self.rules.board {
+            board.color_delta * self.metric.per_oklab_unit()
+        } else {
+            delta_for_level(self.metric, self.level())
         }
+    }
 
-        let delta = color_delta_for_level(level);
+    /// A colour round: the honeycomb, its groups, and the answer hidden in
+    /// one of them.
+    fn deal_colours(&mut self, level: usize, rng: &mut impl Rng) {
+        let delta = self.color_delta();
 
-        let slots = self.cut_board(columns_for_level(level));
+        let dials = self.rules.board.unwrap_or(BoardDials::for_level(level));
+        let slots = self.cut_board(dials.columns);
         // The mosaic: which cells are empty, which colour group each filled
         // cell belongs to, and which one is the answer.
-        let pattern = mosaic_pattern::generate(
-            &slots,
-            palette_size_for_level(level),
-            empty_share_for_level(level),
-            &mut rng,
-        );
+        let pattern =
+            mosaic_pattern::generate(&slots, dials.palette_size, dials.empty_share, rng);
 
         // The centre of the round, kept off the extremes of lightness so the
         // palette has room to spread in any direction and stay displayable.
-        let base_lab = Self::random_base(&mut rng);
-        let base_color = oklab::to_color(base_lab).unwrap_or(Color::srgb(0.5, 0.5, 0.5));
-        let palette = Self::palette(&mut rng, base_lab, pattern.group_count);
+        let vision = self.color_vision;
+        let metric = self.metric;
+        let gamut = self.gamut;
+        let base_lab = Self::random_base(rng, vision, gamut);
+        let base_color = gamut.to_displayable(base_lab).1;
+        let palette = Self::palette(rng, base_lab, pattern.group_count, vision, gamut);
 
         // Only filled cells become pieces. An empty cell is simply absent —
         // it shows the ground, which is the whole point of it.
+        let mut report = DealReport {
+            cells: slots.len(),
+            pieces: pattern.filled_count(),
+            groups: pattern.group_count,
+            answer_to_others: f32::INFINITY,
+            group_separation: palette
+                .iter()
+                .enumerate()
+                .flat_map(|(i, (a, _))| {
+                    palette[i + 1..]
+                        .iter()
+                        .map(move |(b, _)| vision.distance(metric, *a, *b))
+                })
+                .fold(f32::INFINITY, f32::min),
+            grey_groups: palette
+                .iter()
+                .filter(|(lab, _)| (lab.a * lab.a + lab.b * lab.b).sqrt() < GREY_CHROMA)
+                .count(),
+            ..default()
+        };
+
         let mut slots_in_play: Vec<Piece> = Vec::with_capacity(pattern.filled_count());
         let mut colors: Vec<Color> = Vec::with_capacity(pattern.filled_count());
         let mut correct = 0;
@@ -762,7 +1266,23 @@ impl ColorPuzzle {
                 // The answer wears its group's colour moved by the level's
                 // delta: a near-twin of everything around it, and the only cell
                 // on the board wearing exactly this colour.
-                colors.push(Self::answer_color(&mut rng, &palette, group, delta));
+                let (color, clear) =
+                    Self::answer_color(rng, &palette, group, delta, vision, metric, gamut);
+                report.answer_fallback = !clear;
+                report.answer_to_group = vision.distance(
+                    metric,
+                    oklab::from_color(color),
+                    oklab::from_color(palette[group].1),
+                );
+                report.answer_to_others = palette
+                    .iter()
+                    .enumerate()
+                    .filter(|(other, _)| *other != group)
+                    .map(|(_, (_, other))| {
+                        vision.distance(metric, oklab::from_color(color), oklab::from_color(*other))
+                    })
+                    .fold(f32::INFINITY, f32::min);
+                colors.push(color);
             } else {
                 colors.push(palette[group].1);
             }
@@ -772,8 +1292,8 @@ impl ColorPuzzle {
 
         self.correct_color_index = correct;
         self.base_color = base_color;
+        self.last_deal = report;
         self.current_tiles = vec![];
-        self.current_columns = 0;
         self.current_slots = slots_in_play;
         self.current_palette = palette.into_iter().map(|(_, color)| color).collect();
         self.current_colors = colors;
@@ -792,9 +1312,23 @@ impl ColorPuzzle {
     /// An arc gives the guarantee directly: `groups` hues spread evenly are
     /// separated by construction, and staying near the base's lightness and
     /// chroma keeps them all displayable and looking like one family.
-    fn palette(rng: &mut ThreadRng, base: Oklab, groups: usize) -> Vec<(Oklab, Color)> {
+    ///
+    /// With a colour vision deficiency most of that arc is one colour, so the
+    /// groups are laid out by [`Self::palette_on_axis`] instead.
+    fn palette(
+        rng: &mut impl Rng,
+        base: Oklab,
+        groups: usize,
+        vision: ColorVision,
+        gamut: Gamut,
+    ) -> Vec<(Oklab, Color)> {
+        if let Some(hue) = vision.preserved_hue() {
+            return Self::palette_on_axis(rng, base, groups, hue, gamut);
+        }
+
         let base_hue = base.b.atan2(base.a);
         let base_chroma = (base.a * base.a + base.b * base.b).sqrt().max(0.06);
+        let room = gamut.chroma_room();
 
         // A little over half the circle: far enough apart to tell one group
         // from the next, close enough that the board reads as one mosaic
@@ -811,75 +1345,166 @@ impl ColorPuzzle {
 
                 let hue = base_hue - ARC / 2.0 + ARC * share + rng.gen_range(-0.05..0.05);
                 let lightness = (base.l + rng.gen_range(-0.09..0.09)).clamp(0.45, 0.85);
-                let mut chroma = (base_chroma * rng.gen_range(0.8..1.15)).clamp(0.05, 0.16);
+                let chroma = (base_chroma * rng.gen_range(0.8..1.15)).clamp(0.05, 0.16 * room);
 
                 // Some hues cannot be as saturated as others at a given
-                // lightness; walk the chroma down rather than clamp the colour,
-                // which would move it off its hue.
-                for _ in 0..10 {
-                    let candidate = Oklab::from_lch(lightness, chroma, hue);
-                    if let Some(color) = oklab::to_color(candidate) {
-                        return (candidate, color);
-                    }
-                    chroma *= 0.85;
-                }
+                // lightness. Mapping lowers the chroma rather than clamping
+                // the colour, which would move it off its hue.
+                gamut.to_displayable(Oklab::from_lch(lightness, chroma, hue))
+            })
+            .collect()
+    }
 
-                let flat = Oklab::from_lch(lightness, 0.0, hue);
-                (flat, oklab::to_color(flat).unwrap_or(Color::srgb(0.5, 0.5, 0.5)))
+    /// The round's colour groups for a player with a colour vision deficiency.
+    ///
+    /// The same arc as [`Self::palette`], turned into the plane the player
+    /// still sees: lightness one way, the preserved hue axis the other. The
+    /// arc sweeps from one end of that axis through grey to the other, and
+    /// climbs and falls in lightness as it goes, so neighbouring groups differ
+    /// in something the player can tell apart.
+    fn palette_on_axis(
+        rng: &mut impl Rng,
+        base: Oklab,
+        groups: usize,
+        hue: f32,
+        gamut: Gamut,
+    ) -> Vec<(Oklab, Color)> {
+        let axis = (hue.cos(), hue.sin());
+        // Where the base sits along the axis, signed: which end it is on.
+        let base_along = base.a * axis.0 + base.b * axis.1;
+        let base_angle = if base_along < 0.0 { std::f32::consts::PI } else { 0.0 };
+        const ARC: f32 = std::f32::consts::PI * 1.15;
+
+        (0..groups)
+            .map(|group| {
+                let share = if groups <= 1 {
+                    0.5
+                } else {
+                    group as f32 / (groups - 1) as f32
+                };
+                let angle = base_angle - ARC / 2.0 + ARC * share + rng.gen_range(-0.05..0.05);
+                let radius = rng.gen_range(0.10..0.14) * gamut.chroma_room();
+                let along = radius * angle.cos();
+                let lightness = (base.l + radius * angle.sin()).clamp(0.45, 0.85);
+
+                // As in `palette`. Mapping keeps the hue, so the colour stays
+                // on the axis.
+                gamut.to_displayable(Oklab::new(lightness, along * axis.0, along * axis.1))
             })
             .collect()
     }
 
     /// The answer's colour: its group's, moved by the level's delta.
     ///
+    /// The fallback is not fair by construction; `check_fairness` is what
+    /// keeps it off the table.
+    ///
     /// It has to stay clear of every *other* group as well. When the ground
     /// settles on this colour the answer disappears; if another group's colour
     /// were within a delta of it, that whole group would nearly disappear too
     /// and the round would have more than one defensible answer.
+    ///
+    /// The flag says whether it made it. When no direction clears them all,
+    /// the last candidate is used anyway, and the deal report counts it. It is
+    /// still the level's delta from its group, only too near another.
     fn answer_color(
-        rng: &mut ThreadRng,
+        rng: &mut impl Rng,
         palette: &[(Oklab, Color)],
         group: usize,
         delta: f32,
-    ) -> Color {
+        vision: ColorVision,
+        metric: Metric,
+        gamut: Gamut,
+    ) -> (Color, bool) {
         let own = palette[group].0;
-        let clearance = (delta * 2.0).max(0.03);
+        let clearance = answer_clearance(delta, metric);
         let mut fallback = palette[group].1;
 
         for _ in 0..48 {
-            let Some((lab, color)) = Self::nudge_chromatic(rng, own, delta) else {
-                continue;
-            };
-
+            let (lab, color) = Self::nudge_chromatic(rng, own, delta, vision, metric, gamut);
             fallback = color;
 
             let clear = palette
                 .iter()
                 .enumerate()
                 .all(|(other, (lab_other, _))| {
-                    other == group || Self::distance(lab, *lab_other) > clearance
+                    other == group || vision.distance(metric, lab, *lab_other) > clearance
                 });
 
             if clear {
-                return color;
+                return (color, true);
             }
         }
 
-        fallback
+        (fallback, false)
     }
 
     /// Lays this round's honeycomb over the play area.
     fn cut_board(&self, columns: usize) -> Vec<Piece> {
+        let area = self.layout_area();
+
+        self.fit_to_play_area(board::layout(-area / 2.0, area / 2.0, columns))
+    }
+
+    /// Lays a `Mosaic` round's honeycomb over the play area.
+    ///
+    /// The same lattice as every other round, but cut from a band no taller
+    /// than the play area is wide and centred in it. Left to fill a portrait
+    /// window, a few columns would stretch into a tall, narrow board with most
+    /// of its pieces on the border, where they have the fewest neighbours to
+    /// agree with.
+    fn cut_mosaic(&self, columns: usize) -> Vec<Piece> {
+        let area = self.layout_area();
+        let band = Vec2::new(area.x, area.y.min(area.x));
+
+        self.fit_to_play_area(board::layout(-band / 2.0, band / 2.0, columns))
+    }
+
+    /// The area a board is laid out on, centred on the origin: the play area
+    /// itself, or [`DAILY_PLAY_AREA`] for a daily run.
+    fn layout_area(&self) -> Vec2 {
+        if self.game_mode.is_daily() {
+            DAILY_PLAY_AREA
+        } else {
+            self.play_area()
+        }
+    }
+
+    /// Moves pieces laid out on [`Self::layout_area`] to the middle of the
+    /// play area, scaling a daily board down or up to fit it.
+    fn fit_to_play_area(&self, mut pieces: Vec<Piece>) -> Vec<Piece> {
         let area = self.play_area();
-        let min = Vec2::new(-area.x / 2.0, self.play_bottom());
-        let max = Vec2::new(area.x / 2.0, self.play_bottom() + area.y);
+        let scale = if self.game_mode.is_daily() {
+            (area / DAILY_PLAY_AREA).min_element()
+        } else {
+            1.0
+        };
+        let middle = Vec2::new(0.0, self.play_bottom() + area.y / 2.0);
 
-        board::layout(min, max, columns)
+        for piece in &mut pieces {
+            piece.centre = middle + piece.centre * scale;
+            for corner in &mut piece.corners {
+                *corner *= scale;
+            }
+        }
+        pieces
     }
 
-    /// This round's pieces, or an empty list in `Mosaic`.
-    pub fn slots(&self) -> &[Piece] {
-        &self.current_slots
+    /// The set this round's tiles were dealt from. Each tile says which, so
+    /// any of them will do.
+    fn mosaic_tile_set(&self) -> &'static wfc::TileSet {
+        tile_sets::table().get(self.current_tiles.first().map_or(0, |tile| tile.set))
+    }
+
+    /// Which of this round's cells touch, for the pattern generator and the
+    /// fairness check.
+    fn mosaic_lattice(&self) -> wfc::Lattice {
+        let cells: Vec<(usize, usize)> = self
+            .current_slots
+            .iter()
+            .map(|piece| (piece.column, piece.row))
+            .collect();
+        wfc::Lattice::new(&cells)
     }
 
     /// The colours the ground travels through this round, in order, ending on
@@ -900,16 +1525,9 @@ impl ColorPuzzle {
         sweep
     }
 
-    /// Perceptual distance between two colors.
-    fn distance(a: Oklab, b: Oklab) -> f32 {
-        let dl = a.l - b.l;
-        let da = a.a - b.a;
-        let db = a.b - b.b;
-        (dl * dl + da * da + db * db).sqrt()
-    }
-
-    /// Moves `base` by `amount` in a random direction that stays displayable.
-    fn nudge(rng: &mut ThreadRng, base: Oklab, amount: f32) -> Option<(Oklab, Color)> {
+    /// Moves `base` by `amount` in a random direction that stays displayable,
+    /// or inwards when none of the ones tried does.
+    fn nudge(rng: &mut impl Rng, base: Oklab, amount: f32) -> (Oklab, Color) {
         for _ in 0..24 {
             let hue = rng.gen_range(0.0..std::f32::consts::TAU);
             let lightness_share = rng.gen_range(-0.6_f32..0.6);
@@ -925,11 +1543,36 @@ impl ColorPuzzle {
             );
 
             if let Some(color) = oklab::to_color(candidate) {
-                return Some((candidate, color));
+                return (candidate, color);
             }
         }
 
-        None
+        Self::step_inward(base, amount, Metric::Oklab, Gamut::Srgb)
+    }
+
+    /// Moves `base` by `amount` towards the middle of the gamut: straight at
+    /// the grey of its lightness, or for a colour already closer to grey than
+    /// that, along lightness towards mid grey.
+    ///
+    /// The nudges' last resort, and what keeps them honest. Random directions
+    /// out of a colour on the edge of the gamut mostly leave it, and the
+    /// nudges used to give up and hand back nothing, which `answer_color`
+    /// turned into the group's own colour: an answer at no distance at all.
+    /// Inwards is a direction that fits, at the full delta, every time. Only
+    /// a step too long for the gamut on either side of it is mapped back, and
+    /// then the board's report still measures what was actually dealt.
+    fn step_inward(base: Oklab, amount: f32, metric: Metric, gamut: Gamut) -> (Oklab, Color) {
+        let chroma = (base.a * base.a + base.b * base.b).sqrt();
+        let direction = if chroma > amount / metric.per_oklab_unit() {
+            (0.0, -base.a / chroma, -base.b / chroma)
+        } else if base.l > 0.5 {
+            (-1.0, 0.0, 0.0)
+        } else {
+            (1.0, 0.0, 0.0)
+        };
+
+        let candidate = base.offset(direction, metric.step_along(base, direction, amount));
+        gamut.to_displayable(candidate)
     }
 
     /// Builds a `Mosaic` round: a tiling that fits together everywhere except
@@ -938,65 +1581,130 @@ impl ColorPuzzle {
     /// Every cell shares one color here — the pattern carries the puzzle, so a
     /// second variable would only muddy which rule the player is being asked to
     /// apply.
-    fn generate_mosaic(&mut self, level: usize, rng: &mut ThreadRng) {
-        self.current_slots = vec![];
-        let (columns, rows) = mosaic_dimensions_for_level(level);
-        let mosaic = wfc::generate(columns, rows, mosaic_violations_for_level(level), rng);
+    fn generate_mosaic(&mut self, level: usize, rng: &mut impl Rng) {
+        self.current_slots = self.cut_mosaic(mosaic_columns_for_level(level));
+        let mosaic = wfc::generate(
+            tile_sets::table().for_level(level, rng),
+            self.mosaic_lattice(),
+            mosaic_violations_for_level(level),
+            rng,
+        );
 
-        let base_lab = Self::random_base(rng);
-        let base_color = oklab::to_color(base_lab).unwrap_or(Color::srgb(0.5, 0.5, 0.5));
+        // Typical, whoever is playing: the pattern carries the puzzle, and the
+        // colour is only what it is drawn in.
+        let base_lab = Self::random_base(rng, ColorVision::Typical, self.gamut);
+        let base_color = self.gamut.to_displayable(base_lab).1;
 
         self.base_color = base_color;
+        // No groups to sweep through: the ground goes straight to its tint.
+        // Left alone, the sweep would walk whichever palette the last colour
+        // round happened to leave behind.
+        self.current_palette = vec![];
         self.current_colors = vec![base_color; mosaic.tiles.len()];
         self.correct_color_index = mosaic.broken;
-        self.current_columns = mosaic.columns;
+        self.last_deal = DealReport {
+            cells: mosaic.tiles.len(),
+            pieces: mosaic
+                .tiles
+                .iter()
+                .filter(|tile| !tile.is_empty())
+                .count(),
+            violations_wanted: mosaic_violations_for_level(level),
+            violations: mosaic.violations,
+            backtracks: mosaic.stats.backtracks,
+            ..default()
+        };
         self.current_tiles = mosaic.tiles;
     }
 
-    /// A displayable, reasonably saturated color to build a round on.
-    fn random_base(rng: &mut ThreadRng) -> Oklab {
+    /// Builds a `Repair` round: a `Mosaic` tiling dealt whole, with some of
+    /// its pieces turned.
+    ///
+    /// Drawn and coloured as `Mosaic` is. There is no answer to point at, so
+    /// the answer's index follows the piece turned last, and the history
+    /// marks the board where it was finished: see [`Self::turn_piece`].
+    fn generate_repair(&mut self, level: usize, rng: &mut impl Rng) {
+        self.current_slots = self.cut_mosaic(mosaic_columns_for_level(level));
+        let scramble = wfc::scramble(
+            tile_sets::table().for_level(level, rng),
+            self.mosaic_lattice(),
+            repair_turns_for_level(level),
+            rng,
+        );
+
+        let base_lab = Self::random_base(rng, ColorVision::Typical, self.gamut);
+        let base_color = self.gamut.to_displayable(base_lab).1;
+
+        self.base_color = base_color;
+        self.current_palette = vec![];
+        self.current_colors = vec![base_color; scramble.tiles.len()];
+        self.correct_color_index = 0;
+        self.moves = 0;
+        self.par = scramble.par;
+        self.last_deal = DealReport {
+            cells: scramble.tiles.len(),
+            pieces: scramble
+                .tiles
+                .iter()
+                .filter(|tile| !tile.is_empty())
+                .count(),
+            backtracks: scramble.stats.backtracks,
+            ..default()
+        };
+        self.dealt_tiles = scramble.tiles.clone();
+        self.current_tiles = scramble.tiles;
+    }
+
+    /// A displayable, reasonably saturated color to build a round on. With a
+    /// colour vision deficiency, on one end or the other of the hue axis the
+    /// player still sees.
+    fn random_base(rng: &mut impl Rng, vision: ColorVision, gamut: Gamut) -> Oklab {
         let lightness = rng.gen_range(0.58..0.78);
-        let hue = rng.gen_range(0.0..std::f32::consts::TAU);
-
-        // Walk the chroma down until the color fits in sRGB. Some hues simply
-        // cannot be as saturated as others at a given lightness, and a clamped
-        // color would quietly change the distance the level is set by.
-        let mut chroma = rng.gen_range(0.09..0.16);
-        for _ in 0..12 {
-            let candidate = Oklab::from_lch(lightness, chroma, hue);
-            if oklab::to_color(candidate).is_some() {
-                return candidate;
-            }
-            chroma *= 0.85;
-        }
+        let hue = vision.fold_hue(rng.gen_range(0.0..std::f32::consts::TAU));
 
-        Oklab::from_lch(lightness, 0.0, hue)
+        // Some hues simply cannot be as saturated as others at a given
+        // lightness. Mapped down to the most they can be, not clamped: a
+        // clamped color would quietly change the distance the level is set by.
+        let chroma = rng.gen_range(0.09..0.16) * gamut.chroma_room();
+        gamut.map(Oklab::from_lch(lightness, chroma, hue))
     }
 
     /// Like [`Self::nudge`], but mostly chromatic: the lightness share is
     /// capped so the difference usually has to be judged as a hue or
     /// saturation shift rather than "that one is brighter".
-    fn nudge_chromatic(rng: &mut ThreadRng, base: Oklab, amount: f32) -> Option<(Oklab, Color)> {
+    ///
+    /// With a colour vision deficiency the chromatic part is kept to the
+    /// preserved hue axis, one way or the other. A step off it is partly, or
+    /// wholly, invisible to the player it is meant for.
+    ///
+    /// `amount` is in `metric`'s units, and the step is stretched along its
+    /// direction until it measures that much there.
+    fn nudge_chromatic(
+        rng: &mut impl Rng,
+        base: Oklab,
+        amount: f32,
+        vision: ColorVision,
+        metric: Metric,
+        gamut: Gamut,
+    ) -> (Oklab, Color) {
         for _ in 0..48 {
-            let hue = rng.gen_range(0.0..std::f32::consts::TAU);
+            let hue = vision.fold_hue(rng.gen_range(0.0..std::f32::consts::TAU));
             let lightness_share = rng.gen_range(-0.45_f32..0.45);
             let chromatic_share = (1.0 - lightness_share * lightness_share).sqrt();
 
-            let candidate = base.offset(
-                (
-                    lightness_share,
-                    chromatic_share * hue.cos(),
-                    chromatic_share * hue.sin(),
-                ),
-                amount,
+            let direction = (
+                lightness_share,
+                chromatic_share * hue.cos(),
+                chromatic_share * hue.sin(),
             );
+            let candidate = base.offset(direction, metric.step_along(base, direction, amount));
 
-            if let Some(color) = oklab::to_color(candidate) {
-                return Some((candidate, color));
+            if let Some(color) = gamut.to_color(candidate) {
+                return (candidate, color);
             }
         }
 
-        None
+        Self::step_inward(base, amount, metric, gamut)
     }
 
     /// 1-based difficulty level, derived from the score.
@@ -1054,6 +1762,25 @@ impl ColorPuzzle {
         self.score
     }
 
+    /// Pieces turned on this `Repair` board so far.
+    pub fn moves(&self) -> usize {
+        self.moves
+    }
+
+    /// Turns this `Repair` board was dealt needing. See [`wfc::Scramble`].
+    pub fn par(&self) -> usize {
+        self.par
+    }
+
+    /// This `Repair` board's turns against its par, as the history keeps
+    /// them once it is finished.
+    pub fn turns(&self) -> RepairTurns {
+        RepairTurns {
+            moves: self.moves,
+            par: self.par,
+        }
+    }
+
     /// Scores a point. Returns true when that point crossed a level boundary,
     /// so the caller can celebrate it as its own event rather than folding it
     /// into the ordinary per-pick feedback.
@@ -1062,20 +1789,25 @@ impl ColorPuzzle {
         self.score += 1;
         let leveled_up = self.level() > level_before;
 
-        match self.game_mode {
-            GameMode::TimeTrial => {
-                let remaining_time = game_timer.timer.duration().as_secs_f32();
-                let new_duration = remaining_time + self.get_seconds_added_per_success();
-                game_timer.timer.set_duration(Duration::from_secs_f32(new_duration));
-            },
-            _ => {}
+        let bonus = self.get_seconds_added_per_success();
+        if bonus > 0.0 {
+            let remaining_time = game_timer.timer.duration().as_secs_f32();
+            game_timer
+                .timer
+                .set_duration(Duration::from_secs_f32(remaining_time + bonus));
         }
 
         leveled_up
     }
 
     pub fn get_seconds_added_per_success(&self) -> f32 {
-        self.seconds_added_per_success
+        self.rules.seconds_added_per_success
+    }
+
+    /// Whether the run has a clock. Read off the rules rather than the mode,
+    /// since a custom run's answer is its own.
+    pub fn is_timed(&self) -> bool {
+        self.rules.is_timed()
     }
 
     // --- Lives -------------------------------------------------------------
@@ -1086,9 +1818,9 @@ impl ColorPuzzle {
         self.lives
     }
 
-    /// The mode's full complement; zero when the mode has none.
+    /// The run's full complement; zero when it has none.
     pub fn max_lives(&self) -> usize {
-        self.game_mode.starting_lives().unwrap_or(0)
+        self.rules.lives
     }
 
     /// Whether this run can be lost by running out of lives.
@@ -1146,7 +1878,8 @@ impl ColorPuzzle {
     pub fn can_hold(&self, power_up: PowerUp) -> bool {
         match power_up {
             PowerUp::ExtraLife => self.uses_lives(),
-            PowerUp::EliminateWrong => true,
+            // Nothing on a `Repair` board is the wrong piece to rule out.
+            PowerUp::EliminateWrong => !self.game_mode.is_repair(),
         }
     }
 
@@ -1159,15 +1892,15 @@ impl ColorPuzzle {
     }
 
     pub fn setup_timer(&mut self) -> Timer {
-        Timer::from_seconds(self.start_seconds, TimerMode::Once)
+        Timer::from_seconds(self.rules.timer_seconds, TimerMode::Once)
     }
 
     pub fn reset(&mut self) {
         self.score = 0;
-        // Reads the *current* mode, which is right for a standalone reset. In
-        // `setup` this runs before the mode is assigned, and the tail of that
-        // function sets the lives again from the new one.
-        self.lives = self.game_mode.starting_lives().unwrap_or(0);
+        self.lives = self.rules.lives;
+        self.moves = 0;
+        self.par = 0;
+        self.dealt_tiles.clear();
     }
 
     /// Puts the score back to where a stored run left it, so the level, the
@@ -1176,6 +1909,22 @@ impl ColorPuzzle {
         self.score = score;
     }
 
+    /// Puts the run at the start of `level` and keeps it there: from here on a
+    /// hit scores nothing and a miss costs nothing, so every round is dealt at
+    /// that level. Called after `setup`, which is what lets it go again.
+    ///
+    /// The score is still what sets the level, rather than the level being
+    /// held beside it, so the board, the distance and the preview are dealt
+    /// by exactly the code a climbing run goes through.
+    pub fn pin_level(&mut self, level: usize) {
+        self.score = score_for_level(level);
+        self.pinned = true;
+    }
+
+    pub fn is_pinned(&self) -> bool {
+        self.pinned
+    }
+
     /// Walks the round's cells: color, whether it is the answer, and the piece
     /// drawn on it (`None` outside `Mosaic`).
     pub fn for_each_cell<F>(&self, mut f: F)
@@ -1192,20 +1941,190 @@ impl ColorPuzzle {
         }
     }
 
-    /// The grid a `Mosaic` round is laid out on.
+    /// Where each of this round's cells sits and what shape it is, in the order
+    /// `for_each_cell` walks them: centre, then the outline around it.
+    pub fn piece_outlines(&self) -> Vec<(Vec2, Vec<Vec2>)> {
+        self.current_slots
+            .iter()
+            .map(|piece| (piece.centre, piece.corners.clone()))
+            .collect()
+    }
+
+    /// Whether a pick at this world-space point lands on the answer.
+    ///
+    /// The same test `player_interaction` makes against the spawned pieces,
+    /// made against the round itself, so a logged pick can be judged without a
+    /// board on screen.
+    pub fn pick_hits(&self, point: Vec2) -> bool {
+        self.piece_outlines()
+            .get(self.correct_color_index)
+            .is_some_and(|(centre, corners)| board::contains(*centre, corners, point))
+    }
+
+    /// The round as the history list keeps it.
+    pub fn level_colors(&self) -> Vec<LevelColor> {
+        let outlines = self.piece_outlines();
+        let mut colors = Vec::with_capacity(outlines.len());
+
+        self.for_each_cell(|index, color, is_correct_color, tile| {
+            let Some((centre, corners)) = outlines.get(index).cloned() else {
+                return;
+            };
+
+            colors.push(LevelColor {
+                color,
+                x: centre.x,
+                y: centre.y,
+                is_correct_color,
+                corners,
+                tile,
+            });
+        });
+
+        colors
+    }
+
+    /// Charges or rewards a pick: the score, the clock and the lives.
+    ///
+    /// Everything a pick does to the run and nothing it does to the screen, so
+    /// that a replayed pick moves the run exactly as the live one did. A miss
+    /// costs seconds in a timed mode and a life everywhere else; see
+    /// `GameMode::miss_penalty_seconds`.
+    pub fn resolve_pick(&mut self, scored: bool, game_timer: &mut GameTimer) -> PickOutcome {
+        let mut outcome = PickOutcome::default();
+
+        if self.game_mode.is_adaptive() {
+            self.staircase.record(scored);
+        }
+
+        // The stairs still move in a pinned `Adaptive` run, since the level
+        // there only sizes the board and the delta is what is being practised.
+        if self.pinned {
+            return outcome;
+        }
+
+        if scored {
+            outcome.bonus_seconds = self.get_seconds_added_per_success();
+
+            outcome.leveled_up = self.increase_score(game_timer);
+
+            if outcome.leveled_up && self.level_grants_life() {
+                outcome.gained_life = self.gain_life();
+            }
+
+            return outcome;
+        }
+
+        // The clock is charged by winding `elapsed` forward rather than by
+        // shortening the duration, so `TimeTrial`'s bonus seconds — which
+        // extend the duration — keep meaning what they meant. Capping at the
+        // duration is what turns "the penalty was more time than you had" into
+        // an ordinary expiry: `tick_game_timer` is what notices, and it is
+        // paused for the length of the hold, so the run ends after the answer
+        // has been shown rather than over the top of it.
+        let penalty = self.rules.miss_penalty_seconds;
+        if penalty > 0.0 {
+            let duration = game_timer.timer.duration().as_secs_f32();
+            let spent = (game_timer.timer.elapsed_secs() + penalty).min(duration);
+            game_timer
+                .timer
+                .set_elapsed(Duration::from_secs_f32(spent));
+        }
+
+        // Whether that was the last one is read back through
+        // `is_out_of_lives`; the run is ended once the hold has played out.
+        self.lose_life();
+
+        outcome
+    }
+
+    /// A tap on a `Repair` board: turns the piece under `point` a sixth of a
+    /// turn clockwise, and settles what that did to the run.
     ///
-    /// Only `Mosaic` has one. Its puzzle is about how pieces meet, so its
-    /// pieces have to be laid out where they can meet; the color modes are cut
-    /// irregularly instead — see [`Self::slots`].
-    pub fn mosaic_grid(&self) -> Option<BoardGrid> {
-        if self.current_columns == 0 || self.current_tiles.is_empty() {
+    /// `None` for a tap that turned nothing — off the board, or on a piece
+    /// that looks the same at every angle — which is not a move. Every turn
+    /// past the board's par is charged as a miss, so it costs what a wrong
+    /// pick costs in the mode; the turn that makes every edge agree is
+    /// scored as a hit. Like `resolve_pick`, this is the whole of what the
+    /// tap does to the run, so a replay turns the same pieces to the same
+    /// end.
+    pub fn turn_piece(&mut self, point: Vec2, game_timer: &mut GameTimer) -> Option<TurnOutcome> {
+        let index = self
+            .piece_outlines()
+            .iter()
+            .position(|(centre, corners)| board::contains(*centre, corners, point))?;
+        let tile = *self.current_tiles.get(index)?;
+
+        let set = self.mosaic_tile_set();
+        let turned = set.tile_of(tile.shape as usize, tile.rotation + 1);
+        if turned.edges() == tile.edges() {
             return None;
         }
 
-        let rows = self.current_tiles.len() / self.current_columns;
-        Some(self.grid_for_dimensions(self.current_columns, rows))
+        self.current_tiles[index] = turned;
+        // The history marks the piece the board was finished on.
+        self.correct_color_index = index;
+        self.moves += 1;
+
+        let mut outcome = TurnOutcome {
+            index,
+            tile: turned,
+            over_par: self.moves > self.par,
+            repaired: None,
+        };
+
+        if outcome.over_par {
+            self.resolve_pick(false, game_timer);
+        }
+
+        if wfc::bad_edges(set, &self.mosaic_lattice(), &self.current_tiles) == 0 {
+            outcome.repaired = Some(self.resolve_pick(true, game_timer));
+        }
+
+        Some(outcome)
     }
-    
+}
+
+/// What a tap on a `Repair` board did. See [`ColorPuzzle::turn_piece`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TurnOutcome {
+    /// The piece turned, and what it is now.
+    pub index: usize,
+    pub tile: Tile,
+    /// The turn was past the board's par, and was charged as a miss.
+    pub over_par: bool,
+    /// The turn finished the board, and what that scored.
+    pub repaired: Option<PickOutcome>,
+}
+
+/// What a pick did to the run, for the parts of the game that announce it.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct PickOutcome {
+    /// Seconds granted by a hit in `TimeTrial`. Zero everywhere else.
+    pub bonus_seconds: f32,
+    pub leveled_up: bool,
+    /// Whether the level-up also handed a life back.
+    pub gained_life: bool,
+}
+
+/// The power-up a streak has just earned, if it has earned one.
+///
+/// Every `PICKS_PER_POWER_UP` correct picks in a row, alternating between the
+/// kinds, skipping any the mode cannot use: a timed mode has no lives, so a
+/// life there would be a button that does nothing, and the player would learn
+/// to ignore both.
+pub fn power_up_for_streak(puzzle: &ColorPuzzle, streak: usize) -> Option<PowerUp> {
+    if streak == 0 || streak % PICKS_PER_POWER_UP != 0 {
+        return None;
+    }
+
+    let usable: Vec<PowerUp> = PowerUp::iter()
+        .filter(|kind| puzzle.can_hold(*kind))
+        .collect();
+
+    usable
+        .get((streak / PICKS_PER_POWER_UP - 1) % usable.len().max(1))
+        .copied()
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -1224,6 +2143,7 @@ pub struct LastInteractionEvent {
     correct_color_index: usize,
     colors: Vec<LevelColor>,
     scored: bool,    
+    turns: Option<RepairTurns>,
 }
 
 impl LastInteractionEvent {
@@ -1233,11 +2153,46 @@ impl LastInteractionEvent {
             correct_color_index,
             colors,
             scored,
+            turns: None,
         }
     }
 
+    /// A finished `Repair` board, and the turns it took.
+    pub fn with_turns(mut self, turns: RepairTurns) -> Self {
+        self.turns = Some(turns);
+        self
+    }
+
+    pub fn scored(&self) -> bool {
+        self.scored
+    }
+
     pub fn level_history(&self) -> LevelHistory {
-        LevelHistory::new(self.clicked_position, self.correct_color_index, self.colors.clone(), self.scored)
+        let level = LevelHistory::new(self.clicked_position, self.correct_color_index, self.colors.clone(), self.scored);
+        match self.turns {
+            Some(turns) => level.with_turns(turns),
+            None => level,
+        }
+    }
+}
+
+/// Turns taken on `Repair` boards, and the turns they were dealt needing.
+///
+/// A board scores one point however it is finished, and a turn only costs
+/// anything once it is past par, as a second off the clock. The clock is
+/// shared by every board, so a run's score cannot say how tidily any one of
+/// them was mended; this can, per board in the history and over the run in
+/// the summary.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RepairTurns {
+    pub moves: usize,
+    pub par: usize,
+}
+
+impl RepairTurns {
+    pub fn add(&mut self, other: RepairTurns) {
+        self.moves += other.moves;
+        self.par += other.par;
     }
 }
 
@@ -1247,6 +2202,8 @@ pub struct LevelHistory {
     pub correct_color_index: usize,
     pub colors: Vec<LevelColor>,
     pub scored: bool,
+    /// Set for a finished `Repair` board.
+    pub turns: Option<RepairTurns>,
 }
 
 impl LevelHistory {
@@ -1257,9 +2214,15 @@ impl LevelHistory {
             correct_color_index,
             colors,
             scored,
+            turns: None,
         }
     }
 
+    pub fn with_turns(mut self, turns: RepairTurns) -> Self {
+        self.turns = Some(turns);
+        self
+    }
+
     pub fn for_each_color<F>(&self, mut f: F)
     where
         F: FnMut(usize, &LevelColor),
@@ -1273,7 +2236,7 @@ impl LevelHistory {
     }
 }
 
-#[derive(Resource)]
+#[derive(Resource, Clone)]
 pub struct GameHistory {
     pub levels_played: usize,
     pub total_score: usize,
@@ -1282,6 +2245,8 @@ pub struct GameHistory {
     pub game_mode: GameMode,
     current_streak: usize,
     pub levels : Vec<LevelHistory>,
+    /// Over every `Repair` board finished this run.
+    pub turns: RepairTurns,
 }
 
 impl GameHistory {
@@ -1294,6 +2259,7 @@ impl GameHistory {
             game_mode: GameMode::Infinite,
             total_time: 0.0,
             levels: vec![],
+            turns: RepairTurns::default(),
         }
     }
 
@@ -1325,6 +2291,10 @@ impl GameHistory {
             self.max_streak = self.current_streak;
         }
 
+        if let Some(turns) = level.turns {
+            self.turns.add(turns);
+        }
+
 
         self.levels.push(level);
     }
@@ -1355,6 +2325,7 @@ impl GameHistory {
         self.max_streak = 0;
         self.total_time = 0.0;
         self.levels = vec![];
+        self.turns = RepairTurns::default();
     }
 
     pub fn get_formatted_time(&self) -> String {
@@ -1372,7 +2343,7 @@ impl Default for GameHistory {
     }
 }
 
-#[derive(Resource, Reflect, Debug)]
+#[derive(Resource, Reflect, Debug, Clone)]
 pub struct GameTimer {
     pub timer: Timer,
 }
@@ -1556,6 +2527,72 @@ mod tests {
         }
     }
 
+    /// A custom run's rules, not its mode, decide the clock and the lives,
+    /// and its board stays as dialled while the score climbs.
+    #[test]
+    fn a_custom_run_plays_on_its_own_rules() {
+        let board = BoardDials {
+            columns: 6,
+            palette_size: 3,
+            empty_share: 0.0,
+            color_delta: 0.03,
+        };
+        let mut puzzle = ColorPuzzle::new();
+        puzzle.setup_custom(RunRules {
+            timer_seconds: 30.0,
+            lives: 0,
+            ..RunRules::custom(board)
+        });
+
+        assert!(puzzle.is_timed());
+        assert_eq!(puzzle.max_lives(), 0);
+        assert_eq!(puzzle.setup_timer().duration().as_secs_f32(), 30.0);
+
+        puzzle.restore_score(score_for_level(20));
+        puzzle.set_window_size(480.0, 800.0);
+        puzzle.generate_colors(&mut StdRng::seed_from_u64(3));
+        assert_eq!(puzzle.color_delta(), 0.03);
+        assert_eq!(puzzle.current_palette.len(), 3);
+
+        // And going back to a mode puts the mode's rules back.
+        puzzle.setup(&GameMode::Infinite);
+        assert!(!puzzle.is_timed());
+        assert_eq!(puzzle.max_lives(), 3);
+    }
+
+    /// The day's boards are the same on a phone and on a desktop window, only
+    /// drawn at another size, and they still fit inside the window's play
+    /// area.
+    #[test]
+    fn every_window_is_dealt_the_same_daily_boards() {
+        let deal = |width: f32, height: f32| {
+            let mut puzzle = ColorPuzzle::new();
+            puzzle.setup(&GameMode::Daily);
+            puzzle.set_window_size(width, height);
+            let area = puzzle.play_area();
+
+            (1..=12)
+                .map(|level| {
+                    puzzle.restore_score(score_for_level(level));
+                    puzzle.generate_colors(&mut RunSeed::daily(20380).rng());
+                    for piece in &puzzle.current_slots {
+                        assert!(piece.centre.x.abs() <= area.x / 2.0);
+                    }
+                    let cells: Vec<(usize, usize)> = puzzle
+                        .current_slots
+                        .iter()
+                        .map(|piece| (piece.column, piece.row))
+                        .collect();
+                    (cells, puzzle.current_colors.clone(), puzzle.correct_color_index)
+                })
+                .collect::<Vec<_>>()
+        };
+
+        let phone = deal(390.0, 844.0);
+        assert_eq!(phone, deal(1280.0, 720.0));
+        assert_eq!(phone, deal(2560.0, 1440.0));
+    }
+
     /// Lives count down to zero and stop there, and zero is what ends the run.
     #[test]
     fn the_last_life_ends_the_run() {
@@ -1580,6 +2617,70 @@ mod tests {
         assert_eq!(puzzle.lives(), 0);
     }
 
+    /// In `Adaptive` the picks move the delta and the level does not; in every
+    /// other mode it is the other way round.
+    #[test]
+    fn adaptive_follows_the_picks_rather_than_the_level() {
+        let mut timer = GameTimer {
+            timer: Timer::from_seconds(0.0, TimerMode::Once),
+        };
+
+        let mut puzzle = ColorPuzzle::new();
+        puzzle.setup(&GameMode::Adaptive);
+        let start = puzzle.color_delta();
+        assert_eq!(start, color_delta_for_level(1));
+
+        for _ in 0..6 {
+            puzzle.resolve_pick(true, &mut timer);
+        }
+        let sharper = puzzle.color_delta();
+        assert!(sharper < start);
+        assert!(sharper < color_delta_for_level(puzzle.level()));
+
+        puzzle.resolve_pick(false, &mut timer);
+        assert!(puzzle.color_delta() > sharper);
+
+        // A fresh run starts back at the top.
+        puzzle.setup(&GameMode::Adaptive);
+        assert_eq!(puzzle.color_delta(), start);
+
+        puzzle.setup(&GameMode::Infinite);
+        for _ in 0..6 {
+            puzzle.resolve_pick(true, &mut timer);
+        }
+        assert_eq!(puzzle.color_delta(), color_delta_for_level(puzzle.level()));
+    }
+
+    /// A pinned run stays on its level whatever is picked, and a new run lets
+    /// go of it.
+    #[test]
+    fn a_pinned_level_stays_put() {
+        let mut timer = GameTimer {
+            timer: Timer::from_seconds(30.0, TimerMode::Once),
+        };
+
+        for mode in [GameMode::Infinite, GameMode::TimeTrial] {
+            let mut puzzle = ColorPuzzle::new();
+            puzzle.setup(&mode);
+            puzzle.pin_level(12);
+            let lives = puzzle.lives();
+
+            for scored in [true, true, true, false, false, false, true] {
+                assert_eq!(puzzle.resolve_pick(scored, &mut timer), PickOutcome::default());
+            }
+            assert_eq!(puzzle.level(), 12);
+            assert_eq!(puzzle.lives(), lives);
+            assert!(!puzzle.is_out_of_lives());
+            assert_eq!(timer.timer.duration().as_secs_f32(), 30.0);
+            assert_eq!(timer.timer.elapsed_secs(), 0.0);
+
+            puzzle.setup(&mode);
+            assert!(!puzzle.is_pinned());
+            puzzle.resolve_pick(true, &mut timer);
+            assert_eq!(puzzle.get_score(), 1);
+        }
+    }
+
     /// A timed mode has no lives to lose, so a miss there must not be able to
     /// end the run through this path — its clock is what does that.
     #[test]
@@ -1743,13 +2844,13 @@ mod tests {
             let delta = color_delta_for_level(level);
 
             for _ in 0..200 {
-                let base = ColorPuzzle::random_base(&mut rng);
-                let palette = ColorPuzzle::palette(&mut rng, base, groups);
+                let base = ColorPuzzle::random_base(&mut rng, ColorVision::Typical, Gamut::Srgb);
+                let palette = ColorPuzzle::palette(&mut rng, base, groups, ColorVision::Typical, Gamut::Srgb);
                 assert_eq!(palette.len(), groups);
 
                 for (i, (a, _)) in palette.iter().enumerate() {
                     for (j, (b, _)) in palette.iter().enumerate().skip(i + 1) {
-                        let distance = ColorPuzzle::distance(*a, *b);
+                        let distance = oklab::euclidean(*a, *b);
                         // The bar is a floor on the construction, not on
                         // fairness. The arc's tightest case is eight groups at
                         // the chroma clamp, where neighbouring hues are about
@@ -1774,6 +2875,30 @@ mod tests {
     }
 
     /// The answer hides inside its own group and clear of every other one.
+    /// A group on the very edge of the gamut, where most directions out of it
+    /// leave: the answer still comes out displayable and the full delta away.
+    #[test]
+    fn an_answer_on_the_gamut_edge_keeps_its_delta() {
+        let mut rng = StdRng::seed_from_u64(5);
+        let edge = Gamut::Srgb.map(Oklab::from_lch(0.7, 0.4, 2.5));
+
+        for _ in 0..50 {
+            let (lab, _) = ColorPuzzle::nudge_chromatic(
+                &mut rng,
+                edge,
+                0.03,
+                ColorVision::Typical,
+                Metric::Oklab,
+                Gamut::Srgb,
+            );
+            assert!(oklab::to_color(lab).is_some());
+            assert!((oklab::euclidean(lab, edge) - 0.03).abs() < 1e-3);
+        }
+
+        let (lab, _) = ColorPuzzle::step_inward(edge, 0.03, Metric::Oklab, Gamut::Srgb);
+        assert!((oklab::euclidean(lab, edge) - 0.03).abs() < 1e-4);
+    }
+
     #[test]
     fn the_answer_is_alone_in_its_colour() {
         let mut rng = rand::thread_rng();
@@ -1783,11 +2908,19 @@ mod tests {
             let delta = color_delta_for_level(level);
 
             for _ in 0..100 {
-                let base = ColorPuzzle::random_base(&mut rng);
-                let palette = ColorPuzzle::palette(&mut rng, base, groups);
+                let base = ColorPuzzle::random_base(&mut rng, ColorVision::Typical, Gamut::Srgb);
+                let palette = ColorPuzzle::palette(&mut rng, base, groups, ColorVision::Typical, Gamut::Srgb);
 
                 for group in 0..groups {
-                    let answer = ColorPuzzle::answer_color(&mut rng, &palette, group, delta);
+                    let (answer, _) = ColorPuzzle::answer_color(
+                        &mut rng,
+                        &palette,
+                        group,
+                        delta,
+                        ColorVision::Typical,
+                        Metric::Oklab,
+                        Gamut::Srgb,
+                    );
 
                     // Its own group is the one it must NOT be far from — that
                     // is the puzzle. Everything else it must be clear of.
@@ -1809,4 +2942,157 @@ mod tests {
             }
         }
     }
+
+    /// The same seed and the same picks deal the same run.
+    ///
+    /// Pausing regenerates the board without a pick, so asking for the same
+    /// round twice is checked as well: it has to give the board back, not the
+    /// next one.
+    #[test]
+    fn a_seed_deals_the_same_boards_every_time() {
+        fn deal(mode: GameMode, seed: RunSeed) -> (Vec<Color>, usize, Vec<Tile>, Vec<Color>) {
+            let mut puzzle = ColorPuzzle::new();
+            puzzle.setup(&mode);
+            puzzle.set_window_size(800.0, 1200.0);
+            puzzle.generate_colors(&mut seed.rng());
+            (
+                puzzle.current_colors.clone(),
+                puzzle.correct_color_index,
+                puzzle.current_tiles.clone(),
+                puzzle.current_palette.clone(),
+            )
+        }
+
+        for mode in GameMode::iter() {
+            let mut seed = RunSeed::new(0x5eed);
+            let mut boards = Vec::new();
+
+            for _ in 0..6 {
+                let board = deal(mode, seed);
+                assert_eq!(board, deal(mode, seed), "{mode:?}: same round, other board");
+                boards.push(board);
+                seed.advance();
+            }
+
+            // And the rounds are not all the same board.
+            assert!(
+                boards.windows(2).any(|pair| pair[0] != pair[1]),
+                "{mode:?}: every round dealt the same board"
+            );
+        }
+    }
+
+    /// A `Repair` board is finished by turning its pieces back, and finishing
+    /// it scores what a hit scores. Turns past the par cost seconds on the
+    /// way; taps on a blank cost nothing, since they turn nothing.
+    #[test]
+    fn turning_a_repair_board_back_scores_it() {
+        let mut puzzle = ColorPuzzle::new();
+        puzzle.setup(&GameMode::Repair);
+        puzzle.set_window_size(390.0, 844.0);
+        let mut timer = GameTimer {
+            timer: puzzle.setup_timer(),
+        };
+
+        let mut seed = RunSeed::new(0x7e9a1);
+        let mut rng = seed.rng();
+        puzzle.generate_colors(&mut rng);
+        assert!(puzzle.par() > 0);
+        assert_eq!(puzzle.check_fairness(), Ok(()));
+
+        // One piece turned, so that tapping each piece a full turn round in
+        // order is sure to come to it.
+        let scramble = wfc::scramble(
+            puzzle.mosaic_tile_set(),
+            puzzle.mosaic_lattice(),
+            0,
+            &mut rng,
+        );
+        puzzle.current_tiles = scramble.tiles;
+        puzzle.par = scramble.par;
+        seed.advance();
+
+        let mut finished = None;
+        'board: for (centre, _) in puzzle.piece_outlines() {
+            for _ in 0..wfc::EDGES {
+                let Some(turn) = puzzle.turn_piece(centre, &mut timer) else {
+                    break;
+                };
+                if let Some(outcome) = turn.repaired {
+                    finished = Some((turn, outcome));
+                    break 'board;
+                }
+            }
+        }
+
+        let (turn, outcome) = finished.expect("the board was never finished");
+        assert_eq!(turn.over_par, puzzle.moves() > puzzle.par());
+        assert_eq!(outcome.bonus_seconds, puzzle.rules.seconds_added_per_success);
+        assert_eq!(puzzle.get_score(), 1);
+
+        let charged = (puzzle.moves().saturating_sub(puzzle.par())) as f32
+            * puzzle.rules.miss_penalty_seconds;
+        assert!((timer.timer.elapsed_secs() - charged).abs() < 1e-3);
+    }
+
+    /// Leaving for the pause screen and coming back deals the board again,
+    /// and a `Repair` board comes back as the player left it: the same pieces
+    /// turned, the same count against par. The next round's starts over.
+    #[test]
+    fn a_paused_repair_board_keeps_its_turns() {
+        let mut puzzle = ColorPuzzle::new();
+        puzzle.setup(&GameMode::Repair);
+        puzzle.set_window_size(390.0, 844.0);
+        let mut timer = GameTimer {
+            timer: puzzle.setup_timer(),
+        };
+        let mut seed = RunSeed::new(0x7e9a1);
+        puzzle.generate_colors(&mut seed.rng());
+        let dealt = puzzle.current_tiles.clone();
+
+        let turned = puzzle
+            .piece_outlines()
+            .into_iter()
+            .find_map(|(centre, _)| puzzle.turn_piece(centre, &mut timer))
+            .expect("nothing on the board turns");
+        assert!(turned.repaired.is_none());
+        let tiles = puzzle.current_tiles.clone();
+        assert_ne!(tiles, dealt);
+
+        // Out to the pause screen and back in: dealt on leaving and again on
+        // entering.
+        puzzle.generate_colors(&mut seed.rng());
+        puzzle.generate_colors(&mut seed.rng());
+        assert_eq!(puzzle.current_tiles, tiles);
+        assert_eq!(puzzle.moves(), 1);
+
+        seed.advance();
+        puzzle.restore_score(1);
+        puzzle.generate_colors(&mut seed.rng());
+        assert_eq!(puzzle.moves(), 0);
+    }
+    /// A `Repair` board scores a point however many turns it took, so the
+    /// history keeps the turns: each board's against its par, and the run's
+    /// over every board it finished.
+    #[test]
+    fn the_history_keeps_each_repair_boards_turns() {
+        let mut history = GameHistory::new();
+        let board = |moves, par| {
+            LevelHistory::new(Vec2::ZERO, 0, vec![], true).with_turns(RepairTurns { moves, par })
+        };
+
+        history.add_level(board(7, 5));
+        history.add_level(board(3, 4));
+        assert_eq!(history.levels[0].turns, Some(RepairTurns { moves: 7, par: 5 }));
+        assert_eq!(history.turns, RepairTurns { moves: 10, par: 9 });
+        assert_eq!(history.total_score, 2);
+
+        // A pick in any other mode has no turns to add.
+        history.add_level(LevelHistory::new(Vec2::ZERO, 0, vec![], true));
+        assert_eq!(history.levels[2].turns, None);
+        assert_eq!(history.turns, RepairTurns { moves: 10, par: 9 });
+
+        history.reset();
+        assert_eq!(history.turns, RepairTurns::default());
+    }
 }
@@ -1,10 +1,14 @@
 pub mod components;
 mod systems;
 
+pub use systems::{eliminate_wrong_groups, spawn_board};
+
 
 use systems::*;
 use components::*;
 use bevy::prelude::*;
+use crate::cvd::ColorVision;
+use crate::oklab::{Gamut, Metric};
 
 
 pub struct PuzzlePlugin;
@@ -17,14 +21,27 @@ impl Plugin for PuzzlePlugin {
             .add_message::<RenderLevelHistoryEvent>()
             .add_message::<NewGameEvent>()
             .add_message::<UsePowerUpEvent>()
+            .add_message::<PieceTurnedEvent>()
             .init_resource::<ColorPuzzle>()
+            .init_resource::<RunSeed>()
+            .init_resource::<RunLog>()
             .init_resource::<GameHistory>()
             .init_resource::<GameTimer>()
             .init_resource::<PendingLevelStart>()
             .init_resource::<MemoryPhase>()
             .init_resource::<RoundIntro>()
             .init_resource::<PowerUps>()
+            .init_resource::<ColorVision>()
+            .init_resource::<Metric>()
+            .init_resource::<Gamut>()
             .register_type::<ColorPuzzle>()
+            .add_systems(Startup, (load_color_vision, load_metric, load_gamut))
+            // Ungated, like the new game handler: the setting is changed on
+            // the settings screen and has to be in place before the next run
+            // is set up, wherever that happens.
+            .add_systems(Update, apply_color_vision.run_if(resource_changed::<ColorVision>))
+            .add_systems(Update, apply_metric.run_if(resource_changed::<Metric>))
+            .add_systems(Update, apply_gamut.run_if(resource_changed::<Gamut>))
             .add_systems(OnEnter(crate::AppState::Game), start_puzzle_level)
             .add_systems(OnExit(crate::AppState::Game), despaw_objects)
             .add_systems(Update, render_game_history.run_if(in_state(crate::AppState::LevelHistory)))
@@ -35,6 +52,7 @@ impl Plugin for PuzzlePlugin {
             .add_systems(Update, handle_new_game_event)
             .add_systems(Update, (
                 tick_game_timer,
+                tick_run_log,
                 store_last_interaction_state,
                 spawn_objects,
                 advance_pending_level,
@@ -44,6 +62,13 @@ impl Plugin for PuzzlePlugin {
                 award_power_ups,
                 apply_power_up,
             ).run_if(in_state(crate::AppState::Game)))
+            // The replay turns pieces through the same event a tap does.
+            .add_systems(
+                Update,
+                redraw_turned_pieces.run_if(
+                    in_state(crate::AppState::Game).or_else(in_state(crate::AppState::Replay)),
+                ),
+            )
             .add_systems(Update, background_transition);
 
     }
@@ -1,15 +1,17 @@
-use std::time::Duration;
-
 use bevy::ecs::system::SystemParam;
 use bevy::prelude::*;
 use bevy_prototype_lyon::prelude::*;
 use bevy::camera::ClearColorConfig;
 use crate::events::InteractionAnimationEvent;
 use crate::feedback::BannerEvent;
+use crate::cvd::ColorVision;
+use crate::oklab::{self, Gamut, Metric};
 use crate::theme;
+use crate::game::challenge::Challenge;
 use super::components::*;
-use crate::systems::{lerp_color, BackgroundTranstion};
-use crate::wfc::Tile;
+use crate::systems::BackgroundTranstion;
+use crate::tile_sets;
+use crate::wfc::{ArmStyle, Tile};
 
 #[derive(Component)]
 pub struct LastClick;
@@ -19,13 +21,52 @@ pub struct LastClick;
 /// Grouped because Bevy 0.10 stops at sixteen system parameters and
 /// `player_interaction` had reached seventeen. The bundle is also the honest
 /// shape of the thing: these four events are always sent together, as one
-/// answer to one tap.
+/// answer to one tap — and in `Repair`, the turn goes out alongside them.
 #[derive(SystemParam)]
 pub struct PickEvents<'w> {
     start_level: MessageWriter<'w, StartLevelEvent>,
     last_interaction: MessageWriter<'w, LastInteractionEvent>,
     animation: MessageWriter<'w, InteractionAnimationEvent>,
     banner: MessageWriter<'w, BannerEvent>,
+    turned: MessageWriter<'w, PieceTurnedEvent>,
+}
+
+/// Where a pick is written down.
+///
+/// The pick moves the run on to its next round and goes into the log in the
+/// same breath, so the two cannot disagree about how many picks there were.
+/// Grouped for the same reason `PickEvents` is: `player_interaction` is at
+/// Bevy's parameter limit.
+#[derive(SystemParam)]
+pub struct RunRecorder<'w> {
+    seed: ResMut<'w, RunSeed>,
+    log: ResMut<'w, RunLog>,
+}
+
+impl RunRecorder<'_> {
+    /// Logs a pick and returns the point it is to be judged at: the tap
+    /// rounded to the whole unit, as the log keeps it. Judging the raw point
+    /// would let a tap on the very edge of a piece score live and miss when the
+    /// log is played again.
+    fn pick(&mut self, position: Vec2) -> Vec2 {
+        let position = position.round();
+        // Hit or miss, the next board belongs to the next round.
+        self.seed.advance();
+        self.log.record(RunAction::Pick(position));
+        position
+    }
+
+    /// Logs a tap on a `Repair` board, rounded as `pick` rounds it. The
+    /// round only moves on once the board is finished: see `repaired`.
+    fn turn(&mut self, position: Vec2) -> Vec2 {
+        let position = position.round();
+        self.log.record(RunAction::Pick(position));
+        position
+    }
+
+    fn repaired(&mut self) {
+        self.seed.advance();
+    }
 }
 
 pub fn player_interaction(
@@ -43,6 +84,7 @@ pub fn player_interaction(
     round_intro: Res<RoundIntro>,
     mut events: PickEvents,
     last_click_query: Query<Entity, With<LastClick>>,
+    mut recorder: RunRecorder,
 ) {
 
     let Ok(window) = windows.single() else {
@@ -105,6 +147,14 @@ pub fn player_interaction(
             return;
         };
 
+        if puzzle.game_mode.is_repair() {
+            let world_position = recorder.turn(world_position);
+            turn_piece(world_position, &mut puzzle, &mut game_timer, &mut events, &mut recorder);
+            return;
+        }
+
+        let world_position = recorder.pick(world_position);
+
         for last_click in last_click_query.iter() {
             commands.entity(last_click).despawn();
         }
@@ -127,37 +177,23 @@ pub fn player_interaction(
             }
         }
 
-        let mut bonus_seconds = 0.0;
-        let mut leveled_up = false;
-        let mut gained_life = false;
-
-        if scored {
-            if puzzle.game_mode == GameMode::TimeTrial {
-                bonus_seconds = puzzle.get_seconds_added_per_success();
-            }
-
-            leveled_up = puzzle.increase_score(&mut game_timer);
-
-            if leveled_up && puzzle.level_grants_life() {
-                gained_life = puzzle.gain_life();
-            }
-        }
+        let outcome = puzzle.resolve_pick(scored, &mut game_timer);
 
         events.animation.write(InteractionAnimationEvent {
             position: world_position,
             scored,
-            bonus_seconds,
+            bonus_seconds: outcome.bonus_seconds,
             // Only meaningful on a miss, where it drives the answer reveal.
             correct_position,
             correct_corners,
         });
 
-        if leveled_up {
+        if outcome.leveled_up {
             // A regained life rides along on the level-up banner rather than
             // getting one of its own: `handle_banner_events` keeps only the
             // newest banner on screen, so two announcements in the same frame
             // means one of them is never read.
-            let text = if gained_life {
+            let text = if outcome.gained_life {
                 format!("NIVEL {}  +1 VIDA", puzzle.level())
             } else {
                 format!("NIVEL {}", puzzle.level())
@@ -179,30 +215,12 @@ pub fn player_interaction(
         } else {
             // Missing is no longer free. A mode with a clock pays in seconds; a
             // mode without one pays a life, which is also the only thing that
-            // can end an untimed run.
+            // can end an untimed run. `resolve_pick` has charged it already;
+            // the run is ended by `advance_pending_level`, once the hold has
+            // played out.
             //
-            // The clock is charged by winding `elapsed` forward rather than by
-            // shortening the duration, so `TimeTrial`'s bonus seconds — which
-            // extend the duration — keep meaning what they meant. Capping at
-            // the duration is what turns "the penalty was more time than you
-            // had" into an ordinary expiry: `tick_game_timer` is what notices,
-            // and it is paused for the length of the hold, so the run ends
-            // after the answer has been shown rather than over the top of it.
-            let penalty = puzzle.game_mode.miss_penalty_seconds();
-            if penalty > 0.0 {
-                let duration = game_timer.timer.duration().as_secs_f32();
-                let spent = (game_timer.timer.elapsed_secs() + penalty).min(duration);
-                game_timer
-                    .timer
-                    .set_elapsed(Duration::from_secs_f32(spent));
-            }
-
-            // Returns whether that was the last one; the run is ended by
-            // `advance_pending_level`, once the hold has played out.
-            puzzle.lose_life();
-
             // Hold the board so the reveal has something to point at.
-            pending_level_start.hold(puzzle.game_mode.hold_seconds());
+            pending_level_start.hold(puzzle.rules.hold_seconds());
 
             // A blank board has nothing to learn from. Put the colors back for
             // the length of the hold, so a missed Memory round still shows the
@@ -217,6 +235,98 @@ pub fn player_interaction(
 
 }
 
+/// A tap on a `Repair` board, from the turn to the next board.
+///
+/// No hold and no reveal, as a miss has: a turn past par is charged and
+/// marked, and the board stays in play. A finished board goes into the
+/// history as a hit and the next one is dealt straight away.
+fn turn_piece(
+    world_position: Vec2,
+    puzzle: &mut ColorPuzzle,
+    game_timer: &mut GameTimer,
+    events: &mut PickEvents,
+    recorder: &mut RunRecorder,
+) {
+    let Some(turn) = puzzle.turn_piece(world_position, game_timer) else {
+        return;
+    };
+
+    let Some(outcome) = turn.repaired else {
+        // A finished board is about to be dealt away, so only an unfinished
+        // one has a piece worth redrawing.
+        events.turned.write(PieceTurnedEvent {
+            index: turn.index,
+            tile: turn.tile,
+        });
+
+        if turn.over_par {
+            events.animation.write(InteractionAnimationEvent {
+                position: world_position,
+                scored: false,
+                bonus_seconds: 0.0,
+                correct_position: None,
+                correct_corners: Vec::new(),
+            });
+        }
+        return;
+    };
+
+    recorder.repaired();
+
+    events.animation.write(InteractionAnimationEvent {
+        position: world_position,
+        scored: true,
+        bonus_seconds: outcome.bonus_seconds,
+        correct_position: None,
+        correct_corners: Vec::new(),
+    });
+
+    if outcome.leveled_up {
+        events.banner.write(BannerEvent::large(
+            format!("NIVEL {}", puzzle.level()),
+            theme::ACCENT,
+        ));
+    }
+
+    events.last_interaction.write(
+        LastInteractionEvent::new(
+            world_position,
+            puzzle.get_correct_color_index(),
+            puzzle.level_colors(),
+            true,
+        )
+        .with_turns(puzzle.turns()),
+    );
+    events.start_level.write(StartLevelEvent);
+}
+
+/// Redraws each `Repair` piece that was turned, in place.
+///
+/// Shared with the replay, which turns its pieces through the same event.
+pub fn redraw_turned_pieces(
+    mut commands: Commands,
+    mut turned: MessageReader<PieceTurnedEvent>,
+    mut pieces: Query<(Entity, &mut PuzzleColor)>,
+) {
+    for event in turned.read() {
+        let Some((entity, mut piece)) = pieces
+            .iter_mut()
+            .find(|(_, piece)| piece.index == event.index)
+        else {
+            continue;
+        };
+
+        piece.tile = Some(event.tile);
+        let arm_size = apothem(&piece.corners);
+        let color = piece.color;
+
+        commands
+            .entity(entity)
+            .despawn_related::<Children>()
+            .with_children(|parent| spawn_tile_arms(parent, event.tile, arm_size, color));
+    }
+}
+
 /// The shape of a board piece, from its outline.
 fn piece_shape(corners: &[Vec2]) -> shapes::Polygon {
     shapes::Polygon {
@@ -225,86 +335,85 @@ fn piece_shape(corners: &[Vec2]) -> shapes::Polygon {
     }
 }
 
-/// Side of a square piece, from its outline.
-fn cell_size(corners: &[Vec2]) -> f32 {
+/// Distance from a hex piece's centre to the middle of its sides, from its
+/// outline. The hexes are pointy-top, so that is how far the outline reaches
+/// sideways.
+fn apothem(corners: &[Vec2]) -> f32 {
     corners
         .iter()
-        .map(|corner| corner.x.abs().max(corner.y.abs()) * 2.0)
+        .map(|corner| corner.x.abs())
         .fold(0.0_f32, f32::max)
 }
 
-/// A square piece, for `Mosaic`: its grid cells are pieces like any other, they
-/// are just all the same shape. Corners are relative to the cell's centre, like
-/// every other piece.
-fn square_corners(size: f32) -> Vec<Vec2> {
-    let half = size / 2.0;
-    vec![
-        Vec2::new(-half, -half),
-        Vec2::new(half, -half),
-        Vec2::new(half, half),
-        Vec2::new(-half, half),
-    ]
-}
-
 /// Draws a mosaic piece as children of its cell.
 ///
-/// One node per arm plus a hub at the centre, all in the round's color. Kept as
-/// children so the cell entity stays exactly what the rest of the game expects:
-/// one `PuzzleColor` per cell, positioned at its bottom-left corner, which is
+/// One node per arm plus a hub at the centre, each drawn as the tile's set
+/// says that arm's label looks, in a shade of the round's color. Kept as
+/// children so the cell entity stays exactly what the rest of the game
+/// expects: one `PuzzleColor` per cell, positioned at its centre, which is
 /// what the hit test and the answer reveal are written against.
-fn spawn_tile_arms(parent: &mut ChildSpawnerCommands, tile: Tile, size: f32, color: Color) {
-    let edges = tile.edges();
+fn spawn_tile_arms(parent: &mut ChildSpawnerCommands, tile: Tile, apothem: f32, color: Color) {
+    let set = tile_sets::table().get(tile.set);
+    let arms: Vec<(usize, &ArmStyle)> = tile
+        .edges()
+        .iter()
+        .enumerate()
+        .filter_map(|(edge, label)| set.arm(*label).map(|style| (edge, style)))
+        .collect();
 
     // A piece with no arms is a blank plate. Drawing its hub anyway would put a
     // mark on every empty cell, which reads as a piece and gives the player a
     // pattern that is not there.
-    if !edges.iter().any(|edge| *edge) {
+    let Some((_, widest)) = arms
+        .iter()
+        .max_by(|(_, a), (_, b)| a.width.total_cmp(&b.width))
+    else {
         return;
-    }
+    };
 
-    let arm_width = size * 0.32;
-    let half_arm = arm_width / 2.0;
-    let half_cell = size / 2.0;
+    let shade = |style: &ArmStyle| {
+        if style.shade >= 0.0 {
+            oklab::mix(color, Color::WHITE, style.shade)
+        } else {
+            oklab::mix(color, Color::BLACK, -style.shade)
+        }
+    };
 
     // Pieces are spawned at their centre, so everything here is drawn around
-    // the origin.
-    let hub = shapes::Rectangle {
-        extents: Vec2::splat(arm_width),
-        origin: shapes::RectangleOrigin::Center,
-        radii: None,
+    // the origin. Round, so arms meeting at any of the six angles join it
+    // without a notch, and as wide as the widest of them so none has a
+    // shoulder sticking out past it.
+    let hub = shapes::Circle {
+        radius: widest.width * apothem / 2.0,
+        center: Vec2::ZERO,
     };
 
     parent.spawn((
-        ShapeBuilder::with(&hub).fill(Fill::color(color)).build(),
+        ShapeBuilder::with(&hub).fill(Fill::color(shade(widest))).build(),
         Transform::from_xyz(0.0, 0.0, 0.01),
     ));
 
-    // Each arm runs from inside the hub out to the edge it points at, so the
-    // two overlap and the joint has no seam. `wfc` indexes edges clockwise from
-    // the top, and the board's y axis grows upward.
-    let reach = half_cell + half_arm;
-    let offset = (half_cell - half_arm) / 2.0;
-    let arms = [
-        (Vec2::new(arm_width, reach), Vec2::new(0.0, offset)),
-        (Vec2::new(reach, arm_width), Vec2::new(offset, 0.0)),
-        (Vec2::new(arm_width, reach), Vec2::new(0.0, -offset)),
-        (Vec2::new(reach, arm_width), Vec2::new(-offset, 0.0)),
-    ];
-
-    for (edge, (extents, position)) in edges.iter().zip(arms) {
-        if !edge {
-            continue;
-        }
-
+    for (edge, style) in arms {
+        // Each arm runs from the centre to the middle of the side it points
+        // at, which is where the neighbour's arm starts: the two cells'
+        // centres and that point are in line, so a joined pipe reads as one
+        // across the gap.
         let arm = shapes::Rectangle {
-            extents,
+            extents: Vec2::new(apothem, style.width * apothem),
             origin: shapes::RectangleOrigin::Center,
             radii: None,
         };
 
+        // `wfc` indexes edges clockwise from the upper right, which on a
+        // pointy-top hex faces 60 degrees up from the x axis; the board's y
+        // axis grows upward, so clockwise is a falling angle.
+        let angle = (60.0 - 60.0 * edge as f32).to_radians();
+        let position = Vec2::from_angle(angle) * apothem / 2.0;
+
         parent.spawn((
-            ShapeBuilder::with(&arm).fill(Fill::color(color)).build(),
-            Transform::from_xyz(position.x, position.y, 0.01),
+            ShapeBuilder::with(&arm).fill(Fill::color(shade(style))).build(),
+            Transform::from_xyz(position.x, position.y, 0.01)
+                .with_rotation(Quat::from_rotation_z(angle)),
         ));
     }
 }
@@ -427,7 +536,7 @@ pub fn render_game_history(
             ))
             .with_children(|parent| {
                 if let Some(tile) = color.tile {
-                    spawn_tile_arms(parent, tile, cell_size(&color.corners), color.color);
+                    spawn_tile_arms(parent, tile, apothem(&color.corners), color.color);
                 }
             });
 
@@ -533,7 +642,9 @@ pub fn tick_game_timer(
     mut app_state_next_state: ResMut<NextState<crate::AppState>>,
     time : Res<Time>,
 ) {
-    if !puzzle.game_mode.is_timed() {
+    // Practice has no clock to run out: the rounds go on until the player
+    // leaves them.
+    if !puzzle.is_timed() || puzzle.is_pinned() {
         return;
     }
 
@@ -561,6 +672,7 @@ pub fn spawn_objects(
     mut memory_phase: ResMut<MemoryPhase>,
     mut round_intro: ResMut<RoundIntro>,
     mut start_level_events: MessageReader<StartLevelEvent>,
+    run_seed: Res<RunSeed>,
 ) {
 
     if start_level_events.read().next().is_none() {
@@ -579,7 +691,7 @@ pub fn spawn_objects(
     }
 
     let previous_background = puzzle.background_color();
-    puzzle.generate_colors();
+    puzzle.generate_colors(&mut run_seed.rng());
 
     let Ok((mut camera, mut background_transition)) = camera_query.single_mut() else {
         return;
@@ -592,54 +704,41 @@ pub fn spawn_objects(
     background_transition.sweep(
         previous_background,
         puzzle.sweep(),
-        puzzle.transition_seconds,
+        puzzle.rules.transition_seconds,
     );
     camera.clear_color = ClearColorConfig::Custom(previous_background);
 
-    if puzzle.game_mode.hides_colors() {
+    if puzzle.rules.hides_colors {
         // The sweep is part of showing the board, so the preview starts after
         // it. Counting the sweep as preview would make a late level's 0.7s
         // preview almost entirely ramp.
-        memory_phase.begin(puzzle.preview_seconds() + puzzle.transition_seconds);
+        memory_phase.begin(puzzle.preview_seconds() + puzzle.rules.transition_seconds);
     } else {
         memory_phase.clear();
     }
 
-    // Collect first: laying the board out needs the cell count, and writing the
-    // resulting cell size back to the puzzle needs the borrow released.
+    spawn_board(&mut commands, &mut puzzle);
+}
+
+/// Spawns the round `puzzle` holds, one entity per cell.
+///
+/// Shared with the replay, which deals its rounds from a log and has to draw
+/// them exactly as they were drawn the first time.
+pub fn spawn_board(commands: &mut Commands, puzzle: &mut ColorPuzzle) {
+    // Collect first: writing the resulting cell size back to the puzzle needs
+    // the borrow released.
     let mut cells = Vec::new();
     puzzle.for_each_cell(|index, color, is_correct_color, tile| {
         cells.push((index, color, is_correct_color, tile));
     });
 
-    // Mosaic is laid out on the grid its pattern was generated for; every other
-    // mode gets the irregular cut, which is what keeps the invisible piece from
-    // being a hole at a predictable address.
-    let grid = puzzle.mosaic_grid();
-    let slots: Vec<(Vec2, Vec<Vec2>)> = match &grid {
-        // A mosaic cell is a piece like any other; they are simply all the same
-        // square, because that mode's question is how pieces meet.
-        Some(grid) => (0..cells.len())
-            .map(|index| {
-                let corner = grid.cell_position(index);
-                (
-                    corner + Vec2::splat(grid.cell_size / 2.0),
-                    square_corners(grid.cell_size),
-                )
-            })
-            .collect(),
-        None => puzzle
-            .slots()
-            .iter()
-            .map(|piece| (piece.centre, piece.corners.clone()))
-            .collect(),
-    };
+    let slots = puzzle.piece_outlines();
 
     // A rough board-wide size, for anything that needs one before a piece is
     // in hand.
-    puzzle.shape_size = grid
-        .as_ref()
-        .map(|grid| grid.cell_size)
+    puzzle.shape_size = slots
+        .first()
+        .map(|(_, corners)| apothem(corners) * 2.0)
         .unwrap_or(puzzle.shape_size);
 
     let mut z = 0.0;
@@ -649,7 +748,7 @@ pub fn spawn_objects(
         };
 
         let shape = piece_shape(&corners);
-        let arm_size = cell_size(&corners);
+        let arm_size = apothem(&corners);
 
         // In a mosaic the cell is a plate the piece is drawn on, so every plate
         // is the same neutral color; in the color modes the piece *is* the
@@ -689,6 +788,8 @@ pub fn start_puzzle_level(
     mut pending_level_start: ResMut<PendingLevelStart>,
     mut memory_phase: ResMut<MemoryPhase>,
     mut round_intro: ResMut<RoundIntro>,
+    mut run_log: ResMut<RunLog>,
+    challenge: Res<Challenge>,
     window_query: Query<&Window, With<Window>>
 ) {
     // A hold left over from a miss in a previous run would swallow the first
@@ -700,13 +801,19 @@ pub fn start_puzzle_level(
     let Ok(window) = window_query.single() else {
         return;
     };
-    puzzle.set_window_size(window.width(), window.height());
+    // Against a share code, the boards are cut against the sender's window so
+    // they are the sender's boards; `fit_board_to_window` makes them fit this one.
+    let size = challenge
+        .board_size()
+        .unwrap_or(Vec2::new(window.width(), window.height()));
+    puzzle.set_window_size(size.x, size.y);
+    run_log.record(RunAction::Window(size));
 
     // Infinite runs never reach the timer-expiry path, so without this the
     // game-over screen would report whichever mode was played last.
     game_history.set_game_mode(puzzle.game_mode);
 
-    if game_timer.timer.duration().as_secs_f32() != puzzle.start_seconds {
+    if game_timer.timer.duration().as_secs_f32() != puzzle.rules.timer_seconds {
         game_timer.timer = puzzle.setup_timer();
     }
 
@@ -724,12 +831,47 @@ pub fn start_puzzle_level(
 
 }
 
+pub fn load_color_vision(mut vision: ResMut<ColorVision>) {
+    *vision = ColorVision::load();
+}
+
+/// Hands the colour vision setting to the puzzle, which keeps it across
+/// `setup`. The setting is only reachable from the menus, between runs, so a
+/// run's boards and the value its log took at the start always agree.
+pub fn apply_color_vision(vision: Res<ColorVision>, mut puzzle: ResMut<ColorPuzzle>) {
+    puzzle.color_vision = *vision;
+}
+
+pub fn load_metric(mut metric: ResMut<Metric>) {
+    *metric = Metric::load();
+}
+
+/// As `apply_color_vision`, for the metric the curve is stated in.
+pub fn apply_metric(metric: Res<Metric>, mut puzzle: ResMut<ColorPuzzle>) {
+    puzzle.metric = *metric;
+}
+
+pub fn load_gamut(mut gamut: ResMut<Gamut>) {
+    *gamut = Gamut::load();
+}
+
+/// As `apply_color_vision`, for the gamut. The puzzle gets what the window
+/// can show of the one asked for, and that is what the log records.
+pub fn apply_gamut(gamut: Res<Gamut>, mut puzzle: ResMut<ColorPuzzle>) {
+    puzzle.gamut = gamut.on_surface();
+}
+
 pub fn handle_new_game_event(
     mut new_game_event_reader: MessageReader<NewGameEvent>,
     mut puzzle: ResMut<ColorPuzzle>,
     mut game_timer: ResMut<GameTimer>,
     mut game_history: ResMut<GameHistory>,
     mut app_state_next_state: ResMut<NextState<crate::AppState>>,
+    mut run_seed: ResMut<RunSeed>,
+    mut daily: ResMut<crate::game::score::resources::DailyChallenge>,
+    mut run_log: ResMut<RunLog>,
+    power_ups: Res<PowerUps>,
+    challenge: Res<Challenge>,
     window_query: Query<&Window, With<Window>>
 ) {
     let events = new_game_event_reader.read().next();
@@ -743,12 +885,33 @@ pub fn handle_new_game_event(
     let Ok(window) = window_query.single() else {
         return;
     };
-    puzzle.setup(&event.game_mode);
-    puzzle.set_window_size(window.width(), window.height());
+    // Going again on a custom run goes again on the rules it was dialled to.
+    if event.game_mode.is_custom() {
+        let rules = puzzle.rules;
+        puzzle.setup_custom(rules);
+    } else {
+        puzzle.setup(&event.game_mode);
+    }
+    let size = challenge
+        .board_size()
+        .unwrap_or(Vec2::new(window.width(), window.height()));
+    puzzle.set_window_size(size.x, size.y);
 
     puzzle.reset();
+    // Going again against a share code goes again on its boards.
+    if let Some(seed) = challenge.seed() {
+        *run_seed = RunSeed::new(seed);
+    } else {
+        *run_seed = RunSeed::for_mode(event.game_mode);
+    }
+    if event.game_mode.is_daily() && !challenge.is_playing() {
+        // Going again on the day's boards: `begin` is what decides this one is
+        // a replay rather than a second scored attempt.
+        daily.begin(crate::clock::today());
+    }
+    run_log.start(&run_seed, &puzzle, *power_ups);
 
-    if game_timer.timer.duration().as_secs_f32() != puzzle.start_seconds {
+    if game_timer.timer.duration().as_secs_f32() != puzzle.rules.timer_seconds {
         game_timer.timer = puzzle.setup_timer();
     } else if game_timer.timer.is_finished() {
         game_timer.timer = puzzle.setup_timer();
@@ -777,6 +940,7 @@ pub fn despaw_objects(
     mut pending_level_start: ResMut<PendingLevelStart>,
     mut memory_phase: ResMut<MemoryPhase>,
     mut round_intro: ResMut<RoundIntro>,
+    run_seed: Res<RunSeed>,
 ) {
 
     pending_level_start.clear();
@@ -788,7 +952,9 @@ pub fn despaw_objects(
         commands.entity(entity).despawn();
     }
 
-    puzzle.generate_colors();
+    // The round has not moved on, so this is the same board again — which is
+    // what coming back from the pause screen should find.
+    puzzle.generate_colors(&mut run_seed.rng());
 }
 
 /// Hands out a power-up every `PICKS_PER_POWER_UP` correct picks in a row.
@@ -809,6 +975,12 @@ pub fn award_power_ups(
     mut banner: MessageWriter<BannerEvent>,
     mut last_award: Local<usize>,
 ) {
+    // A practice round is meant to be answered unaided, or its accuracy says
+    // nothing about the level.
+    if puzzle.is_pinned() {
+        return;
+    }
+
     let streak = game_history.current_streak();
 
     // A miss resets the streak, and with it the progress toward the next one.
@@ -816,23 +988,15 @@ pub fn award_power_ups(
         *last_award = 0;
     }
 
-    if streak == 0 || streak % PICKS_PER_POWER_UP != 0 || streak == *last_award {
+    if streak == *last_award {
         return;
     }
 
-    *last_award = streak;
-
-    let usable: Vec<PowerUp> = PowerUp::iter()
-        .filter(|kind| puzzle.can_hold(*kind))
-        .collect();
-
-    let Some(kind) = usable
-        .get((streak / PICKS_PER_POWER_UP - 1) % usable.len().max(1))
-        .copied()
-    else {
+    let Some(kind) = power_up_for_streak(&puzzle, streak) else {
         return;
     };
 
+    *last_award = streak;
     power_ups.grant(kind);
     banner.write(BannerEvent::power_up(kind.label()));
 }
@@ -856,6 +1020,7 @@ pub fn apply_power_up(
     mut puzzle: ResMut<ColorPuzzle>,
     mut board: Query<(&PuzzleColor, &mut Shape)>,
     mut banner: MessageWriter<BannerEvent>,
+    mut run_log: ResMut<RunLog>,
 ) {
     let Some(event) = events.read().next() else {
         return;
@@ -869,6 +1034,7 @@ pub fn apply_power_up(
                 return;
             }
             puzzle.gain_life();
+            run_log.record(RunAction::PowerUp(PowerUp::ExtraLife));
             banner.write(BannerEvent::power_up("+1 VIDA"));
         }
         PowerUp::EliminateWrong => {
@@ -876,44 +1042,53 @@ pub fn apply_power_up(
                 return;
             }
 
-            let ground = puzzle.background_color();
+            eliminate_wrong_groups(puzzle.background_color(), &mut board);
+            run_log.record(RunAction::PowerUp(PowerUp::EliminateWrong));
 
-            // Every colour on the board except the answer's own. Grouped by
-            // colour rather than by cell, because ruling out half the *cells*
-            // would leave groups half-dimmed and say nothing.
-            let mut groups: Vec<Color> = Vec::new();
-            for (piece, _) in board.iter() {
-                if piece.is_correct_color {
-                    continue;
-                }
-                if !groups.iter().any(|c| colors_match(*c, piece.color)) {
-                    groups.push(piece.color);
-                }
-            }
+            banner.write(BannerEvent::power_up("DESCARTADOS"));
+        }
+    }
+}
 
-            // Half of them, rounded up, so a board with two wrong groups still
-            // loses one and the power-up always does something visible.
-            let cut = groups.len().div_ceil(2);
-            let doomed: Vec<Color> = groups.into_iter().take(cut).collect();
+/// Dims half of the groups that do not hold the answer, toward `ground`.
+///
+/// Shared with the replay, which spends the power-ups a run logged and has to
+/// show the board the player was looking at afterwards.
+pub fn eliminate_wrong_groups(ground: Color, board: &mut Query<(&PuzzleColor, &mut Shape)>) {
+    // Every colour on the board except the answer's own. Grouped by colour
+    // rather than by cell, because ruling out half the *cells* would leave
+    // groups half-dimmed and say nothing.
+    let mut groups: Vec<Color> = Vec::new();
+    for (piece, _) in board.iter() {
+        if piece.is_correct_color {
+            continue;
+        }
+        if !groups.iter().any(|c| colors_match(*c, piece.color)) {
+            groups.push(piece.color);
+        }
+    }
 
-            for (piece, mut shape) in board.iter_mut() {
-                if piece.is_correct_color {
-                    continue;
-                }
-                if doomed.iter().any(|c| colors_match(*c, piece.color)) {
-                    shape.fill = Some(Fill::color(lerp_color(
-                        piece.color,
-                        ground,
-                        ELIMINATED_MIX,
-                    )));
-                }
-            }
+    // Half of them, rounded up, so a board with two wrong groups still loses
+    // one and the power-up always does something visible.
+    let cut = groups.len().div_ceil(2);
+    let doomed: Vec<Color> = groups.into_iter().take(cut).collect();
 
-            banner.write(BannerEvent::power_up("DESCARTADOS"));
+    for (piece, mut shape) in board.iter_mut() {
+        if piece.is_correct_color {
+            continue;
+        }
+        if doomed.iter().any(|c| colors_match(*c, piece.color)) {
+            shape.fill = Some(Fill::color(oklab::mix(piece.color, ground, ELIMINATED_MIX)));
         }
     }
 }
 
+/// Runs the log's clock while the game screen is up. Time on the pause screen
+/// is not part of the run.
+pub fn tick_run_log(time: Res<Time>, mut run_log: ResMut<RunLog>) {
+    run_log.tick(time.delta_secs());
+}
+
 /// Whether two colours are the same group.
 ///
 /// Compared channel-wise with a tolerance rather than by `==`: the generator
@@ -0,0 +1,508 @@
+use std::time::Duration;
+
+use bevy::prelude::*;
+
+use crate::game::puzzle::components::{
+    power_up_for_streak, ColorPuzzle, GameHistory, GameTimer, LevelHistory, PickOutcome,
+    PowerUp, PowerUps, RunAction, RunLog, RunSeed,
+};
+use crate::wfc::Tile;
+use crate::AppState;
+
+/// Root of the replay's control bar.
+#[derive(Component)]
+pub struct ReplayControls;
+
+/// Switches between normal and double speed.
+#[derive(Component)]
+pub struct ReplaySpeedButton;
+
+/// The speed button's label, rewritten when it is pressed.
+#[derive(Component)]
+pub struct ReplaySpeedLabel;
+
+/// Leaves the replay for the summary it was opened from.
+#[derive(Component)]
+pub struct ReplayBackButton;
+
+/// How fast a replay runs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ReplaySpeed {
+    #[default]
+    Normal,
+    Double,
+}
+
+impl ReplaySpeed {
+    pub fn factor(&self) -> f32 {
+        match self {
+            ReplaySpeed::Normal => 1.0,
+            ReplaySpeed::Double => 2.0,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            ReplaySpeed::Normal => "1X",
+            ReplaySpeed::Double => "2X",
+        }
+    }
+
+    pub fn toggled(&self) -> Self {
+        match self {
+            ReplaySpeed::Normal => ReplaySpeed::Double,
+            ReplaySpeed::Double => ReplaySpeed::Normal,
+        }
+    }
+}
+
+/// The parts of the game a run moves: the same resources a live run writes to,
+/// borrowed for the length of one step.
+pub struct RunState<'a> {
+    pub puzzle: &'a mut ColorPuzzle,
+    pub game_timer: &'a mut GameTimer,
+    pub history: &'a mut GameHistory,
+    pub power_ups: &'a mut PowerUps,
+}
+
+/// Something the replay did that the screen has to show.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ReplayStep {
+    /// A board was dealt. `from` is the ground it sweeps from.
+    Deal { from: Color },
+    /// A logged pick, judged again.
+    Pick {
+        position: Vec2,
+        scored: bool,
+        outcome: PickOutcome,
+        /// The answer's centre and outline, for the reveal on a miss.
+        answer: Option<(Vec2, Vec<Vec2>)>,
+    },
+    /// A `Repair` piece was turned, and the board is not finished yet.
+    Turn { index: usize, tile: Tile },
+    /// A streak earned a power-up.
+    Granted(PowerUp),
+    /// A power-up was spent.
+    Spent(PowerUp),
+    /// The log has run out.
+    End,
+}
+
+/// A run being played back from its `RunLog`.
+///
+/// Nothing here is a recording of the screen. The replay deals every board
+/// again from the log's seed and judges every pick again against it, through
+/// the same `resolve_pick` the live game uses, so what it shows is what the run
+/// *was* rather than what it claimed. The systems around it only draw.
+///
+/// Time is the log's time: the clock is moved on by the caller, and anything
+/// logged at or before the new time happens, in order. The misses hold the
+/// board for `hold_seconds` exactly as they did live, because that hold is where
+/// the player saw the answer they missed — and it is the part of a lost run
+/// worth watching.
+#[derive(Resource, Default)]
+pub struct Replay {
+    log: RunLog,
+    seed: RunSeed,
+    clock: f32,
+    /// Index of the next action in the log.
+    next: usize,
+    /// When the hold after a miss runs out, while there is one.
+    hold_until: Option<f32>,
+    /// When the board on screen was dealt, for `Memory`'s preview. `None`
+    /// until the first one is.
+    dealt_at: Option<f32>,
+    /// When the run's clock ran out, in a timed mode.
+    expired_at: Option<f32>,
+    /// Whether the log asked for something the live game would have refused.
+    impossible: bool,
+    finished: bool,
+    pub speed: ReplaySpeed,
+}
+
+/// How far a pick may sit on the wrong side of a deadline and still count.
+///
+/// The live game ticks its timers and the log's clock in separate systems, and
+/// the log keeps milliseconds, so the two can disagree by a frame about a pick
+/// made right as a hold, a preview or the run's clock ran out. A frame or two
+/// either way is that; more is a log the game did not write.
+const CLOCK_GRACE_SECONDS: f32 = 0.1;
+
+impl Replay {
+    /// Puts `run` back to where the log's run started.
+    ///
+    /// Mirrors what the play and continue buttons do, in the same order: the
+    /// mode first, since `setup` fills the lives from it, then the score and
+    /// lives a resumed run came back with.
+    pub fn start(log: RunLog, run: &mut RunState) -> Self {
+        // Before anything is dealt: the boards are dealt for the eyes the run
+        // was played with, not the watcher's. The live value comes back with
+        // the rest of the set-aside run.
+        run.puzzle.color_vision = log.color_vision;
+        run.puzzle.metric = log.metric;
+        run.puzzle.gamut = log.gamut;
+        if log.game_mode.is_custom() {
+            run.puzzle.setup_custom(log.rules);
+        } else {
+            run.puzzle.setup(&log.game_mode);
+        }
+        run.puzzle.restore_score(log.start_score);
+        run.puzzle.restore_lives(log.start_lives);
+        run.game_timer.timer = run.puzzle.setup_timer();
+
+        run.history.reset();
+        run.history.set_game_mode(log.game_mode);
+        run.history.restore(log.start_score);
+
+        *run.power_ups = log.start_power_ups;
+
+        Self {
+            seed: RunSeed::new(log.seed),
+            log,
+            ..default()
+        }
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.finished
+    }
+
+    /// Whether every move in the log so far is one the live game could have
+    /// taken: no pick before a board was dealt, during the hold after a miss,
+    /// with no lives left or after the clock ran out. A log written by the game
+    /// always is; one written by hand to claim a score need not be.
+    pub fn is_possible(&self) -> bool {
+        !self.impossible
+    }
+
+    /// Whether a `Memory` board should be blank right now.
+    pub fn hides_board(&self, puzzle: &ColorPuzzle) -> bool {
+        puzzle.rules.hides_colors
+            && self.hold_until.is_none()
+            && self.dealt_at.is_some_and(|dealt_at| {
+                self.clock - dealt_at >= puzzle.preview_seconds() + puzzle.rules.transition_seconds
+            })
+    }
+
+    /// Moves the run on by `seconds` of log time, returning what happened.
+    pub fn advance(&mut self, seconds: f32, run: &mut RunState) -> Vec<ReplayStep> {
+        let mut steps = Vec::new();
+        if self.finished {
+            return steps;
+        }
+
+        let target = (self.clock + seconds).min(self.log.duration());
+
+        loop {
+            let action_at = self.log.actions.get(self.next).map(|logged| logged.seconds);
+
+            // A hold that ends at the same moment as an action ends first: the
+            // live game could not have taken a pick while it was holding. Within
+            // a frame of it counts as the same moment.
+            if let Some(hold_until) = self.hold_until.filter(|until| {
+                *until <= target
+                    && action_at.is_none_or(|at| *until <= at + CLOCK_GRACE_SECONDS)
+            }) {
+                self.run_clock(hold_until, run);
+                self.hold_until = None;
+
+                // The last life went on that miss; the live run ended here.
+                if !run.puzzle.is_out_of_lives() {
+                    steps.push(self.deal(run));
+                }
+                continue;
+            }
+
+            let Some(at) = action_at.filter(|at| *at <= target) else {
+                break;
+            };
+
+            self.run_clock(at, run);
+            let action = self.log.actions[self.next].action;
+            self.next += 1;
+            self.apply(action, run, &mut steps);
+        }
+
+        self.run_clock(target, run);
+
+        if self.next >= self.log.actions.len() && self.clock >= self.log.duration() {
+            self.finished = true;
+            steps.push(ReplayStep::End);
+        }
+
+        steps
+    }
+
+    /// Runs the clock up to `to`. The run timer stands still during a hold, as
+    /// `tick_game_timer` does.
+    fn run_clock(&mut self, to: f32, run: &mut RunState) {
+        let elapsed = (to - self.clock).max(0.0);
+        let from = self.clock;
+        self.clock = to.max(self.clock);
+
+        if run.puzzle.is_timed() && self.hold_until.is_none() {
+            let remaining = run.game_timer.timer.remaining_secs();
+            if self.expired_at.is_none() && elapsed >= remaining {
+                self.expired_at = Some(from + remaining);
+            }
+            run.game_timer.timer.tick(Duration::from_secs_f32(elapsed));
+        }
+    }
+
+    fn deal(&mut self, run: &mut RunState) -> ReplayStep {
+        let from = run.puzzle.background_color();
+        run.puzzle.generate_colors(&mut self.seed.rng());
+        self.dealt_at = Some(self.clock);
+
+        ReplayStep::Deal { from }
+    }
+
+    fn apply(&mut self, action: RunAction, run: &mut RunState, steps: &mut Vec<ReplayStep>) {
+        match action {
+            RunAction::Window(size) => {
+                // Entering the game screen clears a hold and deals the round in
+                // hand, cut against the window it was played on.
+                run.puzzle.set_window_size(size.x, size.y);
+                self.hold_until = None;
+                steps.push(self.deal(run));
+            }
+            RunAction::Pick(position) => {
+                let expired = self
+                    .expired_at
+                    .is_some_and(|expired_at| self.clock - expired_at > CLOCK_GRACE_SECONDS);
+                // `player_interaction` refuses picks while a Memory board is
+                // still showing its colors; the preview starts after the sweep.
+                let previewing = run.puzzle.rules.hides_colors
+                    && self.dealt_at.is_some_and(|dealt_at| {
+                        self.clock - dealt_at + CLOCK_GRACE_SECONDS
+                            < run.puzzle.preview_seconds() + run.puzzle.rules.transition_seconds
+                    });
+                if self.dealt_at.is_none()
+                    || self.hold_until.is_some()
+                    || (run.puzzle.uses_lives() && run.puzzle.is_out_of_lives())
+                    || expired
+                    || previewing
+                {
+                    self.impossible = true;
+                }
+
+                if run.puzzle.game_mode.is_repair() {
+                    self.turn(position, run, steps);
+                    return;
+                }
+
+                let scored = run.puzzle.pick_hits(position);
+                let colors = run.puzzle.level_colors();
+                let answer_index = run.puzzle.get_correct_color_index();
+                let answer = run.puzzle.piece_outlines().get(answer_index).cloned();
+
+                self.seed.advance();
+                let outcome = run.puzzle.resolve_pick(scored, run.game_timer);
+                run.history
+                    .add_level(LevelHistory::new(position, answer_index, colors, scored));
+
+                steps.push(ReplayStep::Pick {
+                    position,
+                    scored,
+                    outcome,
+                    answer,
+                });
+
+                if scored {
+                    if let Some(kind) =
+                        power_up_for_streak(run.puzzle, run.history.current_streak())
+                    {
+                        run.power_ups.grant(kind);
+                        steps.push(ReplayStep::Granted(kind));
+                    }
+                    steps.push(self.deal(run));
+                } else {
+                    self.hold_until = Some(self.clock + run.puzzle.rules.hold_seconds());
+                }
+            }
+            RunAction::PowerUp(PowerUp::ExtraLife) => {
+                if run.puzzle.lives() < run.puzzle.max_lives()
+                    && run.power_ups.spend(PowerUp::ExtraLife)
+                {
+                    run.puzzle.gain_life();
+                    steps.push(ReplayStep::Spent(PowerUp::ExtraLife));
+                } else {
+                    // Only a power-up actually spent is logged.
+                    self.impossible = true;
+                }
+            }
+            RunAction::PowerUp(PowerUp::EliminateWrong) => {
+                if run.power_ups.spend(PowerUp::EliminateWrong) {
+                    steps.push(ReplayStep::Spent(PowerUp::EliminateWrong));
+                } else {
+                    self.impossible = true;
+                }
+            }
+        }
+    }
+
+    /// A logged tap on a `Repair` board, turned again. The same steps
+    /// `player_interaction` takes: a turn that finishes the board is a hit and
+    /// deals the next, and there is never a hold.
+    fn turn(&mut self, position: Vec2, run: &mut RunState, steps: &mut Vec<ReplayStep>) {
+        let Some(turn) = run.puzzle.turn_piece(position, run.game_timer) else {
+            return;
+        };
+
+        let Some(outcome) = turn.repaired else {
+            steps.push(ReplayStep::Turn {
+                index: turn.index,
+                tile: turn.tile,
+            });
+            if turn.over_par {
+                steps.push(ReplayStep::Pick {
+                    position,
+                    scored: false,
+                    outcome: PickOutcome::default(),
+                    answer: None,
+                });
+            }
+            return;
+        };
+
+        let colors = run.puzzle.level_colors();
+        run.history.add_level(
+            LevelHistory::new(position, turn.index, colors, true).with_turns(run.puzzle.turns()),
+        );
+        steps.push(ReplayStep::Pick {
+            position,
+            scored: true,
+            outcome,
+            answer: None,
+        });
+
+        self.seed.advance();
+        steps.push(self.deal(run));
+    }
+}
+
+/// What the replay screen plays, and where its back button leads.
+///
+/// Set by whoever opens it: the summary hands over the run that just ended,
+/// the challenge screen a run read from a share code.
+#[derive(Resource)]
+pub struct ReplaySource {
+    pub log: RunLog,
+    pub back_to: AppState,
+}
+
+impl Default for ReplaySource {
+    fn default() -> Self {
+        Self {
+            log: RunLog::default(),
+            back_to: AppState::GameOverResume,
+        }
+    }
+}
+
+/// The finished run's own state, set aside while the replay borrows the
+/// resources and put back when it is closed.
+///
+/// Put back whole rather than left as the replay ends it: the summary is
+/// entered again on the way out, and the history screen behind it decides from
+/// the timer whether the run can still be continued.
+#[derive(Resource, Default)]
+pub struct SetAsideRun {
+    pub run: Option<(ColorPuzzle, GameTimer, GameHistory, PowerUps)>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::puzzle::components::{GameMode, RunRules};
+
+    /// Plays a run the way the live game logs it — a window, then a pick on
+    /// the answer every `gap` seconds, with one miss — and returns the log
+    /// alongside the score it ended on.
+    fn play(game_mode: GameMode, seed: u64, picks: usize, gap: f32) -> (RunLog, usize) {
+        let mut puzzle = ColorPuzzle::default();
+        let mut game_timer = GameTimer::default();
+        let power_ups = PowerUps::default();
+        let run_seed = RunSeed::new(seed);
+
+        if game_mode.is_custom() {
+            // Rules no mode has: a clock and a hidden board, so the replay
+            // has to take both from the log rather than from the mode.
+            puzzle.setup_custom(RunRules {
+                timer_seconds: 45.0,
+                hides_colors: true,
+                transition_seconds: 0.35,
+                lives: 0,
+                ..RunRules::for_mode(game_mode)
+            });
+        } else {
+            puzzle.setup(&game_mode);
+        }
+        game_timer.timer = puzzle.setup_timer();
+
+        let mut log = RunLog::default();
+        log.start(&run_seed, &puzzle, power_ups);
+
+        let mut seed = run_seed;
+        puzzle.set_window_size(480.0, 800.0);
+        log.record(RunAction::Window(Vec2::new(480.0, 800.0)));
+        puzzle.generate_colors(&mut seed.rng());
+
+        for index in 0..picks {
+            log.tick(gap);
+            let outlines = puzzle.piece_outlines();
+            let (answer, _) = outlines[puzzle.get_correct_color_index()].clone();
+            // One deliberate miss, off the board entirely.
+            let position = if index == 2 { Vec2::splat(10_000.0) } else { answer };
+
+            log.record(RunAction::Pick(position));
+            seed.advance();
+            let scored = puzzle.pick_hits(position);
+            puzzle.resolve_pick(scored, &mut game_timer);
+            if !scored {
+                log.tick(puzzle.rules.hold_seconds());
+            }
+            puzzle.generate_colors(&mut seed.rng());
+        }
+        log.tick(gap);
+
+        (log, puzzle.get_score())
+    }
+
+    #[test]
+    fn a_replay_reaches_the_score_the_run_did() {
+        for game_mode in [GameMode::Infinite, GameMode::Mosaic, GameMode::Memory, GameMode::Custom] {
+            let (log, score) = play(game_mode, 7, 8, 0.8);
+
+            let mut puzzle = ColorPuzzle::default();
+            let mut game_timer = GameTimer::default();
+            let mut history = GameHistory::default();
+            let mut power_ups = PowerUps::default();
+            let mut run = RunState {
+                puzzle: &mut puzzle,
+                game_timer: &mut game_timer,
+                history: &mut history,
+                power_ups: &mut power_ups,
+            };
+
+            let mut replay = Replay::start(log, &mut run);
+            let mut steps = Vec::new();
+            // Uneven frames, so actions land mid-frame and several share one.
+            for frame in 0..400 {
+                steps.extend(replay.advance(0.01 + (frame % 7) as f32 * 0.013, &mut run));
+            }
+
+            assert!(replay.is_finished(), "{:?} never finished", game_mode);
+            assert_eq!(steps.last(), Some(&ReplayStep::End));
+            assert_eq!(run.puzzle.get_score(), score, "{:?}", game_mode);
+            assert_eq!(run.history.levels_played, 8);
+            assert_eq!(
+                steps
+                    .iter()
+                    .filter(|step| matches!(step, ReplayStep::Pick { scored: false, .. }))
+                    .count(),
+                1
+            );
+        }
+    }
+}
@@ -0,0 +1,56 @@
+//! Watching a finished run again.
+//!
+//! Opened from the summary, or from a share code on the challenge screen. The
+//! board, the ground's sweep and the HUD are the game's own, driven from a
+//! `RunLog` instead of from input; see `Replay` for how the log becomes a run
+//! again.
+
+pub mod components;
+mod styles;
+mod systems;
+
+use components::{Replay, ReplaySource, SetAsideRun};
+use systems::interactions::*;
+use systems::layout::*;
+use systems::playback::*;
+
+use crate::AppState;
+use bevy::prelude::*;
+
+pub struct ReplayPlugin;
+
+/// Winds the run back to the log's start on entering the replay.
+///
+/// The HUD is built on the same transition and orders itself after this set,
+/// so it is sized for the run being replayed rather than the one that ended.
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ReplaySet;
+
+impl Plugin for ReplayPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Replay>()
+            .init_resource::<SetAsideRun>()
+            .init_resource::<ReplaySource>()
+            // OnEnter State Systems. The controls read the replay's speed, so
+            // they are built after it has been started.
+            .add_systems(
+                OnEnter(AppState::Replay),
+                (start_replay.in_set(ReplaySet), spawn_replay_controls).chain(),
+            )
+            .add_systems(
+                Update,
+                (
+                    drive_replay,
+                    sync_replay_memory,
+                    interact_with_speed_button,
+                    interact_with_replay_back_button,
+                )
+                    .run_if(in_state(AppState::Replay)),
+            )
+            // OnExit State Systems
+            .add_systems(
+                OnExit(AppState::Replay),
+                (finish_replay, despawn_replay_controls),
+            );
+    }
+}
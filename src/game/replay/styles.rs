@@ -0,0 +1,32 @@
+use bevy::prelude::*;
+
+use crate::theme;
+
+/// Fills the window and parks the controls along its bottom edge, over the
+/// board's lower margin rather than over the HUD.
+pub fn replay_controls_root_style() -> Node {
+    Node {
+        position_type: PositionType::Absolute,
+        width: Val::Percent(100.0),
+        height: Val::Percent(100.0),
+        flex_direction: FlexDirection::Column,
+        justify_content: JustifyContent::FlexEnd,
+        align_items: AlignItems::Center,
+        padding: UiRect::all(Val::Px(theme::SPACE_MD)),
+        ..Node::DEFAULT
+    }
+}
+
+pub fn replay_controls_row_style() -> Node {
+    Node {
+        flex_direction: FlexDirection::Row,
+        justify_content: JustifyContent::Center,
+        align_items: AlignItems::Center,
+        column_gap: Val::Px(theme::SPACE_SM),
+        ..Node::DEFAULT
+    }
+}
+
+/// Two of these and their margins fit across the narrowest window the game
+/// allows, 320px.
+pub const REPLAY_BUTTON_WIDTH: f32 = 132.0;
@@ -0,0 +1,54 @@
+use bevy::prelude::*;
+
+use crate::events::TransitionToStateEvent;
+use crate::game::replay::components::*;
+use crate::theme;
+
+/// Flips between 1x and 2x. Takes effect from the next frame: the replay's
+/// clock is moved on by frame time times the speed, so nothing that has
+/// already happened is replayed differently.
+pub fn interact_with_speed_button(
+    mut button_query: Query<
+        (&Interaction, &mut BackgroundColor),
+        (Changed<Interaction>, With<ReplaySpeedButton>),
+    >,
+    mut label_query: Query<&mut Text, With<ReplaySpeedLabel>>,
+    mut replay: ResMut<Replay>,
+) {
+    for (interaction, mut background_color) in button_query.iter_mut() {
+        match *interaction {
+            Interaction::Pressed => {
+                *background_color = theme::BUTTON_PRESSED.into();
+                replay.speed = replay.speed.toggled();
+                for mut text in label_query.iter_mut() {
+                    text.0 = replay.speed.label().to_string();
+                }
+            }
+            Interaction::Hovered => *background_color = theme::BUTTON_HOVERED.into(),
+            Interaction::None => *background_color = theme::BUTTON.into(),
+        }
+    }
+}
+
+/// Back to the screen the replay was opened from.
+pub fn interact_with_replay_back_button(
+    mut button_query: Query<
+        (&Interaction, &mut BackgroundColor),
+        (Changed<Interaction>, With<ReplayBackButton>),
+    >,
+    source: Res<ReplaySource>,
+    mut transition_to_state_event_writer: MessageWriter<TransitionToStateEvent>,
+) {
+    for (interaction, mut background_color) in button_query.iter_mut() {
+        match *interaction {
+            Interaction::Pressed => {
+                *background_color = theme::BUTTON_PRIMARY_PRESSED.into();
+                transition_to_state_event_writer.write(TransitionToStateEvent {
+                    state: source.back_to,
+                });
+            }
+            Interaction::Hovered => *background_color = theme::BUTTON_PRIMARY_HOVERED.into(),
+            Interaction::None => *background_color = theme::BUTTON_PRIMARY.into(),
+        }
+    }
+}
@@ -0,0 +1,67 @@
+use bevy::prelude::*;
+
+use crate::game::replay::components::*;
+use crate::game::replay::styles::*;
+use crate::theme;
+
+/// The speed toggle and the way out. Everything else on screen is the game's
+/// own board and HUD, driven by the log.
+pub fn spawn_replay_controls(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    replay: Res<Replay>,
+) {
+    commands
+        .spawn((replay_controls_root_style(), ReplayControls))
+        .with_children(|parent| {
+            parent
+                .spawn(replay_controls_row_style())
+                .with_children(|parent| {
+                    parent
+                        .spawn((
+                            (
+                                Button,
+                                theme::button_style(REPLAY_BUTTON_WIDTH),
+                                BackgroundColor(theme::BUTTON),
+                            ),
+                            ReplaySpeedButton,
+                        ))
+                        .with_children(|parent| {
+                            parent.spawn((
+                                theme::wrapped_text(
+                                    replay.speed.label(),
+                                    theme::text_button(&asset_server),
+                                    theme::button_text_width(REPLAY_BUTTON_WIDTH),
+                                ),
+                                ReplaySpeedLabel,
+                            ));
+                        });
+
+                    parent
+                        .spawn((
+                            (
+                                Button,
+                                theme::button_style(REPLAY_BUTTON_WIDTH),
+                                BackgroundColor(theme::BUTTON_PRIMARY),
+                            ),
+                            ReplayBackButton,
+                        ))
+                        .with_children(|parent| {
+                            parent.spawn(theme::wrapped_text(
+                                "VOLTAR",
+                                theme::text_button(&asset_server),
+                                theme::button_text_width(REPLAY_BUTTON_WIDTH),
+                            ));
+                        });
+                });
+        });
+}
+
+pub fn despawn_replay_controls(
+    mut commands: Commands,
+    query: Query<Entity, With<ReplayControls>>,
+) {
+    for entity in query.iter() {
+        commands.entity(entity).despawn();
+    }
+}
@@ -0,0 +1,207 @@
+//! Drives the board, the ground and the HUD from a `Replay`.
+//!
+//! The replay itself decides what happens and when; everything here is about
+//! showing it the way the live game did — the same `spawn_board`, the same
+//! sweep, the same pick effects and banners — so the player watches their run
+//! and not an approximation of it.
+
+use bevy::camera::ClearColorConfig;
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+use bevy_prototype_lyon::prelude::*;
+
+use crate::events::InteractionAnimationEvent;
+use crate::feedback::BannerEvent;
+use crate::game::puzzle::components::{
+    ColorPuzzle, GameHistory, GameTimer, PieceTurnedEvent, PowerUp, PowerUps, PuzzleColor,
+};
+use crate::game::puzzle::{eliminate_wrong_groups, spawn_board};
+use crate::game::replay::components::*;
+use crate::systems::BackgroundTranstion;
+use crate::theme;
+
+/// The resources a run moves, taken together so they can be lent to the
+/// replay as one `RunState`.
+#[derive(SystemParam)]
+pub struct ReplayRun<'w> {
+    puzzle: ResMut<'w, ColorPuzzle>,
+    game_timer: ResMut<'w, GameTimer>,
+    history: ResMut<'w, GameHistory>,
+    power_ups: ResMut<'w, PowerUps>,
+}
+
+impl ReplayRun<'_> {
+    fn state(&mut self) -> RunState<'_> {
+        RunState {
+            puzzle: &mut self.puzzle,
+            game_timer: &mut self.game_timer,
+            history: &mut self.history,
+            power_ups: &mut self.power_ups,
+        }
+    }
+
+    fn snapshot(&self) -> (ColorPuzzle, GameTimer, GameHistory, PowerUps) {
+        (
+            self.puzzle.clone(),
+            self.game_timer.clone(),
+            self.history.clone(),
+            *self.power_ups,
+        )
+    }
+}
+
+/// Sets the finished run aside and winds the resources back to the start of
+/// the one being replayed.
+pub fn start_replay(
+    source: Res<ReplaySource>,
+    mut replay: ResMut<Replay>,
+    mut set_aside: ResMut<SetAsideRun>,
+    mut run: ReplayRun,
+) {
+    set_aside.run = Some(run.snapshot());
+    *replay = Replay::start(source.log.clone(), &mut run.state());
+}
+
+/// Moves the replay on by a frame and draws what happened in it.
+pub fn drive_replay(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut replay: ResMut<Replay>,
+    mut run: ReplayRun,
+    board: Query<Entity, With<PuzzleColor>>,
+    mut shapes: Query<(&PuzzleColor, &mut Shape)>,
+    mut camera_query: Query<(&mut Camera, &mut BackgroundTranstion), With<Camera2d>>,
+    mut animation: MessageWriter<InteractionAnimationEvent>,
+    mut banner: MessageWriter<BannerEvent>,
+    mut turned: MessageWriter<PieceTurnedEvent>,
+    // An elimination logged in the same frame as a deal has to wait for the
+    // new board's entities, which do not exist until the commands are applied.
+    mut pending_elimination: Local<bool>,
+) {
+    if replay.is_finished() {
+        return;
+    }
+
+    if *pending_elimination {
+        *pending_elimination = false;
+        let ground = run.puzzle.background_color();
+        eliminate_wrong_groups(ground, &mut shapes);
+    }
+
+    let speed = replay.speed.factor();
+    let steps = replay.advance(time.delta_secs() * speed, &mut run.state());
+
+    let mut dealt = false;
+    for step in steps {
+        match step {
+            ReplayStep::Deal { from } => {
+                // Recursive: a mosaic cell owns the nodes its piece is drawn from.
+                for entity in board.iter() {
+                    commands.entity(entity).despawn();
+                }
+                spawn_board(&mut commands, &mut run.puzzle);
+                dealt = true;
+
+                if let Ok((mut camera, mut background_transition)) = camera_query.single_mut() {
+                    // The sweep runs at the replay's speed, so at 2x it still
+                    // lands before the next pick does.
+                    background_transition.sweep(
+                        from,
+                        run.puzzle.sweep(),
+                        run.puzzle.rules.transition_seconds / speed,
+                    );
+                    camera.clear_color = ClearColorConfig::Custom(from);
+                }
+            }
+            ReplayStep::Pick {
+                position,
+                scored,
+                outcome,
+                answer,
+            } => {
+                let (correct_position, correct_corners) = match answer {
+                    Some((centre, corners)) => (Some(centre), corners),
+                    None => (None, Vec::new()),
+                };
+                animation.write(InteractionAnimationEvent {
+                    position,
+                    scored,
+                    bonus_seconds: outcome.bonus_seconds,
+                    correct_position,
+                    correct_corners,
+                });
+
+                if outcome.leveled_up {
+                    let text = if outcome.gained_life {
+                        format!("NIVEL {}  +1 VIDA", run.puzzle.level())
+                    } else {
+                        format!("NIVEL {}", run.puzzle.level())
+                    };
+                    banner.write(BannerEvent::large(text, theme::ACCENT));
+                }
+            }
+            ReplayStep::Turn { index, tile } => {
+                turned.write(PieceTurnedEvent { index, tile });
+            }
+            ReplayStep::Granted(kind) => {
+                banner.write(BannerEvent::power_up(kind.label()));
+            }
+            ReplayStep::Spent(PowerUp::ExtraLife) => {
+                banner.write(BannerEvent::power_up("+1 VIDA"));
+            }
+            ReplayStep::Spent(PowerUp::EliminateWrong) => {
+                if dealt {
+                    *pending_elimination = true;
+                } else {
+                    let ground = run.puzzle.background_color();
+                    eliminate_wrong_groups(ground, &mut shapes);
+                }
+                banner.write(BannerEvent::power_up("DESCARTADOS"));
+            }
+            ReplayStep::End => {
+                banner.write(BannerEvent::large("FIM DO REPLAY", theme::MUTED));
+            }
+        }
+    }
+}
+
+/// Blanks and restores a `Memory` board as the replay's clock says it was.
+///
+/// Repaints only when that changes, as `hide_memory_board` does: a board that
+/// has had groups ruled out keeps them ruled out until it is hidden.
+pub fn sync_replay_memory(
+    replay: Res<Replay>,
+    puzzle: Res<ColorPuzzle>,
+    mut board: Query<(&PuzzleColor, &mut Shape)>,
+    mut was_hidden: Local<bool>,
+) {
+    let hidden = replay.hides_board(&puzzle);
+    if hidden == *was_hidden {
+        return;
+    }
+    *was_hidden = hidden;
+
+    for (piece, mut shape) in board.iter_mut() {
+        let fill = if hidden { puzzle.hidden_color() } else { piece.color };
+        shape.fill = Some(Fill::color(fill));
+    }
+}
+
+/// Clears the replayed board and puts the finished run back.
+pub fn finish_replay(
+    mut commands: Commands,
+    board: Query<Entity, With<PuzzleColor>>,
+    mut set_aside: ResMut<SetAsideRun>,
+    mut run: ReplayRun,
+) {
+    for entity in board.iter() {
+        commands.entity(entity).despawn();
+    }
+
+    if let Some((puzzle, game_timer, history, power_ups)) = set_aside.run.take() {
+        *run.puzzle = puzzle;
+        *run.game_timer = game_timer;
+        *run.history = history;
+        *run.power_ups = power_ups;
+    }
+}
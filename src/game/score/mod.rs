@@ -28,6 +28,7 @@ impl Plugin for ScorePlugin {
         app.init_resource::<LastRunOutcome>()
             .init_resource::<BestScores>()
             .init_resource::<SavedRun>()
+            .init_resource::<DailyChallenge>()
             .add_systems(Startup, load_best_scores)
             .add_systems(Update, remember_run.run_if(in_state(AppState::Game)))
             .add_systems(
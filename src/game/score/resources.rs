@@ -1,10 +1,10 @@
 use bevy::prelude::*;
 
 use crate::game::puzzle::components::{GameMode, PowerUp, PowerUps};
-use crate::storage;
+use crate::storage::{self, StorageBackend};
 
 const STORAGE_KEY: &str = "color_puzzle.best_scores";
-const RUN_KEY: &str = "color_puzzle.saved_run";
+const DAILY_KEY: &str = "color_puzzle.daily";
 
 /// Best score per mode, persisted where the platform allows it.
 ///
@@ -19,6 +19,9 @@ pub struct BestScores {
     time_trial: usize,
     memory: usize,
     mosaic: usize,
+    daily: usize,
+    adaptive: usize,
+    repair: usize,
 }
 
 impl BestScores {
@@ -29,6 +32,11 @@ impl BestScores {
             GameMode::TimeTrial => self.time_trial,
             GameMode::Memory => self.memory,
             GameMode::Mosaic => self.mosaic,
+            GameMode::Daily => self.daily,
+            GameMode::Adaptive => self.adaptive,
+            GameMode::Repair => self.repair,
+            // Rules dialled by hand are not one table to be best at.
+            GameMode::Custom => 0,
         }
     }
 
@@ -39,6 +47,10 @@ impl BestScores {
             GameMode::TimeTrial => self.time_trial = value,
             GameMode::Memory => self.memory = value,
             GameMode::Mosaic => self.mosaic = value,
+            GameMode::Daily => self.daily = value,
+            GameMode::Adaptive => self.adaptive = value,
+            GameMode::Repair => self.repair = value,
+            GameMode::Custom => {}
         }
     }
 
@@ -61,7 +73,12 @@ impl BestScores {
     }
 
     pub fn load() -> Self {
-        storage::load(STORAGE_KEY)
+        Self::load_from(storage::backend())
+    }
+
+    pub fn load_from(store: &dyn StorageBackend) -> Self {
+        store
+            .load(STORAGE_KEY)
             .map(|raw| Self::deserialize(&raw))
             .unwrap_or_default()
     }
@@ -103,6 +120,122 @@ pub struct LastRunOutcome {
     pub score: usize,
     pub best: usize,
     pub is_record: bool,
+    /// Where the day stands, when the run was a daily one.
+    pub daily: Option<DailyResult>,
+    /// The verified score of the share code the run was played against.
+    pub challenge: Option<usize>,
+}
+
+/// The day's scored attempt, as the game-over screen reports it.
+#[derive(Debug, Clone, Copy)]
+pub struct DailyResult {
+    pub day: u64,
+    /// What the day's one scored attempt made.
+    pub score: usize,
+    /// Whether the run that just ended was that attempt, rather than a replay
+    /// of boards the player had already seen.
+    pub counted: bool,
+}
+
+/// Today's daily challenge: whether it has been attempted, and what it scored.
+///
+/// **The attempt is spent when the run starts, not when it ends.** Counting it
+/// at the end would let a player close the tab on a bad start and go again on
+/// boards they now know. The score is written as the run goes (see
+/// `remember_run`), so a tab closed halfway keeps the points reached rather than
+/// a zero.
+///
+/// Only the latest day is stored. There is no history to show, and the best
+/// over all days already lives in `BestScores` like every other mode's.
+#[derive(Resource, Debug, Default)]
+pub struct DailyChallenge {
+    /// The day the stored attempt belongs to, if there is one.
+    day: Option<u64>,
+    score: usize,
+    /// Whether the latest daily run is the day's scored attempt. Not stored: a
+    /// run cannot outlive the session that started it.
+    ///
+    /// Left as it is when the run ends, and only replaced by the next `begin`.
+    /// The summary screen is entered again on the way back from the history
+    /// list, and the outcome is recorded again with it; clearing this on the
+    /// first pass would report the day's scored run as a replay on the second.
+    scoring: bool,
+}
+
+impl DailyChallenge {
+    /// What `day`'s scored attempt made, or `None` if it has not been played.
+    pub fn result_for(&self, day: u64) -> Option<usize> {
+        (self.day == Some(day)).then_some(self.score)
+    }
+
+    /// Starts a daily run on `day`. Returns whether it is the scored attempt;
+    /// every later run that day is a replay and counts for nothing.
+    pub fn begin(&mut self, day: u64) -> bool {
+        self.scoring = self.day != Some(day);
+
+        if self.scoring {
+            self.day = Some(day);
+            self.score = 0;
+            self.persist();
+        }
+
+        self.scoring
+    }
+
+    /// Keeps the scored attempt's score up to date. Ignored for a replay.
+    pub fn record(&mut self, score: usize) {
+        if !self.scoring || score == self.score {
+            return;
+        }
+
+        self.score = score;
+        self.persist();
+    }
+
+    /// Says where the day stands once a run has ended.
+    pub fn finish(&mut self, score: usize) -> DailyResult {
+        self.record(score);
+
+        DailyResult {
+            day: self.day.unwrap_or_default(),
+            score: self.score,
+            counted: self.scoring,
+        }
+    }
+
+    fn persist(&self) {
+        if let Some(day) = self.day {
+            storage::save(DAILY_KEY, &format!("day={};score={}", day, self.score));
+        }
+    }
+
+    pub fn load() -> Self {
+        Self::load_from(storage::backend())
+    }
+
+    pub fn load_from(store: &dyn StorageBackend) -> Self {
+        let mut daily = Self::default();
+        let Some(raw) = store.load(DAILY_KEY) else {
+            return daily;
+        };
+
+        for entry in raw.split(';') {
+            let Some((key, value)) = entry.split_once('=') else {
+                continue;
+            };
+            let Ok(value) = value.trim().parse::<u64>() else {
+                continue;
+            };
+
+            match key.trim() {
+                "day" => daily.day = Some(value),
+                "score" => daily.score = value as usize,
+                _ => {}
+            }
+        }
+
+        daily
+    }
 }
 
 /// Where a stored run had got to.
@@ -135,6 +268,8 @@ pub struct SavedRun {
 }
 
 impl SavedRun {
+    pub const STORAGE_KEY: &'static str = "color_puzzle.saved_run";
+
     /// The stored run for one mode, if there is one to come back to.
     pub fn get(&self, game_mode: GameMode) -> Option<RunProgress> {
         self.runs
@@ -152,6 +287,10 @@ impl SavedRun {
     /// to, so it clears that mode's slot instead — otherwise the menu would
     /// offer to resume a run the player never started scoring in.
     pub fn store(&mut self, game_mode: GameMode, score: usize, lives: usize, power_ups: PowerUps) {
+        if !game_mode.is_resumable() {
+            return;
+        }
+
         if score == 0 {
             self.clear(game_mode);
             return;
@@ -187,7 +326,7 @@ impl SavedRun {
     }
 
     fn persist(&self) {
-        storage::save(RUN_KEY, &self.serialize());
+        storage::save(Self::STORAGE_KEY, &self.serialize());
     }
 
     /// `mode=score:lives:life=n,cut=n` entries separated by `;`.
@@ -197,32 +336,35 @@ impl SavedRun {
     fn serialize(&self) -> String {
         self.runs
             .iter()
-            .map(|run| {
-                let power_ups = PowerUp::iter()
-                    .map(|kind| format!("{}={}", kind.storage_key(), run.power_ups.count(kind)))
-                    .collect::<Vec<_>>()
-                    .join(",");
-
-                format!(
-                    "{}={}:{}:{}",
-                    run.game_mode.storage_key(),
-                    run.score,
-                    run.lives,
-                    power_ups
-                )
-            })
+            .map(Self::serialize_entry)
             .collect::<Vec<_>>()
             .join(";")
     }
 
+    fn serialize_entry(run: &RunProgress) -> String {
+        let power_ups = PowerUp::iter()
+            .map(|kind| format!("{}={}", kind.storage_key(), run.power_ups.count(kind)))
+            .collect::<Vec<_>>()
+            .join(",");
+
+        format!(
+            "{}={}:{}:{}",
+            run.game_mode.storage_key(),
+            run.score,
+            run.lives,
+            power_ups
+        )
+    }
+
     pub fn load() -> Self {
-        let Some(raw) = storage::load(RUN_KEY) else {
+        Self::load_from(storage::backend())
+    }
+
+    pub fn load_from(store: &dyn StorageBackend) -> Self {
+        let Some(raw) = store.load(Self::STORAGE_KEY) else {
             return Self::default();
         };
 
-        // Splitting on `;` is what makes this backwards compatible for free: a
-        // save written when there was only one slot is simply a one-entry list,
-        // and the optional tails below cover the fields it predates.
         let runs = raw
             .split(';')
             .filter_map(|entry| Self::parse_entry(entry.trim()))
@@ -231,17 +373,27 @@ impl SavedRun {
         Self { runs }
     }
 
+    /// Reads one entry in the shape `serialize_entry` writes. The shapes older
+    /// builds wrote are rewritten into this one by `migrate_v1` before anything
+    /// is read, so a missing field here is a damaged entry and is dropped.
     fn parse_entry(entry: &str) -> Option<RunProgress> {
         let (key, value) = entry.split_once('=')?;
 
-        // `score`, `score:lives`, or `score:lives:life=n,cut=n` — each tail was
-        // added later, and a save missing one is read as the friendlier of the
-        // two readings rather than discarded.
-        let mut parts = value.trim().split(':');
+        let mut parts = value.trim().splitn(3, ':');
         let score = parts.next()?.trim().parse::<usize>().ok()?;
-        let lives = parts.next();
-        let power_ups = parts.next();
+        let lives = parts.next()?.trim().parse::<usize>().ok()?;
+        let power_ups = Self::parse_power_ups(parts.next()?);
+
+        Self::progress(key, score, Some(lives), power_ups)
+    }
 
+    /// Checks a stored run can still be played, and builds it.
+    fn progress(
+        key: &str,
+        score: usize,
+        lives: Option<usize>,
+        power_ups: PowerUps,
+    ) -> Option<RunProgress> {
         if score == 0 {
             return None;
         }
@@ -249,40 +401,66 @@ impl SavedRun {
         // An unknown mode key means the save came from a build that had a mode
         // this one does not. Dropping it beats resuming into the wrong game.
         let game_mode = GameMode::iter().find(|mode| mode.storage_key() == key.trim())?;
+        if !game_mode.is_resumable() {
+            return None;
+        }
 
         let full = game_mode.starting_lives().unwrap_or(0);
-        let lives = lives
-            .and_then(|lives| lives.trim().parse::<usize>().ok())
-            .unwrap_or(full)
-            .min(full);
+        let lives = lives.unwrap_or(full).min(full);
 
         // A stored run with no lives left was already lost; see `store`.
         if full > 0 && lives == 0 {
             return None;
         }
 
-        let mut counts = PowerUps::default();
-        if let Some(raw) = power_ups {
-            for field in raw.split(',') {
-                let Some((kind, count)) = field.split_once('=') else {
-                    continue;
-                };
-                let Ok(count) = count.trim().parse::<usize>() else {
-                    continue;
-                };
-                if let Some(kind) = PowerUp::iter().find(|k| k.storage_key() == kind.trim()) {
-                    for _ in 0..count {
-                        counts.grant(kind);
-                    }
-                }
-            }
-        }
-
         Some(RunProgress {
             game_mode,
             score,
             lives,
-            power_ups: counts,
+            power_ups,
         })
     }
+
+    fn parse_power_ups(raw: &str) -> PowerUps {
+        let mut counts = PowerUps::default();
+        for field in raw.split(',') {
+            let Some((kind, count)) = field.split_once('=') else {
+                continue;
+            };
+            let Ok(count) = count.trim().parse::<usize>() else {
+                continue;
+            };
+            if let Some(kind) = PowerUp::iter().find(|k| k.storage_key() == kind.trim()) {
+                for _ in 0..count {
+                    counts.grant(kind);
+                }
+            }
+        }
+        counts
+    }
+
+    /// Rewrites a value stored before the schema had a version.
+    ///
+    /// It was a single slot once, then a `;` list, and each entry was `score`,
+    /// then `score:lives`, then `score:lives:life=n,cut=n`. A missing tail is
+    /// read as the friendlier of the two readings — full lives, nothing in hand
+    /// — rather than discarded. An entry that cannot be read at all is dropped,
+    /// which is what the loader did with it anyway.
+    pub fn migrate_v1(raw: &str) -> String {
+        raw.split(';')
+            .filter_map(|entry| {
+                let (key, value) = entry.trim().split_once('=')?;
+                let mut parts = value.trim().split(':');
+                let score = parts.next()?.trim().parse::<usize>().ok()?;
+                let lives = parts
+                    .next()
+                    .and_then(|lives| lives.trim().parse::<usize>().ok());
+                let power_ups = parts.next().map(Self::parse_power_ups).unwrap_or_default();
+
+                Self::progress(key, score, lives, power_ups)
+            })
+            .map(|run| Self::serialize_entry(&run))
+            .collect::<Vec<_>>()
+            .join(";")
+    }
 }
@@ -1,13 +1,20 @@
 use bevy::prelude::*;
 
 use super::resources::*;
+use crate::game::challenge::Challenge;
+use crate::game::practice::Practice;
 use crate::game::puzzle::components::{ColorPuzzle, GameHistory, PowerUps};
 
 /// Populates the already-initialised resource rather than inserting it, so no
 /// system can observe a frame where `BestScores` does not exist yet.
-pub fn load_best_scores(mut best_scores: ResMut<BestScores>, mut saved_run: ResMut<SavedRun>) {
+pub fn load_best_scores(
+    mut best_scores: ResMut<BestScores>,
+    mut saved_run: ResMut<SavedRun>,
+    mut daily: ResMut<DailyChallenge>,
+) {
     *best_scores = BestScores::load();
     *saved_run = SavedRun::load();
+    *daily = DailyChallenge::load();
 }
 
 /// Keeps the stored run in step with the one being played.
@@ -21,8 +28,18 @@ pub fn remember_run(
     puzzle: Res<ColorPuzzle>,
     power_ups: Res<PowerUps>,
     mut saved_run: ResMut<SavedRun>,
+    mut daily: ResMut<DailyChallenge>,
+    challenge: Res<Challenge>,
+    practice: Res<Practice>,
     mut last: Local<Option<(usize, usize, PowerUps)>>,
 ) {
+    // A run on someone else's boards is not one to come back to, and not the
+    // day's attempt even when the code is a daily one. A practice run is not
+    // a run at all: its score is the level it was put at.
+    if challenge.is_playing() || practice.is_playing() {
+        return;
+    }
+
     let progress = (puzzle.get_score(), puzzle.lives(), *power_ups);
 
     if *last == Some(progress) {
@@ -31,6 +48,10 @@ pub fn remember_run(
 
     *last = Some(progress);
     saved_run.store(puzzle.game_mode, progress.0, progress.1, progress.2);
+
+    if puzzle.game_mode.is_daily() {
+        daily.record(progress.0);
+    }
 }
 
 /// Called once as a run ends. Stores the result and works out whether it was a
@@ -40,15 +61,36 @@ pub fn record_run_outcome(
     mut best_scores: ResMut<BestScores>,
     mut outcome: ResMut<LastRunOutcome>,
     mut saved_run: ResMut<SavedRun>,
+    mut daily: ResMut<DailyChallenge>,
+    challenge: Res<Challenge>,
 ) {
     let score = game_history.total_score;
     let mode = game_history.game_mode;
 
+    // Played against a share code: the only comparison that means anything is
+    // with the code. Nothing is stored, since nothing was stored for it.
+    if let Some(target) = challenge.target() {
+        outcome.score = score;
+        outcome.best = best_scores.get(mode);
+        outcome.is_record = false;
+        outcome.daily = None;
+        outcome.challenge = Some(target);
+        return;
+    }
+    outcome.challenge = None;
+
     // This run is over, so there is nothing left to come back to — but only
     // this one. The other modes keep whatever they had.
     saved_run.clear(mode);
 
-    let is_record = best_scores.submit(mode, score);
+    // A replay of the day's boards is played on boards the player has already
+    // seen, so it goes nowhere near the best: it would be a record for memory.
+    outcome.daily = mode.is_daily().then(|| daily.finish(score));
+    let counted = outcome.daily.is_none_or(|result| result.counted);
+
+    // A custom run keeps no best: rules dialled by hand make every run its
+    // own table, and one set easy enough would top it forever.
+    let is_record = counted && !mode.is_custom() && best_scores.submit(mode, score);
 
     outcome.score = score;
     outcome.best = best_scores.get(mode);
@@ -5,6 +5,7 @@ use crate::game::puzzle::components::ColorPuzzle;
 use crate::game::puzzle::components::GameHistory;
 use crate::game::puzzle::components::GameTimer;
 use crate::game::puzzle::components::RenderLevelHistoryEvent;
+use crate::game::practice::Practice;
 use crate::game::ui::game_history_menu::components::*;
 use crate::game::ui::game_history_menu::styles::*;
 use crate::game::ui::game_history_menu::SpawnPaginationEvent;
@@ -75,7 +76,7 @@ pub fn interact_with_continue_button(
                 // An Infinite run has no clock to expire, so it always resumes.
                 // Testing the timer alone used to send it to the game-over
                 // screen, because a zero-length timer reads as finished.
-                let run_is_over = puzzle.game_mode.is_timed() && game_timer.timer.is_finished();
+                let run_is_over = puzzle.is_timed() && game_timer.timer.is_finished();
 
                 transition_to_state_event_writer.write(TransitionToStateEvent {
                     state: if run_is_over {
@@ -92,6 +93,9 @@ pub fn interact_with_continue_button(
 }
 
 /// Ends the run deliberately and goes to the summary.
+///
+/// A practice run has no summary to go to, since it has no score, and goes
+/// back to the practice screen, where its accuracy is.
 pub fn interact_with_end_run_button(
     mut button_query: Query<
         (&Interaction, &mut BackgroundColor),
@@ -101,12 +105,21 @@ pub fn interact_with_end_run_button(
     puzzle: Res<ColorPuzzle>,
     game_timer: Res<GameTimer>,
     mut game_history: ResMut<GameHistory>,
+    mut practice: ResMut<Practice>,
 ) {
     for (interaction, mut color) in button_query.iter_mut() {
         match *interaction {
             Interaction::Pressed => {
                 *color = theme::BUTTON_DANGER_PRESSED.into();
 
+                if practice.is_playing() {
+                    practice.leave();
+                    transition_to_state_event_writer.write(TransitionToStateEvent {
+                        state: AppState::Practice,
+                    });
+                    continue;
+                }
+
                 // Record what the summary needs, the same way the timer-expiry
                 // path does.
                 game_history.set_game_mode(puzzle.game_mode);
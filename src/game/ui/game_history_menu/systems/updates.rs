@@ -88,9 +88,16 @@ pub fn spawn_pagination_itens(
                         ));
 
                         // "OK"/"X" rather than a check mark: the display font
-                        // has no glyph for one, and it would render blank.
+                        // has no glyph for one, and it would render blank. A
+                        // `Repair` board is always finished, so it shows the
+                        // turns it took against its par instead.
+                        let mark = match level.turns {
+                            Some(turns) => format!("{}/{}", turns.moves, turns.par),
+                            None if scored => "OK".to_string(),
+                            None => "X".to_string(),
+                        };
                         parent.spawn(theme::wrapped_text(
-                            if scored { "OK" } else { "X" },
+                            mark,
                             theme::text(
                                 &asset_server,
                                 theme::TEXT_SM,
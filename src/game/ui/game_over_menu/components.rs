@@ -9,6 +9,10 @@ pub struct MainMenuButton;
 #[derive(Component)]
 pub struct GameOverHistoryButton;
 
+/// Plays the run back from its log.
+#[derive(Component)]
+pub struct GameOverReplayButton;
+
 /// The primary action on the end-of-run screen.
 #[derive(Component)]
 pub struct PlayAgainButton;
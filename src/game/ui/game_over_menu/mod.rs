@@ -29,6 +29,7 @@ impl Plugin for GameOverMenuPlugin {
                 (
                     interact_with_play_again_button,
                     interact_with_history_button,
+                    interact_with_replay_button,
                     interact_with_main_menu_button,
                     interact_with_share_button,
                 )
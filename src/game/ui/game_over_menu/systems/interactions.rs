@@ -1,9 +1,11 @@
 use bevy::prelude::*;
 
 use crate::events::TransitionToStateEvent;
-use crate::game::puzzle::components::{level_for_score, GameHistory, NewGameEvent};
+use crate::feedback::BannerEvent;
+use crate::game::puzzle::components::{level_for_score, GameHistory, NewGameEvent, RunLog};
+use crate::game::replay::components::ReplaySource;
 use crate::game::score::resources::LastRunOutcome;
-use crate::storage;
+use crate::{clipboard, share_code, storage, theme};
 use crate::game::ui::game_over_menu::components::*;
 use crate::game::ui::game_over_menu::styles::*;
 use crate::AppState;
@@ -57,6 +59,34 @@ pub fn interact_with_history_button(
     }
 }
 
+/// Opens the replay of the run that just ended.
+pub fn interact_with_replay_button(
+    mut button_query: Query<
+        (&Interaction, &mut BackgroundColor),
+        (Changed<Interaction>, With<GameOverReplayButton>),
+    >,
+    run_log: Res<RunLog>,
+    mut replay_source: ResMut<ReplaySource>,
+    mut transition_to_state_event_writer: MessageWriter<TransitionToStateEvent>,
+) {
+    for (interaction, mut color) in button_query.iter_mut() {
+        match *interaction {
+            Interaction::Pressed => {
+                *color = BUTTON_PRESSED.into();
+                *replay_source = ReplaySource {
+                    log: run_log.clone(),
+                    back_to: AppState::GameOverResume,
+                };
+                transition_to_state_event_writer.write(TransitionToStateEvent {
+                    state: AppState::Replay,
+                });
+            }
+            Interaction::Hovered => *color = BUTTON_HOVERED.into(),
+            Interaction::None => *color = BUTTON.into(),
+        }
+    }
+}
+
 pub fn interact_with_main_menu_button(
     mut button_query: Query<
         (&Interaction, &mut BackgroundColor),
@@ -104,6 +134,10 @@ pub fn interact_with_game_over_resume_button(
 /// A counter rides along on the key so two shares of the same score still read
 /// as two separate requests. Without it the second press writes an identical
 /// value, the storage event never fires, and the button appears dead.
+///
+/// The run's share code goes along when it has one, so whoever sees the score
+/// can paste it in and check it. On the desktop, where there is no page to
+/// share from, the code is put on the clipboard instead.
 pub fn interact_with_share_button(
     mut button_query: Query<
         (&Interaction, &mut BackgroundColor),
@@ -111,6 +145,8 @@ pub fn interact_with_share_button(
     >,
     outcome: Res<LastRunOutcome>,
     game_history: Res<GameHistory>,
+    run_log: Res<RunLog>,
+    mut banner: MessageWriter<BannerEvent>,
     mut requests: Local<usize>,
 ) {
     for (interaction, mut background_color) in button_query.iter_mut() {
@@ -119,18 +155,41 @@ pub fn interact_with_share_button(
                 *background_color = BUTTON_PRESSED.into();
                 *requests += 1;
 
+                // A daily card carries its date: the picture is how the day's
+                // scores get compared, and without it two days look alike.
+                let mode = match outcome.daily {
+                    Some(daily) => format!(
+                        "{} {}",
+                        game_history.game_mode.as_str(),
+                        crate::clock::date_label(daily.day)
+                    ),
+                    None => game_history.game_mode.as_str().to_string(),
+                };
+
                 // `key=value` pairs, the same shape every other stored value
                 // here uses.
-                let payload = format!(
+                let mut payload = format!(
                     "n={};mode={};score={};best={};record={};level={};streak={}",
                     *requests,
-                    game_history.game_mode.as_str(),
+                    mode,
                     outcome.score,
                     outcome.best,
                     usize::from(outcome.is_record),
                     level_for_score(outcome.score),
                     game_history.max_streak,
                 );
+                if game_history.game_mode.is_repair() {
+                    let turns = game_history.turns;
+                    payload.push_str(&format!(";turns={}/{}", turns.moves, turns.par));
+                }
+
+                // A continued run has no code: its log starts partway in.
+                if let Some(code) = share_code::encode(&run_log, outcome.score) {
+                    payload.push_str(&format!(";code={}", code));
+                    if clipboard::copy(&code) {
+                        banner.write(BannerEvent::notice("CODIGO COPIADO", theme::SUCCESS));
+                    }
+                }
 
                 storage::save("color_puzzle.share_request", &payload);
             }
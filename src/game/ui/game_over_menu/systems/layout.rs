@@ -10,6 +10,7 @@ use bevy::prelude::*;
 use crate::feedback::{PopAnim, RevealIn};
 use crate::game::puzzle::components::GameHistory;
 use crate::game::puzzle::components::GameMode;
+use crate::game::puzzle::components::RunLog;
 use crate::game::score::resources::LastRunOutcome;
 use crate::game::ui::game_over_menu::components::*;
 use crate::game::ui::game_over_menu::styles::*;
@@ -20,6 +21,7 @@ pub fn spawn_game_over_menu(
     asset_server: Res<AssetServer>,
     game_history: Res<GameHistory>,
     outcome: Res<LastRunOutcome>,
+    run_log: Res<RunLog>,
     window_query: Query<&Window>,
 ) {
     let width = window_query
@@ -27,7 +29,14 @@ pub fn spawn_game_over_menu(
         .map(|window| theme::content_width(window.width()))
         .unwrap_or(theme::CONTENT_MAX_WIDTH);
 
-    build_game_over_menu(&mut commands, &asset_server, &game_history, &outcome, width);
+    build_game_over_menu(
+        &mut commands,
+        &asset_server,
+        &game_history,
+        &outcome,
+        run_log.has_picks(),
+        width,
+    );
 }
 
 pub fn build_game_over_menu(
@@ -35,6 +44,7 @@ pub fn build_game_over_menu(
     asset_server: &Res<AssetServer>,
     game_history: &Res<GameHistory>,
     outcome: &Res<LastRunOutcome>,
+    has_replay: bool,
     width: f32,
 ) -> Entity {
     let text_width = theme::button_text_width(width);
@@ -78,8 +88,24 @@ pub fn build_game_over_menu(
                     // Either a celebration or a target. Never nothing: an
                     // end screen with no comparison gives the player no reason
                     // to go again.
-                    let (record_text, record_color) = if outcome.is_record {
+                    //
+                    // A replay of the day's boards has neither: it is played on
+                    // boards already seen, so it says that instead, as does a
+                    // custom run, which keeps no best. A run against a share
+                    // code is compared with the code.
+                    let replay = outcome.daily.is_some_and(|daily| !daily.counted);
+                    let (record_text, record_color) = if let Some(target) = outcome.challenge {
+                        if outcome.score > target {
+                            (format!("VENCEU O DESAFIO DE {}", target), theme::ACCENT)
+                        } else {
+                            (format!("DESAFIO: {}", target), theme::MUTED)
+                        }
+                    } else if outcome.is_record {
                         ("NOVO RECORDE!".to_string(), theme::ACCENT)
+                    } else if replay {
+                        ("REPETICAO - NAO CONTA".to_string(), theme::MUTED)
+                    } else if game_history.game_mode.is_custom() {
+                        ("PARTIDA PERSONALIZADA - SEM RECORDE".to_string(), theme::MUTED)
                     } else {
                         (format!("RECORDE {}", outcome.best), theme::MUTED)
                     };
@@ -90,12 +116,34 @@ pub fn build_game_over_menu(
                     ));
 
                     let mut rows = vec![
-                        ("DESAFIOS", format!("{}", game_history.levels_played)),
-                        ("MAIOR SEQUENCIA", format!("{}", game_history.max_streak)),
+                        ("DESAFIOS".to_string(), format!("{}", game_history.levels_played)),
+                        ("MAIOR SEQUENCIA".to_string(), format!("{}", game_history.max_streak)),
                     ];
 
+                    // The number the day is compared on, with the date, so two
+                    // screens held side by side show they played the same day.
+                    if let Some(daily) = outcome.daily {
+                        rows.insert(
+                            0,
+                            (
+                                format!("DIA {}", crate::clock::date_label(daily.day)),
+                                format!("{}", daily.score),
+                            ),
+                        );
+                    }
+
                     if game_history.game_mode == GameMode::TimeTrial {
-                        rows.push(("TEMPO TOTAL", game_history.get_formatted_time()));
+                        rows.push(("TEMPO TOTAL".to_string(), game_history.get_formatted_time()));
+                    }
+
+                    // The score counts boards however they were mended, so
+                    // the turns they took are set against their par here.
+                    if game_history.game_mode.is_repair() {
+                        let turns = game_history.turns;
+                        rows.push((
+                            "GIROS / PAR".to_string(),
+                            format!("{} / {}", turns.moves, turns.par),
+                        ));
                     }
 
                     for (index, (label, value)) in rows.into_iter().enumerate() {
@@ -141,6 +189,19 @@ pub fn build_game_over_menu(
                         BUTTON,
                         ShareScoreButton,
                     );
+                    // Only when there is something to watch: a run ended
+                    // before its first pick has no log worth opening.
+                    if has_replay {
+                        spawn_button(
+                            parent,
+                            asset_server,
+                            "VER REPLAY",
+                            button_style(width),
+                            text_width,
+                            BUTTON,
+                            GameOverReplayButton,
+                        );
+                    }
                     spawn_button(
                         parent,
                         asset_server,
@@ -195,6 +256,7 @@ pub fn relayout_game_over_menu(
     asset_server: Res<AssetServer>,
     game_history: Res<GameHistory>,
     outcome: Res<LastRunOutcome>,
+    run_log: Res<RunLog>,
     window_query: Query<&Window>,
 ) {
     if relayout_events.read().next().is_none() {
@@ -214,6 +276,7 @@ pub fn relayout_game_over_menu(
         &asset_server,
         &game_history,
         &outcome,
+        run_log.has_picks(),
         theme::content_width(window.width()),
     );
 }
@@ -7,7 +7,7 @@ use crate::game::ui::hud::systems::interactions::{
     interact_with_power_up_buttons,
 };
 use crate::game::ui::hud::systems::layout::{
-    despawn_back_button, despawn_hud, spawn_back_button, spawn_hud,
+    despawn_back_button, despawn_hud, spawn_back_button, spawn_hud, spawn_replay_hud,
 };
 use crate::game::ui::hud::systems::updates::{
     update_level_progress, update_lives_pips, update_power_up_buttons, update_score_text,
@@ -24,27 +24,39 @@ impl Plugin for HudPlugin {
             // OnEnter Systems
             .add_systems(OnEnter(AppState::Game), spawn_hud)
             .add_systems(OnEnter(AppState::LevelHistory), spawn_back_button)
+            // After the replay has wound the run back, so the markers are
+            // built for the lives it started with.
+            .add_systems(
+                OnEnter(AppState::Replay),
+                spawn_replay_hud.after(crate::game::replay::ReplaySet),
+            )
             // Systems
             .add_systems(
                 Update,
                 interact_with_history_back_button.run_if(in_state(AppState::LevelHistory)),
             )
+            .add_systems(
+                Update,
+                (interact_with_pause_button, interact_with_power_up_buttons)
+                    .run_if(in_state(AppState::Game)),
+            )
+            // The replay moves the same resources a run does, so the same
+            // updates show it.
             .add_systems(
                 Update,
                 (
-                    interact_with_pause_button,
                     update_score_text,
                     update_streak_text,
                     update_timer_text,
                     update_lives_pips,
                     update_level_progress,
                     update_power_up_buttons,
-                    interact_with_power_up_buttons,
                 )
-                    .run_if(in_state(AppState::Game)),
+                    .run_if(in_state(AppState::Game).or_else(in_state(AppState::Replay))),
             )
             // OnExit Systems
             .add_systems(OnExit(AppState::Game), despawn_hud)
+            .add_systems(OnExit(AppState::Replay), despawn_hud)
             .add_systems(OnExit(AppState::LevelHistory), despawn_back_button);
     }
 }
@@ -12,21 +12,40 @@ use crate::game::ui::hud::components::*;
 use crate::game::ui::hud::styles::*;
 use crate::theme;
 
+/// In practice the lives are left off, since none are spent, and the score's
+/// place goes to the level's accuracy.
 pub fn spawn_hud(
     mut commands: Commands,
     asset_server: Res<AssetServer>,
     puzzle: Res<ColorPuzzle>,
 ) {
-    build_hud(&mut commands, &asset_server, puzzle.max_lives());
+    if puzzle.is_pinned() {
+        build_hud(&mut commands, &asset_server, 0, "ACERTO", true);
+    } else {
+        build_hud(&mut commands, &asset_server, puzzle.max_lives(), "PONTOS", true);
+    }
+}
+
+/// The HUD over a replay: everything but the pause button, since there is no
+/// run to pause.
+pub fn spawn_replay_hud(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    puzzle: Res<ColorPuzzle>,
+) {
+    build_hud(&mut commands, &asset_server, puzzle.max_lives(), "PONTOS", false);
 }
 
 /// `lives` is the mode's full complement, and zero in a timed mode — the row of
 /// markers is built once, at its final length, because the number of lives a
-/// run can hold never changes mid-run.
+/// run can hold never changes mid-run. `score_label` names the first stat, and
+/// `pausable` is false over a replay.
 pub fn build_hud(
     commands: &mut Commands,
     asset_server: &Res<AssetServer>,
     lives: usize,
+    score_label: &str,
+    pausable: bool,
 ) -> Entity {
     commands
         .spawn((
@@ -45,7 +64,7 @@ pub fn build_hud(
                             spawn_stat::<ScoreValueText>(
                                 parent,
                                 asset_server,
-                                "PONTOS",
+                                score_label,
                                 "0",
                                 theme::PRIMARY,
                             );
@@ -72,6 +91,9 @@ pub fn build_hud(
                             // Pause. Previously an unpositioned, unsized
                             // transparent button; now a real, thumb-sized
                             // target.
+                            if !pausable {
+                                return;
+                            }
                             parent
                                 .spawn((
                                     (Button, icon_button_style(), BackgroundColor(BUTTON)),
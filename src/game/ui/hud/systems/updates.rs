@@ -7,6 +7,7 @@
 use bevy::prelude::*;
 
 use crate::feedback::PopAnim;
+use crate::game::practice::Practice;
 use crate::game::puzzle::components::{ColorPuzzle, GameHistory, GameTimer, PowerUp, PowerUps};
 use crate::game::ui::hud::components::*;
 use crate::game::ui::hud::styles::{BUTTON, LIVES_PIP_SPENT_COLOR, POWER_UP_EMPTY_COLOR};
@@ -34,6 +35,7 @@ const MAX_COUNT_SECONDS: f32 = 0.4;
 pub fn update_score_text(
     mut commands: Commands,
     puzzle: Res<ColorPuzzle>,
+    practice: Res<Practice>,
     time: Res<Time>,
     mut displayed: Local<f32>,
     mut last_target: Local<usize>,
@@ -45,6 +47,22 @@ pub fn update_score_text(
         return;
     };
 
+    // A practice run's score is only where its level starts, and counting up
+    // to it would celebrate points nobody scored. The stat shows the level's
+    // accuracy instead, which is what practice is for.
+    if practice.is_playing() {
+        let wanted = match practice.current().percent() {
+            Some(percent) => format!("{percent}%"),
+            None => "--".to_string(),
+        };
+        if text.0 != wanted {
+            text.0 = wanted;
+        }
+        *displayed = target as f32;
+        *last_target = target;
+        return;
+    }
+
     if target != *last_target {
         // Jumping down means a new run started; don't count backwards.
         if target < *last_target {
@@ -110,7 +128,7 @@ pub fn update_timer_text(
         return;
     };
 
-    if !puzzle.game_mode.is_timed() {
+    if !puzzle.is_timed() || puzzle.is_pinned() {
         text.0 = "--".to_string();
         text_color.0 = theme::MUTED;
         return;
@@ -191,9 +209,35 @@ pub fn update_lives_pips(
 
 pub fn update_level_progress(
     puzzle: Res<ColorPuzzle>,
+    practice: Res<Practice>,
     mut fill_query: Query<&mut Node, With<LevelProgressFill>>,
     mut level_query: Query<&mut Text, With<LevelValueText>>,
 ) {
+    // In practice there is no next level to approach, so the row names the
+    // level being practised and the picks behind the accuracy beside it.
+    if practice.is_playing() {
+        if let Ok(mut style) = fill_query.single_mut() {
+            style.width = Val::Percent(0.0);
+        }
+        if let Ok(mut text) = level_query.single_mut() {
+            let tally = practice.current();
+            let mut wanted = format!(
+                "TREINO NIVEL {}   {}/{}",
+                puzzle.level(),
+                tally.hits,
+                tally.picks
+            );
+            if puzzle.game_mode.is_adaptive() {
+                let decimals = puzzle.metric.decimals();
+                wanted.push_str(&format!("   DELTA {:.*}", decimals, puzzle.color_delta()));
+            }
+            if text.0 != wanted {
+                text.0 = wanted;
+            }
+        }
+        return;
+    }
+
     if let Ok(mut style) = fill_query.single_mut() {
         style.width = Val::Percent(puzzle.progress_to_next_level() * 100.0);
     }
@@ -201,11 +245,27 @@ pub fn update_level_progress(
     if let Ok(mut text) = level_query.single_mut() {
         // Naming the remaining distance is what turns a bar into a goal. There
         // is no last level any more, so there is no "MAXIMO" case to fall to.
-        text.0 = format!(
+        let mut wanted = format!(
             "NIVEL {}   FALTAM {}",
             puzzle.level(),
             puzzle.points_to_next_level()
         );
+        // In `Adaptive` the level only sizes the board, and the number that
+        // says how hard the round is is the delta. Showing it is what lets
+        // the player see the stairs move, and know where they are holding.
+        if puzzle.game_mode.is_adaptive() {
+            let decimals = puzzle.metric.decimals();
+            wanted.push_str(&format!("   DELTA {:.*}", decimals, puzzle.color_delta()));
+        }
+        // In `Repair` the board's par is the number a turn is judged against:
+        // the first turn past it costs, so the player has to be able to see
+        // it coming.
+        if puzzle.game_mode.is_repair() {
+            wanted.push_str(&format!("   GIROS {}/{}", puzzle.moves(), puzzle.par()));
+        }
+        if text.0 != wanted {
+            text.0 = wanted;
+        }
     }
 }
 
@@ -35,11 +35,12 @@ impl Plugin for InteractionAnimationPlugin {
                 fade_answer_reveal,
                 handle_interaction_animation_events,
             )
-                .run_if(in_state(AppState::Game)),
+                .run_if(in_state(AppState::Game).or_else(in_state(AppState::Replay))),
         )
         // These animations are driven only while playing, so leaving the
         // state mid-animation would otherwise strand them on screen frozen.
-        .add_systems(OnExit(AppState::Game), despawn_effects);
+        .add_systems(OnExit(AppState::Game), despawn_effects)
+        .add_systems(OnExit(AppState::Replay), despawn_effects);
     }
 }
 
@@ -0,0 +1,304 @@
+//! The vision test: how small a colour difference the player can see.
+//!
+//! The game's rounds mix every kind of difference at once. The answer is
+//! pushed in whatever direction clears the other groups, so a score says how
+//! good someone is at the game and not what their eyes, or their screen, can
+//! resolve. The test takes the other variables away. Each board is four
+//! squares of one colour, one of them moved along a single Oklab axis, and
+//! the player says which. A staircase per axis (see `staircase`) brings the
+//! distance down to where they are right about seven times in ten, and where
+//! it settles is their just-noticeable difference along that axis.
+//!
+//! Three axes, because eyes and screens differ by direction. Lightness is
+//! what everyone resolves best, a weak red-green channel is the commonest
+//! colour deficiency, and blue-yellow is where a night-shift filter or a tired
+//! panel shows first. One number would average those into something that
+//! describes none of them.
+//!
+//! The axes take turns, so a lapse of attention is spread over all three
+//! rather than spoiling one, and the player cannot settle into looking for one
+//! kind of difference.
+//!
+//! Free of game state, like `staircase` and `fairness`, so it can be run
+//! against a simulated player in the tests.
+
+use bevy::prelude::Color;
+use rand::prelude::*;
+
+use crate::oklab::{self, Oklab};
+use crate::staircase::Staircase;
+
+/// Squares on a board. Four choices put a guess at one in four, far enough
+/// below the staircase's seven in ten that luck barely moves the estimate.
+pub const CELLS: usize = 4;
+
+/// Where every axis starts: a difference nobody misses, so the first boards
+/// show the player what kind of thing they are looking for.
+const CEILING: f32 = 0.08;
+/// About one step of an 8-bit channel in the mid tones. Below it the screen
+/// draws the odd square in the same colour as the rest, and the board has no
+/// answer at all.
+const FLOOR: f32 = 0.004;
+
+/// Reversals an axis needs before its estimate is trusted.
+const TARGET_REVERSALS: usize = 10;
+/// Boards an axis gets at most, so a player whose picks never settle still
+/// reaches the end of the test.
+const MAX_TRIALS: usize = 40;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Axis {
+    Lightness,
+    RedGreen,
+    BlueYellow,
+}
+
+impl Axis {
+    pub const ALL: [Axis; 3] = [Axis::Lightness, Axis::RedGreen, Axis::BlueYellow];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Axis::Lightness => "CLARO-ESCURO",
+            Axis::RedGreen => "VERMELHO-VERDE",
+            Axis::BlueYellow => "AZUL-AMARELO",
+        }
+    }
+
+    /// The unit direction in Oklab. `a` runs green to red and `b` blue to
+    /// yellow, so the opponent channels are the axes as they stand.
+    fn direction(&self) -> (f32, f32, f32) {
+        match self {
+            Axis::Lightness => (1.0, 0.0, 0.0),
+            Axis::RedGreen => (0.0, 1.0, 0.0),
+            Axis::BlueYellow => (0.0, 0.0, 1.0),
+        }
+    }
+
+    fn index(&self) -> usize {
+        match self {
+            Axis::Lightness => 0,
+            Axis::RedGreen => 1,
+            Axis::BlueYellow => 2,
+        }
+    }
+}
+
+/// A finished test: the smallest difference resolved along each axis, in
+/// Oklab units.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Thresholds {
+    pub lightness: f32,
+    pub red_green: f32,
+    pub blue_yellow: f32,
+}
+
+impl Thresholds {
+    pub fn get(&self, axis: Axis) -> f32 {
+        match axis {
+            Axis::Lightness => self.lightness,
+            Axis::RedGreen => self.red_green,
+            Axis::BlueYellow => self.blue_yellow,
+        }
+    }
+}
+
+/// One forced choice.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Board {
+    pub colors: Vec<Color>,
+    pub answer: usize,
+    pub axis: Axis,
+    /// How far the answer sits from the others.
+    pub delta: f32,
+}
+
+/// A test under way: a staircase per axis, and whose turn it is.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Calibration {
+    staircases: [Staircase; 3],
+    trials: [usize; 3],
+    turn: usize,
+}
+
+impl Default for Calibration {
+    fn default() -> Self {
+        Self {
+            staircases: [Staircase::new(FLOOR, CEILING); 3],
+            trials: [0; 3],
+            turn: 0,
+        }
+    }
+}
+
+impl Calibration {
+    /// Boards answered so far, over all three axes.
+    pub fn trials(&self) -> usize {
+        self.trials.iter().sum()
+    }
+
+    /// How far through the test, 0..1. Counts each axis as done at whichever
+    /// of its two stopping rules it is closer to, so the bar never runs
+    /// backwards.
+    pub fn progress(&self) -> f32 {
+        Axis::ALL
+            .iter()
+            .map(|axis| {
+                let i = axis.index();
+                let by_reversals =
+                    self.staircases[i].reversals() as f32 / TARGET_REVERSALS as f32;
+                let by_trials = self.trials[i] as f32 / MAX_TRIALS as f32;
+                by_reversals.max(by_trials).min(1.0)
+            })
+            .sum::<f32>()
+            / 3.0
+    }
+
+    fn axis_finished(&self, axis: Axis) -> bool {
+        let i = axis.index();
+        self.staircases[i].reversals() >= TARGET_REVERSALS || self.trials[i] >= MAX_TRIALS
+    }
+
+    pub fn is_finished(&self) -> bool {
+        Axis::ALL.iter().all(|axis| self.axis_finished(*axis))
+    }
+
+    /// The next board, on the next axis still measuring, or `None` once the
+    /// test is over.
+    pub fn deal(&mut self, rng: &mut impl Rng) -> Option<Board> {
+        let axis = (0..Axis::ALL.len())
+            .map(|offset| Axis::ALL[(self.turn + offset) % Axis::ALL.len()])
+            .find(|axis| !self.axis_finished(*axis))?;
+        self.turn = axis.index() + 1;
+
+        Some(deal_board(
+            axis,
+            self.staircases[axis.index()].delta(),
+            rng,
+        ))
+    }
+
+    /// Moves the board's axis on the player's pick. Returns whether it was
+    /// the answer.
+    pub fn record(&mut self, board: &Board, picked: usize) -> bool {
+        let correct = picked == board.answer;
+        let i = board.axis.index();
+        self.trials[i] += 1;
+        self.staircases[i].record(correct);
+        correct
+    }
+
+    /// The result, once the test is over. An axis that ran out of boards
+    /// before settling reports where it stood, which is the best guess there
+    /// is.
+    pub fn thresholds(&self) -> Option<Thresholds> {
+        if !self.is_finished() {
+            return None;
+        }
+        let estimate = |axis: Axis| {
+            let staircase = &self.staircases[axis.index()];
+            staircase.threshold().unwrap_or(staircase.delta())
+        };
+        Some(Thresholds {
+            lightness: estimate(Axis::Lightness),
+            red_green: estimate(Axis::RedGreen),
+            blue_yellow: estimate(Axis::BlueYellow),
+        })
+    }
+}
+
+/// Four squares of one colour, one of them moved `delta` along `axis`.
+///
+/// The base colour is drawn afresh for every board, muted and in the mid
+/// tones, where both it and the moved square can be shown. Otherwise the test
+/// would measure the player on one colour, and a threshold that only holds
+/// for grey-green is not the player's threshold.
+pub fn deal_board(axis: Axis, delta: f32, rng: &mut impl Rng) -> Board {
+    let sign = if rng.gen_bool(0.5) { 1.0 } else { -1.0 };
+
+    let pair = (0..32)
+        .map(|_| {
+            Oklab::from_lch(
+                rng.gen_range(0.5..0.75),
+                rng.gen_range(0.0..0.06),
+                rng.gen_range(0.0..std::f32::consts::TAU),
+            )
+        })
+        // A neutral mid grey can take a step of the ceiling in any
+        // direction, so the test never stalls on a board it cannot show.
+        .chain(std::iter::once(Oklab::new(0.62, 0.0, 0.0)))
+        .find_map(|base| {
+            let moved = base.offset(axis.direction(), delta * sign);
+            Some((oklab::to_color(base)?, oklab::to_color(moved)?))
+        });
+    let (base, moved) = pair.unwrap_or((Color::srgb(0.5, 0.5, 0.5), Color::srgb(0.5, 0.5, 0.5)));
+
+    let answer = rng.gen_range(0..CELLS);
+    let colors = (0..CELLS)
+        .map(|index| if index == answer { moved } else { base })
+        .collect();
+
+    Board {
+        colors,
+        answer,
+        axis,
+        delta,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A board differs along its axis and nowhere else.
+    #[test]
+    fn a_board_moves_one_square_along_one_axis() {
+        let mut rng = StdRng::seed_from_u64(3);
+        for axis in Axis::ALL {
+            let board = deal_board(axis, 0.03, &mut rng);
+            let base = oklab::from_color(board.colors[(board.answer + 1) % CELLS]);
+            let moved = oklab::from_color(board.colors[board.answer]);
+            let change = [moved.l - base.l, moved.a - base.a, moved.b - base.b];
+
+            for (index, value) in change.iter().enumerate() {
+                if index == axis.index() {
+                    assert!((value.abs() - 0.03).abs() < 2e-3, "{axis:?} moved {value}");
+                } else {
+                    assert!(value.abs() < 2e-3, "{axis:?} leaked {value} into {index}");
+                }
+            }
+        }
+    }
+
+    /// A simulated player who sees lightness down to 0.01, blue-yellow to
+    /// 0.03 and red-green only to 0.05, and guesses below that: the test
+    /// finishes, and ranks and places the three about where they are.
+    #[test]
+    fn a_simulated_player_gets_their_thresholds_back() {
+        let true_thresholds = Thresholds {
+            lightness: 0.01,
+            red_green: 0.05,
+            blue_yellow: 0.03,
+        };
+        let mut rng = StdRng::seed_from_u64(11);
+        let mut calibration = Calibration::default();
+
+        while let Some(board) = calibration.deal(&mut rng) {
+            let picked = if board.delta > true_thresholds.get(board.axis) {
+                board.answer
+            } else {
+                rng.gen_range(0..CELLS)
+            };
+            calibration.record(&board, picked);
+        }
+
+        assert!(calibration.trials() <= MAX_TRIALS * 3);
+        assert_eq!(calibration.progress(), 1.0);
+        let measured = calibration.thresholds().unwrap();
+        for axis in Axis::ALL {
+            let ratio = measured.get(axis) / true_thresholds.get(axis);
+            assert!((0.6..1.6).contains(&ratio), "{axis:?} measured {ratio}x");
+        }
+        assert!(measured.lightness < measured.blue_yellow);
+        assert!(measured.blue_yellow < measured.red_green);
+    }
+}
@@ -27,19 +27,68 @@ use interaction_animation::*;
 mod achievements_menu;
 use achievements_menu::AchievementsMenuPlugin;
 
+mod challenge_menu;
+use challenge_menu::ChallengeMenuPlugin;
+
+mod settings_menu;
+use settings_menu::SettingsMenuPlugin;
+
+mod practice_menu;
+use practice_menu::PracticeMenuPlugin;
+
+mod custom_menu;
+use custom_menu::CustomMenuPlugin;
+
+mod vision_test;
+use vision_test::VisionTestPlugin;
+
+mod vision_filter;
+use vision_filter::VisionFilterPlugin;
+
 mod audio;
+mod clipboard;
+mod clock;
+mod cvd;
+#[cfg(not(target_arch = "wasm32"))]
+mod difficulty_report;
+mod encoding;
+mod fairness;
+mod jnd;
+mod asset_file;
 mod board;
 mod layout;
+mod modes;
+mod share_code;
 mod mosaic_pattern;
 mod oklab;
+mod staircase;
 mod wfc;
 mod storage;
 mod theme;
+mod tile_sets;
 
 pub const PIXELS_PER_METER: f32 = 492.3;
 pub const RESOLUTION: f32 = 16.0 / 9.0;
 
 fn main() {
+    // A tuning tool rather than the game: deals boards headless and prints
+    // what came out. See `difficulty_report`.
+    #[cfg(not(target_arch = "wasm32"))]
+    match difficulty_report::Options::from_args(std::env::args()) {
+        Ok(Some(options)) => return difficulty_report::run(&options),
+        Ok(None) => {}
+        Err(error) => {
+            eprintln!("{}: {}", difficulty_report::FLAG, error);
+            std::process::exit(2);
+        }
+    }
+
+    // Before the window: a broken `assets/modes.ron` or `assets/tiles.ron`
+    // stops the game here, with the line it broke on, rather than at the
+    // first menu card or the first Mosaic round.
+    modes::table();
+    tile_sets::table();
+
     App::new()
         // DefaultPlugins comes *first*, and that order is load bearing:
         // `init_state` needs the `StateTransition` schedule, which arrives with
@@ -81,6 +130,12 @@ fn main() {
             audio::GameAudioPlugin,
             InteractionAnimationPlugin,
             AchievementsMenuPlugin,
+            ChallengeMenuPlugin,
+            SettingsMenuPlugin,
+            PracticeMenuPlugin,
+            CustomMenuPlugin,
+            VisionTestPlugin,
+            VisionFilterPlugin,
         ))
 
         // Startup Systems
@@ -108,4 +163,17 @@ pub enum AppState {
     GameOver,
     /// The goals list, reached from the main menu.
     Achievements,
+    /// A finished run played back from its log, reached from the summary.
+    Replay,
+    /// A share code pasted in from the main menu, verified and waiting to be
+    /// watched or played against.
+    Challenge,
+    /// Progress export and import, reached from the main menu.
+    Settings,
+    /// The colour discrimination test, reached from the settings screen.
+    VisionTest,
+    /// Level select for practice runs, reached from the main menu.
+    Practice,
+    /// The custom game builder, reached from the main menu.
+    Custom,
 }
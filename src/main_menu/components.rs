@@ -23,6 +23,22 @@ pub struct ContinueRunButton {
     pub power_ups: PowerUps,
 }
 
+/// Opens the custom game screen.
+#[derive(Component)]
+pub struct CustomGameButton;
+
 /// Opens the goals screen.
 #[derive(Component)]
 pub struct AchievementsButton;
+
+/// Reads a share code off the clipboard and opens it.
+#[derive(Component)]
+pub struct PasteCodeButton;
+
+/// Opens the practice screen.
+#[derive(Component)]
+pub struct PracticeButton;
+
+/// Opens the settings screen.
+#[derive(Component)]
+pub struct SettingsButton;
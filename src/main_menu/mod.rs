@@ -26,6 +26,10 @@ impl Plugin for MainMenuPlugin {
                     interact_with_play_button,
                     interact_with_continue_run_button,
                     interact_with_achievements_button,
+                    interact_with_paste_code_button,
+                    interact_with_practice_button,
+                    interact_with_custom_game_button,
+                    interact_with_settings_button,
                 )
                     .run_if(in_state(AppState::MainMenu)),
             )
@@ -112,3 +112,22 @@ pub fn get_mode_description_text_style(asset_server: &Res<AssetServer>) -> theme
 pub fn get_best_score_text_style(asset_server: &Res<AssetServer>) -> theme::TextStyle {
     theme::text(asset_server, theme::TEXT_XS, theme::ACCENT)
 }
+
+/// The row under the mode cards: the goals, practice, the share-code paste and
+/// the settings, side by side. One row rather than two so the cards keep the
+/// height they fit in.
+pub fn footer_row_style() -> Node {
+    Node {
+        flex_direction: FlexDirection::Row,
+        justify_content: JustifyContent::Center,
+        align_items: AlignItems::Center,
+        ..Node::DEFAULT
+    }
+}
+
+/// Width of each of `count` footer buttons, so the row, margins included,
+/// takes the room one full-width button would.
+pub fn footer_button_width(width: f32, count: usize) -> f32 {
+    let count = count.max(1) as f32;
+    (width - theme::SPACE_XS * 2.0 * (count - 1.0)) / count
+}
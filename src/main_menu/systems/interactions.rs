@@ -1,13 +1,16 @@
 use bevy::prelude::*;
 
 use crate::events::TransitionToStateEvent;
-use crate::game::puzzle::components::{ColorPuzzle, PowerUps};
+use crate::feedback::BannerEvent;
+use crate::game::challenge::Challenge;
+use crate::game::practice::Practice;
+use crate::game::puzzle::components::{ColorPuzzle, GameMode, PowerUps, RunLog, RunSeed};
 use crate::game::puzzle::components::GameHistory;
 use crate::main_menu::components::*;
 use crate::main_menu::styles::{card_border, card_border_hovered, card_border_pressed};
-use crate::game::score::resources::SavedRun;
+use crate::game::score::resources::{DailyChallenge, SavedRun};
 use crate::pagination::Pagination;
-use crate::AppState;
+use crate::{clipboard, share_code, theme, AppState};
 
 pub fn interact_with_play_button(
     // Iterated, not `get_single_mut`: the menu has one of these per mode, so a
@@ -22,6 +25,11 @@ pub fn interact_with_play_button(
     mut game_history: ResMut<GameHistory>,
     mut pagination: ResMut<Pagination>,
     mut power_ups: ResMut<PowerUps>,
+    mut run_seed: ResMut<RunSeed>,
+    mut daily: ResMut<DailyChallenge>,
+    mut run_log: ResMut<RunLog>,
+    mut challenge: ResMut<Challenge>,
+    mut practice: ResMut<Practice>,
 ) {
     for (interaction, mut background_color, play_button) in button_query.iter_mut() {
         // The card's border carries the mode's own color, so the feedback for
@@ -32,10 +40,17 @@ pub fn interact_with_play_button(
         match *interaction {
             Interaction::Pressed => {
                 *background_color = card_border_pressed(accent).into();
+                challenge.leave();
+                practice.leave();
                 puzzle.setup(&play_button.game_mode);
+                *run_seed = RunSeed::for_mode(play_button.game_mode);
+                if play_button.game_mode.is_daily() {
+                    daily.begin(crate::clock::today());
+                }
                 // A fresh run starts empty-handed: power-ups are earned inside
                 // one run and do not carry between them.
                 power_ups.clear();
+                run_log.start(&run_seed, &puzzle, *power_ups);
                 game_history.reset();
                 game_history.set_game_mode(play_button.game_mode);
                 pagination.reset();
@@ -66,6 +81,10 @@ pub fn interact_with_continue_run_button(
     mut pagination: ResMut<Pagination>,
     mut saved_run: ResMut<SavedRun>,
     mut power_ups: ResMut<PowerUps>,
+    mut run_seed: ResMut<RunSeed>,
+    mut run_log: ResMut<RunLog>,
+    mut challenge: ResMut<Challenge>,
+    mut practice: ResMut<Practice>,
 ) {
     for (interaction, mut background_color, button) in button_query.iter_mut() {
         let accent = button.game_mode.accent();
@@ -73,13 +92,19 @@ pub fn interact_with_continue_run_button(
         match *interaction {
             Interaction::Pressed => {
                 *background_color = card_border_pressed(accent).into();
+                challenge.leave();
+                practice.leave();
 
                 puzzle.setup(&button.game_mode);
+                // A new seed, not the stored run's: the seed belongs to the
+                // boards, and those are not what gets restored.
+                *run_seed = RunSeed::random();
                 puzzle.restore_score(button.score);
                 // After `setup`, which seeds a full complement: the run is
                 // picked up where it was left, lives included.
                 puzzle.restore_lives(button.lives);
                 *power_ups = button.power_ups;
+                run_log.start(&run_seed, &puzzle, *power_ups);
 
                 game_history.reset();
                 game_history.set_game_mode(button.game_mode);
@@ -129,3 +154,120 @@ pub fn interact_with_achievements_button(
         }
     }
 }
+
+/// Opens the practice screen.
+pub fn interact_with_practice_button(
+    mut button_query: Query<
+        (&Interaction, &mut BackgroundColor),
+        (Changed<Interaction>, With<PracticeButton>),
+    >,
+    mut transition_to_state_event_writer: MessageWriter<TransitionToStateEvent>,
+) {
+    for (interaction, mut background_color) in button_query.iter_mut() {
+        match *interaction {
+            Interaction::Pressed => {
+                *background_color = theme::SURFACE.into();
+                transition_to_state_event_writer.write(TransitionToStateEvent {
+                    state: AppState::Practice,
+                });
+            }
+            Interaction::Hovered => *background_color = theme::BUTTON_HOVERED.into(),
+            Interaction::None => *background_color = theme::SURFACE_RAISED.into(),
+        }
+    }
+}
+
+/// Opens the custom game screen. A card like the modes', so it answers a
+/// touch in its own colour the way they do.
+pub fn interact_with_custom_game_button(
+    mut button_query: Query<
+        (&Interaction, &mut BackgroundColor),
+        (Changed<Interaction>, With<CustomGameButton>),
+    >,
+    mut transition_to_state_event_writer: MessageWriter<TransitionToStateEvent>,
+) {
+    let accent = GameMode::Custom.accent();
+    for (interaction, mut background_color) in button_query.iter_mut() {
+        match *interaction {
+            Interaction::Pressed => {
+                *background_color = card_border_pressed(accent).into();
+                transition_to_state_event_writer.write(TransitionToStateEvent {
+                    state: AppState::Custom,
+                });
+            }
+            Interaction::Hovered => *background_color = card_border_hovered(accent).into(),
+            Interaction::None => *background_color = card_border(accent).into(),
+        }
+    }
+}
+
+/// Opens the settings screen.
+pub fn interact_with_settings_button(
+    mut button_query: Query<
+        (&Interaction, &mut BackgroundColor),
+        (Changed<Interaction>, With<SettingsButton>),
+    >,
+    mut transition_to_state_event_writer: MessageWriter<TransitionToStateEvent>,
+) {
+    for (interaction, mut background_color) in button_query.iter_mut() {
+        match *interaction {
+            Interaction::Pressed => {
+                *background_color = theme::SURFACE.into();
+                transition_to_state_event_writer.write(TransitionToStateEvent {
+                    state: AppState::Settings,
+                });
+            }
+            Interaction::Hovered => *background_color = theme::BUTTON_HOVERED.into(),
+            Interaction::None => *background_color = theme::SURFACE_RAISED.into(),
+        }
+    }
+}
+
+/// Opens a share code from the clipboard.
+///
+/// The code is played through before anything is shown: a code that does not
+/// reach the score it claims, or asks for a move the game would have refused,
+/// never gets as far as the challenge screen. What is wrong with it is said in
+/// a banner, and the menu stays where it is.
+pub fn interact_with_paste_code_button(
+    mut button_query: Query<
+        (&Interaction, &mut BackgroundColor),
+        (Changed<Interaction>, With<PasteCodeButton>),
+    >,
+    mut challenge: ResMut<Challenge>,
+    mut banner: MessageWriter<BannerEvent>,
+    mut transition_to_state_event_writer: MessageWriter<TransitionToStateEvent>,
+) {
+    for (interaction, mut background_color) in button_query.iter_mut() {
+        match *interaction {
+            Interaction::Pressed => {
+                *background_color = theme::SURFACE.into();
+
+                // Nothing pasted (an empty clipboard, a dismissed prompt) is
+                // not an error worth a banner.
+                let Some(text) = clipboard::paste("Cole o codigo do desafio")
+                    .filter(|text| !text.trim().is_empty())
+                else {
+                    continue;
+                };
+
+                let verified = share_code::decode(&text).and_then(|code| {
+                    share_code::verify(&code).map(|score| (code, score))
+                });
+                match verified {
+                    Ok((code, score)) => {
+                        challenge.open(code, score);
+                        transition_to_state_event_writer.write(TransitionToStateEvent {
+                            state: AppState::Challenge,
+                        });
+                    }
+                    Err(error) => {
+                        banner.write(BannerEvent::notice(error.message(), theme::DANGER));
+                    }
+                }
+            }
+            Interaction::Hovered => *background_color = theme::BUTTON_HOVERED.into(),
+            Interaction::None => *background_color = theme::SURFACE_RAISED.into(),
+        }
+    }
+}
@@ -2,7 +2,7 @@ use bevy::camera::ClearColorConfig;
 use bevy::prelude::*;
 
 use crate::game::puzzle::components::{level_for_score, GameMode};
-use crate::game::score::resources::{BestScores, SavedRun};
+use crate::game::score::resources::{BestScores, DailyChallenge, SavedRun};
 use crate::main_menu::components::*;
 use crate::main_menu::styles::*;
 use crate::systems::BackgroundTranstion;
@@ -13,6 +13,7 @@ pub fn spawn_main_menu(
     asset_server: Res<AssetServer>,
     best_scores: Res<BestScores>,
     saved_run: Res<SavedRun>,
+    daily: Res<DailyChallenge>,
     window_query: Query<&Window>,
 ) {
     // Cards are laid out against the real window width so their labels can be
@@ -22,7 +23,15 @@ pub fn spawn_main_menu(
         .map(|window| (theme::content_width(window.width()), window.height()))
         .unwrap_or((theme::CONTENT_MAX_WIDTH, 720.0));
 
-    build_main_menu(&mut commands, &asset_server, &best_scores, &saved_run, width, height);
+    build_main_menu(
+        &mut commands,
+        &asset_server,
+        &best_scores,
+        &saved_run,
+        &daily,
+        width,
+        height,
+    );
 }
 
 /// Puts the app's own background back after a run.
@@ -66,6 +75,7 @@ pub fn relayout_main_menu(
     asset_server: Res<AssetServer>,
     best_scores: Res<BestScores>,
     saved_run: Res<SavedRun>,
+    daily: Res<DailyChallenge>,
     window_query: Query<&Window>,
 ) {
     if relayout_events.read().next().is_none() {
@@ -85,6 +95,7 @@ pub fn relayout_main_menu(
         &asset_server,
         &best_scores,
         &saved_run,
+        &daily,
         theme::content_width(window.width()),
         window.height(),
     );
@@ -101,19 +112,23 @@ pub fn build_main_menu(
     asset_server: &Res<AssetServer>,
     best_scores: &Res<BestScores>,
     saved_run: &Res<SavedRun>,
+    daily: &Res<DailyChallenge>,
     width: f32,
     height: f32,
 ) -> Entity {
+    let today = crate::clock::today();
+
     // The card is a bordered wrapper around a padded row, so the text column has
     // the wrapper's padding and the chip's width taken off it.
     let text_width = mode_card_text_width(width);
     // One card per mode, always: a mode with a stored run resumes it from its
     // own card rather than from a separate one at the top, so the list has a
     // fixed length and the card the player reaches for does not move.
-    // The goals button is a row like the others as far as the fit is
-    // concerned, so it is counted here — otherwise the five cards claim the
-    // whole height and it lands off the bottom of a short screen.
-    let cards = GameMode::iter().count() + 1;
+    // The row of goals and paste buttons is a row like the others as far as
+    // the fit is concerned, so it is counted here — otherwise the five cards claim the
+    // whole height and it lands off the bottom of a short screen. So is the
+    // custom game's card, which is not a mode in the list.
+    let cards = GameMode::iter().count() + 2;
     let card_height = mode_card_height(height, cards);
     let chip_size = mode_chip_size(card_height);
 
@@ -146,11 +161,18 @@ pub fn build_main_menu(
                 let best = best_scores.get(game_mode);
                 let title = game_mode.as_str().to_uppercase();
 
-                match saved_run.get(game_mode) {
+                // Only the daily card has a day to have played.
+                let played_today = if game_mode.is_daily() {
+                    daily.result_for(today)
+                } else {
+                    None
+                };
+
+                match (saved_run.get(game_mode), played_today) {
                     // A mode with a run in progress leads with where that run
                     // got to. The player who left mid-run is here to finish it,
                     // and the level is what says how far in they were.
-                    Some(run) => {
+                    (Some(run), _) => {
                         // The lives are part of what is being come back to, so
                         // the card says how many are left rather than making
                         // the resumed run reveal it.
@@ -179,10 +201,27 @@ pub fn build_main_menu(
                             },
                         );
                     }
+                    // The day already played: what matters now is today's
+                    // number, and that another go will not change it.
+                    (None, Some(score)) => {
+                        spawn_card(
+                            parent,
+                            asset_server,
+                            game_mode.accent(),
+                            width,
+                            card_height,
+                            chip_size,
+                            text_width,
+                            &title,
+                            "REPETIR NAO CONTA.",
+                            Some(format!("HOJE: {} - RECORDE: {}", score, best)),
+                            PlayButton { game_mode },
+                        )
+                    }
                     // Nothing stored: say what the mode is before the player
                     // commits to it, and show the target, because the number to
                     // beat is what the run is for.
-                    None => spawn_card(
+                    (None, None) => spawn_card(
                         parent,
                         asset_server,
                         game_mode.accent(),
@@ -198,28 +237,68 @@ pub fn build_main_menu(
                 }
             }
 
+            // After the modes, as the one card that is every mode at once. It
+            // opens the builder rather than a run, and has no best to show.
+            let custom = GameMode::Custom;
+            spawn_card(
+                parent,
+                asset_server,
+                custom.accent(),
+                width,
+                card_height,
+                chip_size,
+                text_width,
+                &custom.as_str().to_uppercase(),
+                &custom.description().to_uppercase(),
+                None,
+                CustomGameButton,
+            );
+
             // Below the modes, not above: the list is what the player came for,
-            // and the goals are read between runs rather than instead of one.
-            parent
-                .spawn((
-                    (
-                        Button,
-                        theme::button_style(width),
-                        BackgroundColor(theme::SURFACE_RAISED),
-                    ),
-                    AchievementsButton,
-                ))
-                .with_children(|parent| {
-                    parent.spawn(theme::wrapped_text(
-                        "METAS",
-                        theme::text_button(asset_server),
-                        width,
-                    ));
-                });
+            // and the goals, practice, codes and settings are visited between
+            // runs rather than instead of one.
+            let quarter = footer_button_width(width, 4);
+            parent.spawn(footer_row_style()).with_children(|parent| {
+                spawn_footer_button(parent, asset_server, "METAS", quarter, AchievementsButton);
+                spawn_footer_button(parent, asset_server, "TREINO", quarter, PracticeButton);
+                spawn_footer_button(
+                    parent,
+                    asset_server,
+                    "COLAR CODIGO",
+                    quarter,
+                    PasteCodeButton,
+                );
+                spawn_footer_button(parent, asset_server, "AJUSTES", quarter, SettingsButton);
+            });
         })
         .id()
 }
 
+fn spawn_footer_button<M: Component>(
+    parent: &mut ChildSpawnerCommands,
+    asset_server: &Res<AssetServer>,
+    label: &str,
+    width: f32,
+    marker: M,
+) {
+    parent
+        .spawn((
+            (
+                Button,
+                theme::button_style(width),
+                BackgroundColor(theme::SURFACE_RAISED),
+            ),
+            marker,
+        ))
+        .with_children(|parent| {
+            parent.spawn(theme::wrapped_text(
+                label,
+                theme::text_button(asset_server),
+                theme::button_text_width(width),
+            ));
+        });
+}
+
 /// The wordmark, one section per letter of "PUZZLE".
 fn wordmark() -> Vec<(String, Color)> {
     let letters = [
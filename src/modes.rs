@@ -0,0 +1,246 @@
+//! The modes' definitions: what each is called, its colour, and its rules.
+//!
+//! These used to be a `match` per property on `GameMode`, six of them plus
+//! one in `ColorPuzzle::setup`, and a mode had to be remembered in each. The
+//! worst of it was `is_timed`: a mode left out of its list got a zero-length
+//! clock and lost its run on the first frame. They are now one table in
+//! `assets/modes.ron`, one entry per mode, and a mode with no clock simply
+//! says so.
+//!
+//! The file is built into the binary rather than fetched, since the browser
+//! build has no files to read and the menu asks for a mode's name before
+//! anything could have arrived. It is read once, the first time a mode is
+//! asked about, and `main` asks before the window opens, so a broken file
+//! stops the game at launch with the line it broke on rather than halfway
+//! through a run. `the_bundled_modes_are_valid` stops it before that.
+//!
+//! What a mode *does* (deal a mosaic, follow a staircase, deal the day's
+//! boards) is still code on `GameMode`, and so is the storage key. The key is
+//! a promise to every save on every device, so the file can only name keys
+//! the code already has, and must name every one of them.
+
+use std::sync::OnceLock;
+
+use bevy::prelude::Color;
+use serde::de::Error as _;
+use serde::{Deserialize, Deserializer};
+
+use crate::asset_file::{self, FileError};
+use crate::game::puzzle::components::GameMode;
+use crate::theme;
+
+const FILE: &str = "assets/modes.ron";
+
+const BUNDLED: &str = include_str!("../assets/modes.ron");
+
+/// One mode's entry. Whatever the file leaves out is zero, or off, except
+/// the transition, which is a second.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ModeDefinition {
+    pub name: String,
+    pub description: String,
+    #[serde(deserialize_with = "accent")]
+    pub accent: Color,
+    #[serde(default)]
+    pub timer_seconds: f32,
+    #[serde(default)]
+    pub seconds_added_per_success: f32,
+    #[serde(default = "one_second")]
+    pub transition_seconds: f32,
+    #[serde(default)]
+    pub lives: usize,
+    #[serde(default)]
+    pub miss_penalty_seconds: f32,
+    #[serde(default)]
+    pub hides_colors: bool,
+}
+
+fn one_second() -> f32 {
+    1.0
+}
+
+/// Every mode's definition, one for each, none missing.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ModeTable {
+    definitions: Vec<(GameMode, ModeDefinition)>,
+}
+
+impl ModeTable {
+    pub fn get(&self, mode: GameMode) -> &ModeDefinition {
+        self.definitions
+            .iter()
+            .find(|(defined, _)| *defined == mode)
+            .map(|(_, definition)| definition)
+            // `parse` refuses a table with a mode missing.
+            .expect("every mode has a definition")
+    }
+}
+
+static TABLE: OnceLock<ModeTable> = OnceLock::new();
+
+/// The bundled table. Panics on a broken file, with the reason; there is no
+/// game to fall back to without one.
+pub fn table() -> &'static ModeTable {
+    TABLE.get_or_init(|| parse(BUNDLED).unwrap_or_else(|error| panic!("{error}")))
+}
+
+pub fn definition(mode: GameMode) -> &'static ModeDefinition {
+    table().get(mode)
+}
+
+/// Every mode the code knows, `Custom` included, which `GameMode::iter`
+/// leaves out.
+fn known_modes() -> impl Iterator<Item = GameMode> {
+    GameMode::iter().chain([GameMode::Custom])
+}
+
+/// Checks what the file cannot say for itself: that the key is one the
+/// code has, and that the rules leave the run a way to end.
+fn check(key: &str, definition: &ModeDefinition) -> Result<GameMode, String> {
+    let Some(mode) = known_modes().find(|mode| mode.storage_key() == key) else {
+        return Err(format!(
+            "no mode has the key `{key}`; keys are saved, so a new one needs code first"
+        ));
+    };
+
+    if !definition.name.is_ascii() || !definition.description.is_ascii() {
+        return Err(format!("`{key}` is shown in a font with no accents"));
+    }
+
+    // The rule the file's header explains, and the bug it exists for.
+    let timed = definition.timer_seconds > 0.0;
+    let has_lives = definition.lives > 0;
+    if timed == has_lives {
+        return Err(format!("`{key}` needs a clock or lives, and not both"));
+    }
+    if !timed
+        && (definition.miss_penalty_seconds > 0.0 || definition.seconds_added_per_success > 0.0)
+    {
+        return Err(format!("`{key}` charges or pays seconds but has no clock"));
+    }
+
+    Ok(mode)
+}
+
+/// Reads an accent by the name the file gives it.
+fn accent<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Color, D::Error> {
+    let name = String::deserialize(deserializer)?;
+    accent_color(&name).ok_or_else(|| D::Error::custom(format!("`{name}` is not a theme colour")))
+}
+
+/// The theme colours a mode may wear, by the name the file uses.
+fn accent_color(name: &str) -> Option<Color> {
+    let color = match name {
+        "primary" => theme::PRIMARY,
+        "success" => theme::SUCCESS,
+        "lime" => theme::LIME,
+        "info" => theme::INFO,
+        "pink" => theme::PINK,
+        "accent" => theme::ACCENT,
+        "teal" => theme::TEAL,
+        "on_surface" => theme::ON_SURFACE,
+        _ => return None,
+    };
+    Some(color)
+}
+
+/// Reads the file. RON and serde do the reading, and refuse a field they do
+/// not know on the line it is on; `check` does the rest.
+pub fn parse(source: &str) -> Result<ModeTable, FileError> {
+    let error = |reason: String| FileError {
+        file: FILE,
+        line: 0,
+        reason,
+    };
+
+    let entries: Vec<(String, ModeDefinition)> = asset_file::deserialize(FILE, source)?;
+    let mut definitions: Vec<(GameMode, ModeDefinition)> = vec![];
+    for (key, definition) in entries {
+        let mode = check(&key, &definition).map_err(error)?;
+        if definitions.iter().any(|(defined, _)| *defined == mode) {
+            return Err(error(format!("`{key}` defined twice")));
+        }
+        definitions.push((mode, definition));
+    }
+
+    if let Some(missing) =
+        known_modes().find(|mode| definitions.iter().all(|(defined, _)| defined != mode))
+    {
+        return Err(error(format!(
+            "no definition for `{}`",
+            missing.storage_key()
+        )));
+    }
+
+    Ok(ModeTable { definitions })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn the_bundled_modes_are_valid() {
+        let table = parse(BUNDLED).unwrap_or_else(|error| panic!("{error}"));
+        for mode in known_modes() {
+            assert!(!table.get(mode).name.is_empty());
+        }
+    }
+
+    /// The keys are in every save. This is the list they were first written
+    /// with; a change here is a change to every player's progress.
+    #[test]
+    fn storage_keys_never_change() {
+        let keys: Vec<&str> = known_modes().map(|mode| mode.storage_key()).collect();
+        assert_eq!(
+            keys,
+            [
+                "infinite",
+                "against_the_clock",
+                "time_trial",
+                "memory",
+                "mosaic",
+                "daily",
+                "adaptive",
+                "repair",
+                "custom",
+            ]
+        );
+    }
+
+    #[test]
+    fn a_broken_file_is_refused_with_the_reason() {
+        let refuse = |source: String| parse(&source).unwrap_err();
+
+        // An unknown key, a renamed one among them.
+        let renamed = BUNDLED.replacen("(\"infinite\"", "(\"endless\"", 1);
+        assert!(refuse(renamed).reason.contains("`endless`"));
+
+        // A mode dropped from the file entirely.
+        let start = BUNDLED.find("    (\"adaptive\"").unwrap();
+        let end = BUNDLED.find("    (\"repair\"").unwrap();
+        let cut = format!("{}{}", &BUNDLED[..start], &BUNDLED[end..]);
+        assert!(refuse(cut).reason.contains("`adaptive`"));
+
+        // A clock taken away from a timed mode leaves it with no way to end:
+        // the zero-length timer the old list was there to prevent.
+        let stuck = BUNDLED.replacen("timer_seconds: 30.0", "timer_seconds: 0.0", 1);
+        assert!(refuse(stuck).reason.contains("clock or lives"));
+
+        // A misspelt field, which would otherwise quietly take its default,
+        // refused on the line it is on.
+        let typo = BUNDLED.replacen("hides_colors: true", "hide_colors: true", 1);
+        let line = BUNDLED
+            .lines()
+            .position(|line| line.contains("hides_colors: true"))
+            .unwrap()
+            + 1;
+        let error = refuse(typo);
+        assert_eq!(error.line, line);
+        assert!(error.reason.contains("hide_colors"));
+
+        let unknown_colour = BUNDLED.replacen("accent: \"teal\"", "accent: \"cyan\"", 1);
+        assert!(refuse(unknown_colour).reason.contains("`cyan`"));
+    }
+}
@@ -10,7 +10,9 @@
 //!
 //! Reference: Björn Ottosson, "A perceptual color space for image processing".
 
-use bevy::prelude::Color;
+use bevy::prelude::{Color, Reflect, Resource};
+
+use crate::storage::{self, StorageBackend};
 
 /// A color as lightness plus two opponent-color axes.
 ///
@@ -48,7 +50,7 @@ impl Oklab {
     }
 }
 
-fn srgb_to_linear(channel: f32) -> f32 {
+pub fn srgb_to_linear(channel: f32) -> f32 {
     if channel <= 0.04045 {
         channel / 12.92
     } else {
@@ -56,7 +58,7 @@ fn srgb_to_linear(channel: f32) -> f32 {
     }
 }
 
-fn linear_to_srgb(channel: f32) -> f32 {
+pub fn linear_to_srgb(channel: f32) -> f32 {
     if channel <= 0.0031308 {
         channel * 12.92
     } else {
@@ -64,11 +66,18 @@ fn linear_to_srgb(channel: f32) -> f32 {
     }
 }
 
+/// From any `Color`, including one a wide-gamut board encoded with channels
+/// past 0..1 (see [`Gamut::encode`]). Read through Bevy's own linear
+/// conversion, which is this file's transfer function inside 0..1 and leaves
+/// the extended values where they are rather than folding them back.
 pub fn from_color(color: Color) -> Oklab {
-    let r = srgb_to_linear(color.to_srgba().red);
-    let g = srgb_to_linear(color.to_srgba().green);
-    let b = srgb_to_linear(color.to_srgba().blue);
+    let linear = color.to_linear();
+    from_linear([linear.red, linear.green, linear.blue])
+}
 
+/// From linear-light RGB, the space colour blindness simulations are defined
+/// in. Values outside 0..1 are converted as they stand.
+pub fn from_linear([r, g, b]: [f32; 3]) -> Oklab {
     let long = 0.4122214708 * r + 0.5363325363 * g + 0.0514459929 * b;
     let medium = 0.2119034982 * r + 0.6806995451 * g + 0.1073969566 * b;
     let short = 0.0883024619 * r + 0.2817188376 * g + 0.6299787005 * b;
@@ -84,14 +93,9 @@ pub fn from_color(color: Color) -> Oklab {
     }
 }
 
-/// Converts back to sRGB, or `None` when the color is outside what the display
-/// can show.
-///
-/// Callers want the `None` rather than a clamp: clamping silently drags the
-/// color back toward the gamut boundary, which changes the very distance the
-/// difficulty is set by. A round built on a clamped color would be easier than
-/// its level claims.
-pub fn to_color(lab: Oklab) -> Option<Color> {
+/// To linear-light RGB, unclamped: a colour outside the display's gamut
+/// comes back with a channel below 0 or above 1.
+pub fn to_linear(lab: Oklab) -> [f32; 3] {
     let long = lab.l + 0.3963377774 * lab.a + 0.2158037573 * lab.b;
     let medium = lab.l - 0.1055613458 * lab.a - 0.0638541728 * lab.b;
     let short = lab.l - 0.0894841775 * lab.a - 1.2914855480 * lab.b;
@@ -100,27 +104,30 @@ pub fn to_color(lab: Oklab) -> Option<Color> {
     let medium = medium * medium * medium;
     let short = short * short * short;
 
-    let r = 4.0767416621 * long - 3.3077115913 * medium + 0.2309699292 * short;
-    let g = -1.2684380046 * long + 2.6097574011 * medium - 0.3413193965 * short;
-    let b = -0.0041960863 * long - 0.7034186147 * medium + 1.7076147010 * short;
-
-    // A hair of tolerance: values a rounding error outside the cube are the
-    // gamut boundary itself, not a color we should throw away.
-    const TOLERANCE: f32 = 0.0005;
-    for channel in [r, g, b] {
-        if channel < -TOLERANCE || channel > 1.0 + TOLERANCE {
-            return None;
-        }
-    }
+    [
+        4.0767416621 * long - 3.3077115913 * medium + 0.2309699292 * short,
+        -1.2684380046 * long + 2.6097574011 * medium - 0.3413193965 * short,
+        -0.0041960863 * long - 0.7034186147 * medium + 1.7076147010 * short,
+    ]
+}
 
-    Some(Color::srgb(
-        linear_to_srgb(r.clamp(0.0, 1.0)),
-        linear_to_srgb(g.clamp(0.0, 1.0)),
-        linear_to_srgb(b.clamp(0.0, 1.0)),
-    ))
+/// Converts back to sRGB, or `None` when the color is outside what the display
+/// can show.
+///
+/// Callers want the `None` rather than a clamp: clamping silently drags the
+/// color back toward the gamut boundary, which changes the very distance the
+/// difficulty is set by. A round built on a clamped color would be easier than
+/// its level claims. A colour that only has to be *some* colour near the one
+/// asked for goes through [`Gamut::to_displayable`] instead.
+pub fn to_color(lab: Oklab) -> Option<Color> {
+    Gamut::Srgb.to_color(lab)
 }
 
 /// Mixes two colors, `amount` of `b` into `a`, perceptually.
+///
+/// The mix is mapped into the narrowest gamut holding both ends, so a mix of
+/// two colours the screen showed is one it can show, and the ends themselves
+/// come back as they went in.
 pub fn mix(a: Color, b: Color, amount: f32) -> Color {
     let a_lab = from_color(a);
     let b_lab = from_color(b);
@@ -132,5 +139,645 @@ pub fn mix(a: Color, b: Color, amount: f32) -> Color {
         a_lab.b + (b_lab.b - a_lab.b) * amount,
     );
 
-    to_color(mixed).unwrap_or(a)
+    let gamut = Gamut::iter()
+        .find(|gamut| gamut.contains(a_lab) && gamut.contains(b_lab))
+        .unwrap_or(Gamut::DisplayP3);
+    gamut.to_displayable(mixed).1
+}
+
+// --- Wide gamut --------------------------------------------------------------
+//
+// Every board used to be dealt inside sRGB, the smallest gamut anything sold
+// in the last decade can show. Most phones and laptops show Display P3, which
+// holds about a quarter more chroma in the reds and greens, and at the high
+// levels, where the groups crowd together, chroma is the room the generators
+// spread them in. A P3 board gives every group more of it.
+//
+// A P3 colour is handed to Bevy as linear sRGB with the channels it needs
+// past 0..1, which is how a wide-gamut surface takes it. An sRGB surface
+// clips those channels, and a clipped board is not the board the fairness
+// check passed, so the game deals in P3 only when the window can show it.
+
+/// Linear sRGB to linear Display P3. Both are D65, so this is the primaries
+/// alone.
+const SRGB_TO_P3: [[f32; 3]; 3] = [
+    [0.8224621, 0.177538, 0.0],
+    [0.0331941, 0.9668058, 0.0],
+    [0.0170827, 0.0723974, 0.9105199],
+];
+
+const P3_TO_SRGB: [[f32; 3]; 3] = [
+    [1.2249401, -0.2249404, 0.0],
+    [-0.0420569, 1.0420571, 0.0],
+    [-0.0196376, -0.0786361, 1.0982735],
+];
+
+fn apply(matrix: [[f32; 3]; 3], [r, g, b]: [f32; 3]) -> [f32; 3] {
+    matrix.map(|row| row[0] * r + row[1] * g + row[2] * b)
+}
+
+/// The colours a board may be dealt in. A setting, persisted, and carried by
+/// every run's log, since the boards are dealt in it.
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Eq, Default, Reflect)]
+pub enum Gamut {
+    #[default]
+    Srgb,
+    DisplayP3,
+}
+
+const GAMUT_KEY: &str = "color_puzzle.gamut";
+
+impl Gamut {
+    /// Narrowest first.
+    pub fn iter() -> impl Iterator<Item = Gamut> {
+        [Gamut::Srgb, Gamut::DisplayP3].into_iter()
+    }
+
+    /// Stable key for storage and share codes. Never change these strings.
+    pub fn storage_key(&self) -> &'static str {
+        match self {
+            Gamut::Srgb => "srgb",
+            Gamut::DisplayP3 => "p3",
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Gamut::Srgb => "GAMA: SRGB",
+            Gamut::DisplayP3 => "GAMA: DISPLAY P3",
+        }
+    }
+
+    /// The label, and whether the game is dealing in sRGB instead because
+    /// the window cannot show this one.
+    pub fn setting_label(&self) -> String {
+        if self.on_surface() == *self {
+            self.label().to_string()
+        } else {
+            format!("{} (SEM SUPORTE)", self.label())
+        }
+    }
+
+    pub fn cycle(&mut self) {
+        let all: Vec<Gamut> = Self::iter().collect();
+        let at = all.iter().position(|gamut| gamut == self).unwrap_or(0);
+        *self = all[(at + 1) % all.len()];
+        storage::save(GAMUT_KEY, self.storage_key());
+    }
+
+    pub fn load() -> Self {
+        Self::load_from(storage::backend())
+    }
+
+    pub fn load_from(store: &dyn StorageBackend) -> Self {
+        store
+            .load(GAMUT_KEY)
+            .and_then(|key| Self::iter().find(|gamut| gamut.storage_key() == key.trim()))
+            .unwrap_or_default()
+    }
+
+    /// The widest gamut the window shows.
+    ///
+    /// sRGB, for now, everywhere. Bevy's renderer configures every window
+    /// surface as 8-bit sRGB (`Rgba8UnormSrgb` or `Bgra8UnormSrgb`) whatever
+    /// the display behind it could do, and asks for no canvas colour space in
+    /// the browser. This is the one place to say otherwise once it can.
+    pub fn surface() -> Gamut {
+        Gamut::Srgb
+    }
+
+    /// The gamut to deal in when this one is asked for: itself, or sRGB on a
+    /// surface too narrow for it.
+    pub fn on_surface(&self) -> Gamut {
+        if Self::surface() == Gamut::DisplayP3 {
+            *self
+        } else {
+            Gamut::Srgb
+        }
+    }
+
+    /// How much more chroma than in sRGB the generators may reach for. The
+    /// P3 figure is the median over hues of its edge's chroma against sRGB's,
+    /// at the lightnesses rounds are built on.
+    pub fn chroma_room(&self) -> f32 {
+        match self {
+            Gamut::Srgb => 1.0,
+            Gamut::DisplayP3 => 1.16,
+        }
+    }
+
+    /// Linear light in this gamut's own primaries, unclamped.
+    fn in_primaries(&self, lab: Oklab) -> [f32; 3] {
+        match self {
+            Gamut::Srgb => to_linear(lab),
+            Gamut::DisplayP3 => apply(SRGB_TO_P3, to_linear(lab)),
+        }
+    }
+
+    fn lab_of(&self, rgb: [f32; 3]) -> Oklab {
+        match self {
+            Gamut::Srgb => from_linear(rgb),
+            Gamut::DisplayP3 => from_linear(apply(P3_TO_SRGB, rgb)),
+        }
+    }
+
+    pub fn contains(&self, lab: Oklab) -> bool {
+        // A hair of tolerance: values a rounding error outside the cube are the
+        // gamut boundary itself, not a color we should throw away.
+        const TOLERANCE: f32 = 0.0005;
+        self.in_primaries(lab)
+            .iter()
+            .all(|channel| (-TOLERANCE..=1.0 + TOLERANCE).contains(channel))
+    }
+
+    /// As [`to_color`], in this gamut.
+    pub fn to_color(self, lab: Oklab) -> Option<Color> {
+        self.contains(lab).then(|| self.encode(lab))
+    }
+
+    /// Brings a colour into this gamut the way CSS Color 4 does: keep its
+    /// lightness and hue and lower its chroma until it fits, then clip, once
+    /// clipping moves it less than a just-noticeable 0.02. Colours already
+    /// inside come back as they are, and a lightness past either end is white
+    /// or black.
+    ///
+    /// This replaced walking the chroma down by a fixed factor a dozen times
+    /// and giving up on grey. The walk stopped wherever its last step happened
+    /// to land, often well inside the gamut, and its grey was a colour with no
+    /// hue at all, which is a different colour rather than a nearer one.
+    /// Mapping lands on the edge, the same edge for the same request every
+    /// time.
+    pub fn map(&self, lab: Oklab) -> Oklab {
+        const JND: f32 = 0.02;
+        const EPSILON: f32 = 0.0001;
+
+        if lab.l >= 1.0 {
+            return Oklab::new(1.0, 0.0, 0.0);
+        }
+        if lab.l <= 0.0 {
+            return Oklab::new(0.0, 0.0, 0.0);
+        }
+        if self.contains(lab) {
+            return lab;
+        }
+
+        let hue = lab.b.atan2(lab.a);
+        let at = |chroma: f32| Oklab::from_lch(lab.l, chroma, hue);
+        let clip = |lab: Oklab| {
+            self.lab_of(
+                self.in_primaries(lab)
+                    .map(|channel| channel.clamp(0.0, 1.0)),
+            )
+        };
+
+        let mut clipped = clip(lab);
+        if euclidean(clipped, lab) < JND {
+            return clipped;
+        }
+
+        let (mut low, mut high) = (0.0, (lab.a * lab.a + lab.b * lab.b).sqrt());
+        let mut low_in_gamut = true;
+        while high - low > EPSILON {
+            let chroma = (low + high) / 2.0;
+            let current = at(chroma);
+            if low_in_gamut && self.contains(current) {
+                low = chroma;
+                continue;
+            }
+
+            clipped = clip(current);
+            let error = euclidean(clipped, current);
+            if error < JND {
+                if JND - error < EPSILON {
+                    return clipped;
+                }
+                low_in_gamut = false;
+                low = chroma;
+            } else {
+                high = chroma;
+            }
+        }
+
+        clipped
+    }
+
+    /// The nearest colour this gamut holds to `lab`, in Oklab and as a `Color`.
+    pub fn to_displayable(self, lab: Oklab) -> (Oklab, Color) {
+        let mapped = self.map(lab);
+        (mapped, self.encode(mapped))
+    }
+
+    /// A colour in this gamut as Bevy takes it, clamped to the gamut's edge.
+    /// sRGB is written as sRGB, exactly as before there was a choice; P3 as
+    /// linear sRGB, with whatever channels it needs outside 0..1.
+    pub fn encode(&self, lab: Oklab) -> Color {
+        let own = self
+            .in_primaries(lab)
+            .map(|channel| channel.clamp(0.0, 1.0));
+        match self {
+            Gamut::Srgb => {
+                let [r, g, b] = own.map(linear_to_srgb);
+                Color::srgb(r, g, b)
+            }
+            Gamut::DisplayP3 => {
+                let [r, g, b] = apply(P3_TO_SRGB, own);
+                Color::linear_rgb(r, g, b)
+            }
+        }
+    }
+}
+
+// --- Other distances ---------------------------------------------------------
+//
+// Oklab's straight-line distance is what the game was built on, and the
+// literature mostly measures in something else: CIEDE2000 is the industry's
+// standard difference formula, and CAM16-UCS the appearance model's uniform
+// space. They disagree with Oklab, and with each other, about which pairs are
+// close, most of all in the blues and between saturated and dull colours.
+// `Metric` lets the curve be stated in any of the three, so a level can be
+// played at "one CIEDE2000 unit" and compared with the same level in Oklab.
+
+/// D65, the white sRGB is defined against, with Y at 1.
+const WHITE: [f32; 3] = [0.95047, 1.0, 1.08883];
+
+/// CIE XYZ, D65, Y 0..1.
+pub fn to_xyz(lab: Oklab) -> [f32; 3] {
+    let [r, g, b] = to_linear(lab);
+    [
+        0.4124564 * r + 0.3575761 * g + 0.1804375 * b,
+        0.2126729 * r + 0.7151522 * g + 0.0721750 * b,
+        0.0193339 * r + 0.119192 * g + 0.9503041 * b,
+    ]
+}
+
+/// CIELAB, D65: `L*` 0..100 and `a*`, `b*` in the same units.
+pub fn to_cielab(lab: Oklab) -> [f32; 3] {
+    let f = |t: f32| {
+        const EPSILON: f32 = 216.0 / 24389.0;
+        const KAPPA: f32 = 24389.0 / 27.0;
+        if t > EPSILON {
+            t.cbrt()
+        } else {
+            (KAPPA * t + 16.0) / 116.0
+        }
+    };
+    let xyz = to_xyz(lab);
+    let [fx, fy, fz] = [0, 1, 2].map(|i| f(xyz[i] / WHITE[i]));
+    [116.0 * fy - 16.0, 500.0 * (fx - fy), 200.0 * (fy - fz)]
+}
+
+/// CIEDE2000 between two colours, with the reference conditions' weights of
+/// one. Sharma, Wu and Dalal's notes on the formula are followed step by step,
+/// hue discontinuity included.
+pub fn ciede2000(first: Oklab, second: Oklab) -> f32 {
+    delta_e_2000(to_cielab(first), to_cielab(second))
+}
+
+fn delta_e_2000([l1, a1, b1]: [f32; 3], [l2, a2, b2]: [f32; 3]) -> f32 {
+    use std::f32::consts::PI;
+
+    let c_bar = ((a1 * a1 + b1 * b1).sqrt() + (a2 * a2 + b2 * b2).sqrt()) / 2.0;
+    let c_bar7 = c_bar.powi(7);
+    let g = 0.5 * (1.0 - (c_bar7 / (c_bar7 + 25f32.powi(7))).sqrt());
+    let (a1, a2) = (a1 * (1.0 + g), a2 * (1.0 + g));
+    let (c1, c2) = ((a1 * a1 + b1 * b1).sqrt(), (a2 * a2 + b2 * b2).sqrt());
+    let hue = |a: f32, b: f32| {
+        if a == 0.0 && b == 0.0 {
+            0.0
+        } else {
+            b.atan2(a).rem_euclid(2.0 * PI)
+        }
+    };
+    let (h1, h2) = (hue(a1, b1), hue(a2, b2));
+
+    let delta_l = l2 - l1;
+    let delta_c = c2 - c1;
+    let delta_h = if c1 * c2 == 0.0 {
+        0.0
+    } else if (h2 - h1).abs() <= PI {
+        h2 - h1
+    } else if h2 <= h1 {
+        h2 - h1 + 2.0 * PI
+    } else {
+        h2 - h1 - 2.0 * PI
+    };
+    let delta_big_h = 2.0 * (c1 * c2).sqrt() * (delta_h / 2.0).sin();
+
+    let l_bar = (l1 + l2) / 2.0;
+    let c_bar = (c1 + c2) / 2.0;
+    let h_bar = if c1 * c2 == 0.0 {
+        h1 + h2
+    } else if (h1 - h2).abs() <= PI {
+        (h1 + h2) / 2.0
+    } else if h1 + h2 < 2.0 * PI {
+        (h1 + h2 + 2.0 * PI) / 2.0
+    } else {
+        (h1 + h2 - 2.0 * PI) / 2.0
+    };
+
+    let t = 1.0 - 0.17 * (h_bar - PI / 6.0).cos()
+        + 0.24 * (2.0 * h_bar).cos()
+        + 0.32 * (3.0 * h_bar + PI / 30.0).cos()
+        - 0.20 * (4.0 * h_bar - 63f32.to_radians()).cos();
+    let delta_theta = 30f32.to_radians() * (-((h_bar.to_degrees() - 275.0) / 25.0).powi(2)).exp();
+    let c_bar7 = c_bar.powi(7);
+    let r_c = 2.0 * (c_bar7 / (c_bar7 + 25f32.powi(7))).sqrt();
+    let l_offset = (l_bar - 50.0).powi(2);
+    let s_l = 1.0 + 0.015 * l_offset / (20.0 + l_offset).sqrt();
+    let s_c = 1.0 + 0.045 * c_bar;
+    let s_h = 1.0 + 0.015 * c_bar * t;
+    let r_t = -(2.0 * delta_theta).sin() * r_c;
+
+    let (l, c, h) = (delta_l / s_l, delta_c / s_c, delta_big_h / s_h);
+    (l * l + c * c + h * h + r_t * c * h).max(0.0).sqrt()
+}
+
+/// CAM16-UCS `J'a'b'`, under the viewing conditions sRGB assumes: a 64 lux
+/// room, a mid-grey surround of 20% and an average surround.
+pub fn to_cam16_ucs(lab: Oklab) -> [f32; 3] {
+    const M16: [[f32; 3]; 3] = [
+        [0.401288, 0.650173, -0.051461],
+        [-0.250268, 1.204414, 0.045854],
+        [-0.002079, 0.048952, 0.953127],
+    ];
+    const ADAPTING_LUMINANCE: f32 = 64.0 / std::f32::consts::PI * 0.2;
+    const BACKGROUND: f32 = 20.0;
+    const SURROUND: f32 = 1.0;
+    const SURROUND_C: f32 = 0.69;
+    const CHROMATIC_INDUCTION: f32 = 1.0;
+
+    let cone = |xyz: [f32; 3]| M16.map(|row| row[0] * xyz[0] + row[1] * xyz[1] + row[2] * xyz[2]);
+
+    let white = cone(WHITE.map(|channel| channel * 100.0));
+    let degree = (SURROUND * (1.0 - (1.0 / 3.6) * ((-ADAPTING_LUMINANCE - 42.0) / 92.0).exp()))
+        .clamp(0.0, 1.0);
+    let adapt = white.map(|channel| degree * 100.0 / channel + 1.0 - degree);
+    let k = 1.0 / (5.0 * ADAPTING_LUMINANCE + 1.0);
+    let k4 = k.powi(4);
+    let luminance_level = 0.2 * k4 * 5.0 * ADAPTING_LUMINANCE
+        + 0.1 * (1.0 - k4).powi(2) * (5.0 * ADAPTING_LUMINANCE).cbrt();
+    let n = BACKGROUND / 100.0;
+    let z = 1.48 + n.sqrt();
+    let induction = 0.725 * n.powf(-0.2);
+
+    let compress = |channel: f32| {
+        let scaled = (luminance_level * channel.abs() / 100.0).powf(0.42);
+        channel.signum() * 400.0 * scaled / (scaled + 27.13) + 0.1
+    };
+    let achromatic = |[r, g, b]: [f32; 3]| (2.0 * r + g + b / 20.0 - 0.305) * induction;
+
+    let white_achromatic = achromatic([0, 1, 2].map(|i| compress(adapt[i] * white[i])));
+
+    let cones = cone(to_xyz(lab).map(|channel| channel * 100.0));
+    let [r, g, b] = [0, 1, 2].map(|i| compress(adapt[i] * cones[i]));
+
+    let a = r - 12.0 * g / 11.0 + b / 11.0;
+    let b_opponent = (r + g - 2.0 * b) / 9.0;
+    let hue = b_opponent.atan2(a);
+    let eccentricity = 0.25 * ((hue + 2.0).cos() + 3.8);
+    let lightness = 100.0
+        * (achromatic([r, g, b]) / white_achromatic)
+            .max(0.0)
+            .powf(SURROUND_C * z);
+    let t = (50000.0 / 13.0
+        * CHROMATIC_INDUCTION
+        * induction
+        * eccentricity
+        * (a * a + b_opponent * b_opponent).sqrt())
+        / (r + g + 21.0 / 20.0 * b);
+    let chroma =
+        t.max(0.0).powf(0.9) * (lightness / 100.0).sqrt() * (1.64 - 0.29f32.powf(n)).powf(0.73);
+    let colourfulness = chroma * luminance_level.powf(0.25);
+
+    let j = 1.7 * lightness / (1.0 + 0.007 * lightness);
+    let m = (1.0 + 0.0228 * colourfulness).ln() / 0.0228;
+    [j, m * hue.cos(), m * hue.sin()]
+}
+
+/// Euclidean distance in CAM16-UCS.
+pub fn cam16_ucs(first: Oklab, second: Oklab) -> f32 {
+    let (a, b) = (to_cam16_ucs(first), to_cam16_ucs(second));
+    let (dj, da, db) = (a[0] - b[0], a[1] - b[1], a[2] - b[2]);
+    (dj * dj + da * da + db * db).sqrt()
+}
+
+/// Which distance the difficulty curve is stated in. A setting, persisted, and
+/// carried by every run's log, since the boards are dealt in it.
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Eq, Default, Reflect)]
+pub enum Metric {
+    #[default]
+    Oklab,
+    Ciede2000,
+    Cam16Ucs,
+}
+
+const METRIC_KEY: &str = "color_puzzle.metric";
+
+impl Metric {
+    pub fn iter() -> impl Iterator<Item = Metric> {
+        [Metric::Oklab, Metric::Ciede2000, Metric::Cam16Ucs].into_iter()
+    }
+
+    /// Stable key for storage and share codes. Never change these strings.
+    pub fn storage_key(&self) -> &'static str {
+        match self {
+            Metric::Oklab => "oklab",
+            Metric::Ciede2000 => "ciede2000",
+            Metric::Cam16Ucs => "cam16ucs",
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Metric::Oklab => "METRICA: OKLAB",
+            Metric::Ciede2000 => "METRICA: CIEDE2000",
+            Metric::Cam16Ucs => "METRICA: CAM16-UCS",
+        }
+    }
+
+    pub fn cycle(&mut self) {
+        let all: Vec<Metric> = Self::iter().collect();
+        let at = all.iter().position(|metric| metric == self).unwrap_or(0);
+        *self = all[(at + 1) % all.len()];
+        storage::save(METRIC_KEY, self.storage_key());
+    }
+
+    pub fn load() -> Self {
+        Self::load_from(storage::backend())
+    }
+
+    pub fn load_from(store: &dyn StorageBackend) -> Self {
+        store
+            .load(METRIC_KEY)
+            .and_then(|key| Self::iter().find(|metric| metric.storage_key() == key.trim()))
+            .unwrap_or_default()
+    }
+
+    pub fn distance(&self, a: Oklab, b: Oklab) -> f32 {
+        match self {
+            Metric::Oklab => euclidean(a, b),
+            Metric::Ciede2000 => ciede2000(a, b),
+            Metric::Cam16Ucs => cam16_ucs(a, b),
+        }
+    }
+
+    /// This metric's units in one Oklab unit, for the kind of step the game
+    /// deals: mostly chromatic, between displayable colours in the mid tones.
+    /// The median over a few thousand such steps, measured once and written
+    /// down. It converts the curve's constants, so a level starts out about
+    /// as hard in any metric, and only differs where the metrics disagree
+    /// about a particular pair, which is the difference worth playing.
+    pub fn per_oklab_unit(&self) -> f32 {
+        match self {
+            Metric::Oklab => 1.0,
+            Metric::Ciede2000 => 230.0,
+            Metric::Cam16Ucs => 200.0,
+        }
+    }
+
+    /// Decimals worth showing for a distance. Oklab's unit is a couple of
+    /// hundred of the others'.
+    pub fn decimals(&self) -> usize {
+        match self {
+            Metric::Oklab => 3,
+            Metric::Ciede2000 | Metric::Cam16Ucs => 1,
+        }
+    }
+
+    /// How far to move `base` along the unit Oklab `direction` for the move
+    /// to measure `target` in this metric. Oklab's own answer is `target`,
+    /// returned as it is, so the default deals exactly what it always did.
+    /// The others are found by bisection: every metric here grows along a
+    /// straight line out of a colour, if not evenly.
+    pub fn step_along(&self, base: Oklab, direction: (f32, f32, f32), target: f32) -> f32 {
+        if *self == Metric::Oklab {
+            return target;
+        }
+
+        let measure = |amount: f32| self.distance(base, base.offset(direction, amount));
+        let mut low = 0.0;
+        let mut high = target / self.per_oklab_unit() * 2.0;
+        for _ in 0..8 {
+            if measure(high) >= target {
+                break;
+            }
+            low = high;
+            high *= 2.0;
+        }
+        for _ in 0..20 {
+            let middle = (low + high) / 2.0;
+            if measure(middle) < target {
+                low = middle;
+            } else {
+                high = middle;
+            }
+        }
+        (low + high) / 2.0
+    }
+}
+
+/// Straight-line distance in Oklab, the game's own.
+pub fn euclidean(a: Oklab, b: Oklab) -> f32 {
+    let (dl, da, db) = (a.l - b.l, a.a - b.a, a.b - b.b);
+    (dl * dl + da * da + db * db).sqrt()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Pairs from Sharma, Wu and Dalal's test data, which catch the usual
+    /// mistakes in the hue terms.
+    #[test]
+    fn ciede2000_matches_the_published_pairs() {
+        let pairs = [
+            ([50.0, 2.6772, -79.7751], [50.0, 0.0, -82.7485], 2.0425),
+            ([50.0, 2.5, 0.0], [73.0, 25.0, -18.0], 27.1492),
+            ([50.0, -1.0, 2.0], [50.0, 0.0, 0.0], 2.3669),
+            ([2.0776, 0.0795, -1.135], [0.9033, -0.0636, -0.5514], 0.9082),
+        ];
+        for (first, second, expected) in pairs {
+            let measured = delta_e_2000(first, second);
+            assert!(
+                (measured - expected).abs() < 1e-3,
+                "{measured} for {expected}"
+            );
+        }
+    }
+
+    #[test]
+    fn mapping_keeps_lightness_and_hue_and_lands_on_the_edge() {
+        for hue in (0..12).map(|step| step as f32 * 0.52) {
+            for lightness in [0.3, 0.6, 0.9] {
+                let wanted = Oklab::from_lch(lightness, 0.4, hue);
+                let (mapped, _) = Gamut::Srgb.to_displayable(wanted);
+                assert!(to_color(mapped).is_some());
+
+                // Within the clip's tolerance of the request's lightness and
+                // hue, and nowhere near grey.
+                assert!((mapped.l - lightness).abs() < 0.02, "{lightness} {hue}");
+                let chroma = (mapped.a * mapped.a + mapped.b * mapped.b).sqrt();
+                let moved = Oklab::from_lch(lightness, chroma, hue);
+                assert!(euclidean(mapped, moved) < 0.02, "{lightness} {hue}");
+                assert!(to_color(Oklab::from_lch(lightness, chroma + 0.03, hue)).is_none());
+            }
+        }
+
+        let inside = Oklab::new(0.6, 0.02, 0.03);
+        assert_eq!(Gamut::Srgb.map(inside), inside);
+    }
+
+    #[test]
+    fn display_p3_holds_srgb_and_round_trips_past_it() {
+        // The sRGB primaries sit inside P3, and its own green does not fit in
+        // sRGB.
+        for lab in [Color::srgb(1.0, 0.0, 0.0), Color::srgb(0.0, 1.0, 0.0)].map(from_color) {
+            assert!(Gamut::DisplayP3.contains(lab));
+        }
+        let green = Gamut::DisplayP3.lab_of([0.0, 1.0, 0.0]);
+        assert!(!Gamut::Srgb.contains(green));
+
+        // Written out with channels past 0..1, and read back unchanged.
+        let back = from_color(Gamut::DisplayP3.encode(green));
+        assert!(euclidean(back, green) < 1e-4);
+
+        // More chroma survives the mapping, and what survives is inside.
+        let wanted = Oklab::from_lch(0.7, 0.4, 2.5);
+        let narrow = Gamut::Srgb.map(wanted);
+        let wide = Gamut::DisplayP3.map(wanted);
+        assert!(Gamut::DisplayP3.contains(wide));
+        assert!(euclidean(wide, wanted) < euclidean(narrow, wanted));
+    }
+
+    #[test]
+    fn every_metric_steps_to_the_distance_asked_for() {
+        let base = Oklab::new(0.65, 0.05, -0.04);
+        let direction = (0.3, 0.8, -0.52);
+        for metric in Metric::iter() {
+            let target = 0.02 * metric.per_oklab_unit();
+            let amount = metric.step_along(base, direction, target);
+            let measured = metric.distance(base, base.offset(direction, amount));
+            assert!((measured / target - 1.0).abs() < 1e-3, "{metric:?}");
+        }
+
+        // The same colour is no distance at all in any of them.
+        assert_eq!(cam16_ucs(base, base), 0.0);
+        assert_eq!(ciede2000(base, base), 0.0);
+    }
+
+    /// P3 asked for is kept as asked, so it takes over once a window can
+    /// show it, and until then the boards fall back to sRGB and the setting
+    /// says so.
+    #[test]
+    fn a_p3_setting_falls_back_to_srgb_on_an_srgb_surface() {
+        let store = crate::storage::MemoryStorage::default();
+        store.save(GAMUT_KEY, Gamut::DisplayP3.storage_key());
+
+        let gamut = Gamut::load_from(&store);
+        assert_eq!(gamut, Gamut::DisplayP3);
+        assert_eq!(Gamut::surface(), Gamut::Srgb);
+        assert_eq!(gamut.on_surface(), Gamut::Srgb);
+        assert_eq!(gamut.setting_label(), "GAMA: DISPLAY P3 (SEM SUPORTE)");
+        assert_eq!(Gamut::Srgb.setting_label(), "GAMA: SRGB");
+    }
 }
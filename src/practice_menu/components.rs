@@ -0,0 +1,19 @@
+use bevy::prelude::Component;
+
+/// Root of the practice screen. Everything under it is despawned together.
+#[derive(Component)]
+pub struct PracticeMenu;
+
+/// Moves on to the next mode.
+#[derive(Component)]
+pub struct PracticeModeButton;
+
+/// Starts a practice run at its level.
+#[derive(Component)]
+pub struct PracticeLevelButton {
+    pub level: usize,
+}
+
+/// Returns to the main menu.
+#[derive(Component)]
+pub struct PracticeBackButton;
@@ -0,0 +1,39 @@
+//! The practice screen: a mode, and a level to hold it at.
+//!
+//! Every level up to `PRACTICE_LEVELS` is a button, wearing how often it has
+//! been answered right in the mode shown, so the screen doubles as the map of
+//! where the player is sure and where they are guessing. Pressing one starts
+//! a practice run there; see `game::practice`.
+
+mod components;
+mod styles;
+mod systems;
+
+use bevy::prelude::*;
+
+use crate::AppState;
+use systems::interactions::*;
+use systems::layout::*;
+
+pub struct PracticeMenuPlugin;
+
+impl Plugin for PracticeMenuPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(AppState::Practice), spawn_practice_menu)
+            .add_systems(
+                Update,
+                (
+                    interact_with_practice_mode_button,
+                    interact_with_practice_level_button,
+                    interact_with_practice_back_button,
+                )
+                    .run_if(in_state(AppState::Practice)),
+            )
+            // Tears down live `Button` entities, so it runs after `Update`.
+            .add_systems(
+                PostUpdate,
+                relayout_practice_menu.run_if(in_state(AppState::Practice)),
+            )
+            .add_systems(OnExit(AppState::Practice), despawn_practice_menu);
+    }
+}
@@ -0,0 +1,58 @@
+//! Layout for the practice screen. Colours and type come from `theme`.
+
+use bevy::prelude::*;
+
+use crate::theme;
+
+/// Level buttons per row. Five keeps each one a touch target wide at the
+/// narrowest window the game allows.
+pub const LEVELS_PER_ROW: usize = 5;
+
+pub fn menu_style() -> Node {
+    Node {
+        flex_direction: FlexDirection::Column,
+        justify_content: JustifyContent::Center,
+        align_items: AlignItems::Center,
+        width: Val::Percent(100.0),
+        height: Val::Percent(100.0),
+        padding: UiRect::vertical(Val::Px(theme::SPACE_MD)),
+        row_gap: Val::Px(theme::SPACE_XS),
+        ..Node::DEFAULT
+    }
+}
+
+pub fn level_row_style() -> Node {
+    Node {
+        flex_direction: FlexDirection::Row,
+        justify_content: JustifyContent::Center,
+        align_items: AlignItems::Center,
+        ..Node::DEFAULT
+    }
+}
+
+/// Width of each level button, so a row, margins included, takes the room
+/// one full-width button would.
+pub fn level_button_width(width: f32) -> f32 {
+    let count = LEVELS_PER_ROW as f32;
+    (width - theme::SPACE_XS * 2.0 * (count - 1.0)) / count
+}
+
+/// A level button: its number over its accuracy.
+pub fn level_button_style(width: f32) -> Node {
+    Node {
+        flex_direction: FlexDirection::Column,
+        padding: UiRect::all(Val::Px(theme::SPACE_XS)),
+        ..theme::button_style(width)
+    }
+}
+
+/// The colour an accuracy is written in: green where the level is held, amber
+/// where it is coming, red where it is still a guess.
+pub fn accuracy_color(percent: Option<usize>) -> Color {
+    match percent {
+        None => theme::MUTED,
+        Some(percent) if percent >= 80 => theme::SUCCESS,
+        Some(percent) if percent >= 50 => theme::ACCENT,
+        Some(_) => theme::DANGER,
+    }
+}
@@ -0,0 +1,95 @@
+use bevy::prelude::*;
+
+use crate::events::TransitionToStateEvent;
+use crate::game::challenge::Challenge;
+use crate::game::practice::Practice;
+use crate::game::puzzle::components::{ColorPuzzle, GameHistory, PowerUps, RunLog, RunSeed};
+use crate::pagination::Pagination;
+use crate::practice_menu::components::*;
+use crate::theme;
+use crate::AppState;
+
+pub fn interact_with_practice_mode_button(
+    mut button_query: Query<
+        (&Interaction, &mut BackgroundColor),
+        (Changed<Interaction>, With<PracticeModeButton>),
+    >,
+    mut practice: ResMut<Practice>,
+) {
+    for (interaction, mut background_color) in button_query.iter_mut() {
+        let accent = practice.mode().accent();
+        match *interaction {
+            // The relayout rebuilds the screen for the new mode, this button
+            // included, in its new colour.
+            Interaction::Pressed => practice.cycle_mode(),
+            Interaction::Hovered => *background_color = theme::BUTTON_HOVERED.into(),
+            Interaction::None => *background_color = accent.into(),
+        }
+    }
+}
+
+/// Starts a practice run at the button's level.
+///
+/// What the mode cards do, with the level pinned once the run is set up and
+/// none of the daily bookkeeping, since `Daily` is not offered here.
+pub fn interact_with_practice_level_button(
+    mut button_query: Query<
+        (&Interaction, &mut BackgroundColor, &PracticeLevelButton),
+        (Changed<Interaction>, With<PracticeLevelButton>),
+    >,
+    mut practice: ResMut<Practice>,
+    mut challenge: ResMut<Challenge>,
+    mut puzzle: ResMut<ColorPuzzle>,
+    mut game_history: ResMut<GameHistory>,
+    mut pagination: ResMut<Pagination>,
+    mut power_ups: ResMut<PowerUps>,
+    mut run_seed: ResMut<RunSeed>,
+    mut run_log: ResMut<RunLog>,
+    mut transition_to_state_event_writer: MessageWriter<TransitionToStateEvent>,
+) {
+    for (interaction, mut background_color, button) in button_query.iter_mut() {
+        match *interaction {
+            Interaction::Pressed => {
+                *background_color = theme::BUTTON_PRESSED.into();
+                let game_mode = practice.mode();
+                challenge.leave();
+                practice.begin(button.level);
+
+                puzzle.setup(&game_mode);
+                puzzle.pin_level(button.level);
+                *run_seed = RunSeed::for_mode(game_mode);
+                power_ups.clear();
+                run_log.start(&run_seed, &puzzle, *power_ups);
+                game_history.reset();
+                game_history.set_game_mode(game_mode);
+                pagination.reset();
+                transition_to_state_event_writer.write(TransitionToStateEvent {
+                    state: AppState::Game,
+                });
+            }
+            Interaction::Hovered => *background_color = theme::BUTTON_HOVERED.into(),
+            Interaction::None => *background_color = theme::BUTTON.into(),
+        }
+    }
+}
+
+pub fn interact_with_practice_back_button(
+    mut button_query: Query<
+        (&Interaction, &mut BackgroundColor),
+        (Changed<Interaction>, With<PracticeBackButton>),
+    >,
+    mut transition_to_state_event_writer: MessageWriter<TransitionToStateEvent>,
+) {
+    for (interaction, mut background_color) in button_query.iter_mut() {
+        match *interaction {
+            Interaction::Pressed => {
+                *background_color = theme::BUTTON_PRESSED.into();
+                transition_to_state_event_writer.write(TransitionToStateEvent {
+                    state: AppState::MainMenu,
+                });
+            }
+            Interaction::Hovered => *background_color = theme::BUTTON_HOVERED.into(),
+            Interaction::None => *background_color = theme::BUTTON.into(),
+        }
+    }
+}
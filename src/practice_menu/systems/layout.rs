@@ -0,0 +1,180 @@
+//! Builds the practice screen.
+
+use bevy::prelude::*;
+
+use crate::game::practice::{Practice, PRACTICE_LEVELS};
+use crate::practice_menu::components::*;
+use crate::practice_menu::styles::*;
+use crate::theme;
+
+pub fn spawn_practice_menu(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    practice: Res<Practice>,
+    window_query: Query<&Window>,
+) {
+    let width = window_query
+        .single()
+        .map(|window| theme::content_width(window.width()))
+        .unwrap_or(theme::CONTENT_MAX_WIDTH);
+
+    build_practice_menu(&mut commands, &asset_server, &practice, width);
+}
+
+pub fn build_practice_menu(
+    commands: &mut Commands,
+    asset_server: &Res<AssetServer>,
+    practice: &Res<Practice>,
+    width: f32,
+) -> Entity {
+    let mode = practice.mode();
+
+    commands
+        .spawn((
+            (menu_style(), BackgroundColor(theme::BACKGROUND)),
+            PracticeMenu,
+        ))
+        .with_children(|parent| {
+            parent.spawn(theme::wrapped_text(
+                "TREINO",
+                theme::text_title(asset_server),
+                width,
+            ));
+            // Said up front, so nobody plays a level here expecting it to count.
+            parent.spawn(theme::wrapped_text(
+                "SEM PONTOS, SEM VIDAS, SEM RELOGIO",
+                theme::text(asset_server, theme::TEXT_XS, theme::MUTED),
+                width,
+            ));
+
+            spawn_button(
+                parent,
+                asset_server,
+                &format!("MODO: {}", mode.as_str().to_uppercase()),
+                width,
+                mode.accent(),
+                PracticeModeButton,
+            );
+
+            let button_width = level_button_width(width);
+            let levels: Vec<usize> = (1..=PRACTICE_LEVELS).collect();
+            for row in levels.chunks(LEVELS_PER_ROW) {
+                parent.spawn(level_row_style()).with_children(|parent| {
+                    for level in row {
+                        spawn_level_button(
+                            parent,
+                            asset_server,
+                            *level,
+                            practice.tally(mode, *level).percent(),
+                            button_width,
+                        );
+                    }
+                });
+            }
+
+            spawn_button(
+                parent,
+                asset_server,
+                "VOLTAR",
+                width,
+                theme::BUTTON,
+                PracticeBackButton,
+            );
+        })
+        .id()
+}
+
+fn spawn_level_button(
+    parent: &mut ChildSpawnerCommands,
+    asset_server: &Res<AssetServer>,
+    level: usize,
+    percent: Option<usize>,
+    width: f32,
+) {
+    let accuracy = match percent {
+        Some(percent) => format!("{percent}%"),
+        None => "--".to_string(),
+    };
+
+    parent
+        .spawn((
+            (
+                Button,
+                level_button_style(width),
+                BackgroundColor(theme::BUTTON),
+            ),
+            PracticeLevelButton { level },
+        ))
+        .with_children(|parent| {
+            parent.spawn(theme::wrapped_text(
+                level.to_string(),
+                theme::text_button(asset_server),
+                theme::button_text_width(width),
+            ));
+            parent.spawn(theme::wrapped_text(
+                accuracy,
+                theme::text(asset_server, theme::TEXT_XS, accuracy_color(percent)),
+                theme::button_text_width(width),
+            ));
+        });
+}
+
+fn spawn_button<M: Component>(
+    parent: &mut ChildSpawnerCommands,
+    asset_server: &Res<AssetServer>,
+    label: &str,
+    width: f32,
+    color: Color,
+    marker: M,
+) {
+    parent
+        .spawn((
+            (Button, theme::button_style(width), BackgroundColor(color)),
+            marker,
+        ))
+        .with_children(|parent| {
+            parent.spawn(theme::wrapped_text(
+                label,
+                theme::text_button(asset_server),
+                theme::button_text_width(width),
+            ));
+        });
+}
+
+pub fn despawn_practice_menu(mut commands: Commands, query: Query<Entity, With<PracticeMenu>>) {
+    for entity in query.iter() {
+        commands.entity(entity).despawn();
+    }
+}
+
+/// Rebuilds for a window that changed size, and for a change of mode, which
+/// relabels every level. Runs in `PostUpdate` for the same reason every other
+/// relayout does: it despawns live `Button` entities.
+pub fn relayout_practice_menu(
+    mut commands: Commands,
+    mut relayout_events: MessageReader<crate::layout::RelayoutEvent>,
+    asset_server: Res<AssetServer>,
+    practice: Res<Practice>,
+    window_query: Query<&Window>,
+    menu_query: Query<Entity, With<PracticeMenu>>,
+) {
+    let resized = relayout_events.read().next().is_some();
+    if !resized && !practice.is_changed() {
+        return;
+    }
+
+    let Ok(window) = window_query.single() else {
+        return;
+    };
+
+    for entity in menu_query.iter() {
+        commands.entity(entity).despawn();
+    }
+
+    build_practice_menu(
+        &mut commands,
+        &asset_server,
+        &practice,
+        theme::content_width(window.width()),
+    );
+}
@@ -0,0 +1,45 @@
+use bevy::prelude::Component;
+
+/// Root of the settings screen. Everything under it is despawned together.
+#[derive(Component)]
+pub struct SettingsMenu;
+
+/// Puts the progress code on the clipboard.
+#[derive(Component)]
+pub struct ExportButton;
+
+/// Reads a progress code off the clipboard and previews it.
+#[derive(Component)]
+pub struct ImportButton;
+
+/// Writes the previewed import over what is stored.
+#[derive(Component)]
+pub struct ConfirmImportButton;
+
+/// Drops the previewed import.
+#[derive(Component)]
+pub struct CancelImportButton;
+
+/// Steps through the colour vision settings.
+#[derive(Component)]
+pub struct ColorVisionButton;
+
+/// Steps through the distance metrics.
+#[derive(Component)]
+pub struct MetricButton;
+
+/// Steps through the gamuts boards are dealt in.
+#[derive(Component)]
+pub struct GamutButton;
+
+/// Steps through the preview filters.
+#[derive(Component)]
+pub struct VisionFilterButton;
+
+/// Opens the vision test.
+#[derive(Component)]
+pub struct VisionTestButton;
+
+/// Returns to the main menu.
+#[derive(Component)]
+pub struct SettingsBackButton;
@@ -0,0 +1,57 @@
+//! The settings screen: moving progress between copies of the game.
+//!
+//! Export puts every stored value on the clipboard as one code. Import reads
+//! one back, says what it would change, and only writes anything once the
+//! player has read that and said yes. Until then the screen shows the preview
+//! in place of the two buttons, so there is no way to import by accident.
+//!
+//! It is also the way into the vision test, which is a tool rather than a
+//! mode and has no place among the cards on the main menu, and holds the
+//! colour vision setting, the metric, the gamut and the preview filter beside
+//! it.
+
+mod components;
+mod resources;
+mod styles;
+mod systems;
+
+use bevy::prelude::*;
+
+use crate::AppState;
+use resources::PendingImport;
+use systems::interactions::*;
+use systems::layout::*;
+
+pub struct SettingsMenuPlugin;
+
+impl Plugin for SettingsMenuPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<PendingImport>()
+            .add_systems(OnEnter(AppState::Settings), spawn_settings_menu)
+            .add_systems(
+                Update,
+                (
+                    interact_with_export_button,
+                    interact_with_import_button,
+                    interact_with_confirm_import_button,
+                    interact_with_cancel_import_button,
+                    interact_with_color_vision_button,
+                    interact_with_metric_button,
+                    interact_with_gamut_button,
+                    interact_with_vision_filter_button,
+                    interact_with_vision_test_button,
+                    interact_with_settings_back_button,
+                )
+                    .run_if(in_state(AppState::Settings)),
+            )
+            // Tears down live `Button` entities, so it runs after `Update`.
+            .add_systems(
+                PostUpdate,
+                relayout_settings_menu.run_if(in_state(AppState::Settings)),
+            )
+            .add_systems(
+                OnExit(AppState::Settings),
+                (despawn_settings_menu, forget_pending_import),
+            );
+    }
+}
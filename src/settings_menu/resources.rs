@@ -0,0 +1,12 @@
+use bevy::prelude::*;
+
+use crate::storage::transfer::Import;
+
+/// A pasted progress code the player has not yet confirmed, with the lines
+/// saying what it would change. The screen is built around it: while there is
+/// one, the preview and its two buttons take the place of export and import.
+#[derive(Resource, Debug, Default)]
+pub struct PendingImport {
+    pub import: Option<Import>,
+    pub preview: Vec<String>,
+}
@@ -0,0 +1,26 @@
+//! Layout for the settings screen. Colours and type come from `theme`.
+
+use bevy::prelude::*;
+
+use crate::theme;
+
+pub fn menu_style() -> Node {
+    Node {
+        flex_direction: FlexDirection::Column,
+        justify_content: JustifyContent::Center,
+        align_items: AlignItems::Center,
+        width: Val::Percent(100.0),
+        height: Val::Percent(100.0),
+        padding: UiRect::vertical(Val::Px(theme::SPACE_MD)),
+        row_gap: Val::Px(theme::SPACE_XS),
+        ..Node::DEFAULT
+    }
+}
+
+/// Space between the text and the buttons under it.
+pub fn spacer_style() -> Node {
+    Node {
+        height: Val::Px(theme::SPACE_LG),
+        ..Node::DEFAULT
+    }
+}
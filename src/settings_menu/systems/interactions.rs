@@ -0,0 +1,281 @@
+use bevy::prelude::*;
+
+use crate::audio::Volume;
+use crate::cvd::ColorVision;
+use crate::oklab::{Gamut, Metric};
+use crate::vision_filter::VisionFilter;
+use crate::events::TransitionToStateEvent;
+use crate::feedback::BannerEvent;
+use crate::game::achievements::Achievements;
+use crate::game::custom::CustomGame;
+use crate::game::practice::Practice;
+use crate::game::score::resources::{BestScores, DailyChallenge, SavedRun};
+use crate::settings_menu::components::*;
+use crate::settings_menu::resources::PendingImport;
+use crate::storage::{self, transfer};
+use crate::vision_test::resources::VisionProfile;
+use crate::{clipboard, theme, AppState};
+
+/// Puts every stored value on the clipboard as one code.
+///
+/// In the browser there is no clipboard to write to, so the code is shown in
+/// a box to copy out of instead, and no banner claims it was copied.
+pub fn interact_with_export_button(
+    mut button_query: Query<
+        (&Interaction, &mut BackgroundColor),
+        (Changed<Interaction>, With<ExportButton>),
+    >,
+    mut banner: MessageWriter<BannerEvent>,
+) {
+    for (interaction, mut background_color) in button_query.iter_mut() {
+        match *interaction {
+            Interaction::Pressed => {
+                *background_color = theme::BUTTON_PRIMARY_PRESSED.into();
+                let code = transfer::export(storage::backend());
+                if clipboard::offer(&code, "Copie o codigo do seu progresso") {
+                    banner.write(BannerEvent::notice("PROGRESSO COPIADO", theme::SUCCESS));
+                }
+            }
+            Interaction::Hovered => *background_color = theme::BUTTON_PRIMARY_HOVERED.into(),
+            Interaction::None => *background_color = theme::BUTTON_PRIMARY.into(),
+        }
+    }
+}
+
+/// Reads a progress code off the clipboard and holds it for confirmation.
+///
+/// Nothing is written here. A code that cannot be read, or that came from a
+/// newer build, is refused in a banner; one that can be read is migrated and
+/// compared with what is stored, and the screen rebuilds around the result.
+pub fn interact_with_import_button(
+    mut button_query: Query<
+        (&Interaction, &mut BackgroundColor),
+        (Changed<Interaction>, With<ImportButton>),
+    >,
+    mut pending: ResMut<PendingImport>,
+    mut banner: MessageWriter<BannerEvent>,
+) {
+    for (interaction, mut background_color) in button_query.iter_mut() {
+        match *interaction {
+            Interaction::Pressed => {
+                *background_color = theme::BUTTON_PRESSED.into();
+
+                // As with a share code: nothing pasted is not an error.
+                let Some(text) = clipboard::paste("Cole o codigo do seu progresso")
+                    .filter(|text| !text.trim().is_empty())
+                else {
+                    continue;
+                };
+
+                match transfer::decode(&text) {
+                    Ok(import) => {
+                        pending.preview = import.preview(storage::backend());
+                        pending.import = Some(import);
+                    }
+                    Err(error) => {
+                        banner.write(BannerEvent::notice(error.message(), theme::DANGER));
+                    }
+                }
+            }
+            Interaction::Hovered => *background_color = theme::BUTTON_HOVERED.into(),
+            Interaction::None => *background_color = theme::BUTTON.into(),
+        }
+    }
+}
+
+/// Writes the import and reloads everything that was read from storage at
+/// startup, so the rest of the session sees the new values rather than
+/// saving the old ones back over them on the next change.
+pub fn interact_with_confirm_import_button(
+    mut button_query: Query<
+        (&Interaction, &mut BackgroundColor),
+        (Changed<Interaction>, With<ConfirmImportButton>),
+    >,
+    mut pending: ResMut<PendingImport>,
+    mut best_scores: ResMut<BestScores>,
+    mut saved_run: ResMut<SavedRun>,
+    mut daily: ResMut<DailyChallenge>,
+    mut achievements: ResMut<Achievements>,
+    mut practice: ResMut<Practice>,
+    mut custom: ResMut<CustomGame>,
+    mut volume: ResMut<Volume>,
+    mut vision: ResMut<VisionProfile>,
+    mut color_vision: ResMut<ColorVision>,
+    mut metric: ResMut<Metric>,
+    mut gamut: ResMut<Gamut>,
+    mut banner: MessageWriter<BannerEvent>,
+) {
+    for (interaction, mut background_color) in button_query.iter_mut() {
+        match *interaction {
+            Interaction::Pressed => {
+                *background_color = theme::BUTTON_DANGER_PRESSED.into();
+                let Some(import) = pending.import.take() else {
+                    continue;
+                };
+                pending.preview.clear();
+
+                import.apply(storage::backend());
+                *best_scores = BestScores::load();
+                *saved_run = SavedRun::load();
+                *daily = DailyChallenge::load();
+                *achievements = Achievements::load();
+                *practice = Practice::load();
+                *custom = CustomGame::load();
+                *volume = Volume::load();
+                *vision = VisionProfile::load();
+                *color_vision = ColorVision::load();
+                *metric = Metric::load();
+                *gamut = Gamut::load();
+
+                banner.write(BannerEvent::notice("PROGRESSO IMPORTADO", theme::SUCCESS));
+            }
+            Interaction::Hovered => *background_color = theme::BUTTON_DANGER_HOVERED.into(),
+            Interaction::None => *background_color = theme::BUTTON_DANGER.into(),
+        }
+    }
+}
+
+pub fn interact_with_cancel_import_button(
+    mut button_query: Query<
+        (&Interaction, &mut BackgroundColor),
+        (Changed<Interaction>, With<CancelImportButton>),
+    >,
+    mut pending: ResMut<PendingImport>,
+) {
+    for (interaction, mut background_color) in button_query.iter_mut() {
+        match *interaction {
+            Interaction::Pressed => {
+                *background_color = theme::BUTTON_PRESSED.into();
+                *pending = PendingImport::default();
+            }
+            Interaction::Hovered => *background_color = theme::BUTTON_HOVERED.into(),
+            Interaction::None => *background_color = theme::BUTTON.into(),
+        }
+    }
+}
+
+/// Steps to the next colour vision setting. It takes effect from the next
+/// run: see `cvd`.
+pub fn interact_with_color_vision_button(
+    mut button_query: Query<
+        (&Interaction, &mut BackgroundColor),
+        (Changed<Interaction>, With<ColorVisionButton>),
+    >,
+    mut color_vision: ResMut<ColorVision>,
+) {
+    for (interaction, mut background_color) in button_query.iter_mut() {
+        match *interaction {
+            Interaction::Pressed => {
+                *background_color = theme::BUTTON_PRESSED.into();
+                color_vision.cycle();
+            }
+            Interaction::Hovered => *background_color = theme::BUTTON_HOVERED.into(),
+            Interaction::None => *background_color = theme::BUTTON.into(),
+        }
+    }
+}
+
+/// Steps to the next metric for the difficulty curve. Like the colour vision
+/// setting, it takes effect from the next run.
+pub fn interact_with_metric_button(
+    mut button_query: Query<
+        (&Interaction, &mut BackgroundColor),
+        (Changed<Interaction>, With<MetricButton>),
+    >,
+    mut metric: ResMut<Metric>,
+) {
+    for (interaction, mut background_color) in button_query.iter_mut() {
+        match *interaction {
+            Interaction::Pressed => {
+                *background_color = theme::BUTTON_PRESSED.into();
+                metric.cycle();
+            }
+            Interaction::Hovered => *background_color = theme::BUTTON_HOVERED.into(),
+            Interaction::None => *background_color = theme::BUTTON.into(),
+        }
+    }
+}
+
+/// Steps to the next gamut for the boards. Like the colour vision setting, it
+/// takes effect from the next run, and only on a window that can show it.
+pub fn interact_with_gamut_button(
+    mut button_query: Query<
+        (&Interaction, &mut BackgroundColor),
+        (Changed<Interaction>, With<GamutButton>),
+    >,
+    mut gamut: ResMut<Gamut>,
+) {
+    for (interaction, mut background_color) in button_query.iter_mut() {
+        match *interaction {
+            Interaction::Pressed => {
+                *background_color = theme::BUTTON_PRESSED.into();
+                gamut.cycle();
+            }
+            Interaction::Hovered => *background_color = theme::BUTTON_HOVERED.into(),
+            Interaction::None => *background_color = theme::BUTTON.into(),
+        }
+    }
+}
+
+/// Steps to the next preview filter, which applies at once: see
+/// `vision_filter`.
+pub fn interact_with_vision_filter_button(
+    mut button_query: Query<
+        (&Interaction, &mut BackgroundColor),
+        (Changed<Interaction>, With<VisionFilterButton>),
+    >,
+    mut filter: ResMut<VisionFilter>,
+) {
+    for (interaction, mut background_color) in button_query.iter_mut() {
+        match *interaction {
+            Interaction::Pressed => {
+                *background_color = theme::BUTTON_PRESSED.into();
+                filter.cycle();
+            }
+            Interaction::Hovered => *background_color = theme::BUTTON_HOVERED.into(),
+            Interaction::None => *background_color = theme::BUTTON.into(),
+        }
+    }
+}
+
+pub fn interact_with_vision_test_button(
+    mut button_query: Query<
+        (&Interaction, &mut BackgroundColor),
+        (Changed<Interaction>, With<VisionTestButton>),
+    >,
+    mut transition_to_state_event_writer: MessageWriter<TransitionToStateEvent>,
+) {
+    for (interaction, mut background_color) in button_query.iter_mut() {
+        match *interaction {
+            Interaction::Pressed => {
+                *background_color = theme::BUTTON_PRESSED.into();
+                transition_to_state_event_writer.write(TransitionToStateEvent {
+                    state: AppState::VisionTest,
+                });
+            }
+            Interaction::Hovered => *background_color = theme::BUTTON_HOVERED.into(),
+            Interaction::None => *background_color = theme::BUTTON.into(),
+        }
+    }
+}
+
+pub fn interact_with_settings_back_button(
+    mut button_query: Query<
+        (&Interaction, &mut BackgroundColor),
+        (Changed<Interaction>, With<SettingsBackButton>),
+    >,
+    mut transition_to_state_event_writer: MessageWriter<TransitionToStateEvent>,
+) {
+    for (interaction, mut background_color) in button_query.iter_mut() {
+        match *interaction {
+            Interaction::Pressed => {
+                *background_color = theme::BUTTON_PRESSED.into();
+                transition_to_state_event_writer.write(TransitionToStateEvent {
+                    state: AppState::MainMenu,
+                });
+            }
+            Interaction::Hovered => *background_color = theme::BUTTON_HOVERED.into(),
+            Interaction::None => *background_color = theme::BUTTON.into(),
+        }
+    }
+}
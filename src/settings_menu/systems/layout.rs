@@ -0,0 +1,251 @@
+//! Builds the settings screen.
+
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+
+use crate::cvd::ColorVision;
+use crate::oklab::{Gamut, Metric};
+use crate::settings_menu::components::*;
+use crate::settings_menu::resources::PendingImport;
+use crate::settings_menu::styles::*;
+use crate::theme;
+use crate::vision_filter::VisionFilter;
+
+/// The settings that are one button each and show their value on it.
+#[derive(SystemParam)]
+pub struct Toggles<'w> {
+    vision: Res<'w, ColorVision>,
+    metric: Res<'w, Metric>,
+    gamut: Res<'w, Gamut>,
+    filter: Res<'w, VisionFilter>,
+}
+
+impl Toggles<'_> {
+    fn is_changed(&self) -> bool {
+        self.vision.is_changed()
+            || self.metric.is_changed()
+            || self.gamut.is_changed()
+            || self.filter.is_changed()
+    }
+}
+
+pub fn spawn_settings_menu(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    pending: Res<PendingImport>,
+    toggles: Toggles,
+    window_query: Query<&Window>,
+) {
+    let width = window_query
+        .single()
+        .map(|window| theme::content_width(window.width()))
+        .unwrap_or(theme::CONTENT_MAX_WIDTH);
+
+    build_settings_menu(&mut commands, &asset_server, &pending, &toggles, width);
+}
+
+pub fn build_settings_menu(
+    commands: &mut Commands,
+    asset_server: &Res<AssetServer>,
+    pending: &Res<PendingImport>,
+    toggles: &Toggles,
+    width: f32,
+) -> Entity {
+    commands
+        .spawn((
+            (menu_style(), BackgroundColor(theme::BACKGROUND)),
+            SettingsMenu,
+        ))
+        .with_children(|parent| {
+            parent.spawn(theme::wrapped_text(
+                "AJUSTES",
+                theme::text_title(asset_server),
+                width,
+            ));
+
+            if pending.import.is_some() {
+                parent.spawn(theme::wrapped_text(
+                    "IMPORTAR SUBSTITUI TUDO:",
+                    theme::text(asset_server, theme::TEXT_SM, theme::ACCENT),
+                    width,
+                ));
+                for line in &pending.preview {
+                    parent.spawn(theme::wrapped_text(
+                        line.as_str(),
+                        theme::text(asset_server, theme::TEXT_XS, theme::ON_SURFACE),
+                        width,
+                    ));
+                }
+
+                parent.spawn(spacer_style());
+
+                // Red, because it is the one button in the game that can
+                // take a record away.
+                spawn_button(
+                    parent,
+                    asset_server,
+                    "CONFIRMAR",
+                    width,
+                    theme::BUTTON_DANGER,
+                    ConfirmImportButton,
+                );
+                spawn_button(
+                    parent,
+                    asset_server,
+                    "CANCELAR",
+                    width,
+                    theme::BUTTON,
+                    CancelImportButton,
+                );
+                return;
+            }
+
+            // Said up front: the code is the player's whole record, and it
+            // will sit in whatever chat they paste it into.
+            parent.spawn(theme::wrapped_text(
+                "LEVE SEU PROGRESSO PARA OUTRO NAVEGADOR OU COMPUTADOR",
+                theme::text(asset_server, theme::TEXT_XS, theme::MUTED),
+                width,
+            ));
+
+            parent.spawn(spacer_style());
+
+            spawn_button(
+                parent,
+                asset_server,
+                "EXPORTAR PROGRESSO",
+                width,
+                theme::BUTTON_PRIMARY,
+                ExportButton,
+            );
+            spawn_button(
+                parent,
+                asset_server,
+                "IMPORTAR PROGRESSO",
+                width,
+                theme::BUTTON,
+                ImportButton,
+            );
+            spawn_button(
+                parent,
+                asset_server,
+                toggles.vision.label(),
+                width,
+                theme::BUTTON,
+                ColorVisionButton,
+            );
+            spawn_button(
+                parent,
+                asset_server,
+                toggles.metric.label(),
+                width,
+                theme::BUTTON,
+                MetricButton,
+            );
+            spawn_button(
+                parent,
+                asset_server,
+                &toggles.gamut.setting_label(),
+                width,
+                theme::BUTTON,
+                GamutButton,
+            );
+            spawn_button(
+                parent,
+                asset_server,
+                toggles.filter.label(),
+                width,
+                theme::BUTTON,
+                VisionFilterButton,
+            );
+            spawn_button(
+                parent,
+                asset_server,
+                "TESTE DE VISAO",
+                width,
+                theme::BUTTON,
+                VisionTestButton,
+            );
+            spawn_button(
+                parent,
+                asset_server,
+                "VOLTAR",
+                width,
+                theme::BUTTON,
+                SettingsBackButton,
+            );
+        })
+        .id()
+}
+
+fn spawn_button<M: Component>(
+    parent: &mut ChildSpawnerCommands,
+    asset_server: &Res<AssetServer>,
+    label: &str,
+    width: f32,
+    color: Color,
+    marker: M,
+) {
+    parent
+        .spawn((
+            (Button, theme::button_style(width), BackgroundColor(color)),
+            marker,
+        ))
+        .with_children(|parent| {
+            parent.spawn(theme::wrapped_text(
+                label,
+                theme::text_button(asset_server),
+                theme::button_text_width(width),
+            ));
+        });
+}
+
+pub fn despawn_settings_menu(
+    mut commands: Commands,
+    query: Query<Entity, With<SettingsMenu>>,
+) {
+    for entity in query.iter() {
+        commands.entity(entity).despawn();
+    }
+}
+
+/// An import left unconfirmed is dropped with the screen, so coming back
+/// later starts from the two buttons rather than a stale preview.
+pub fn forget_pending_import(mut pending: ResMut<PendingImport>) {
+    *pending = PendingImport::default();
+}
+
+/// Rebuilds for a window that changed size, for an import being previewed or
+/// dropped, which swaps the buttons, and for any of the toggles, whose buttons
+/// show their values. Runs in `PostUpdate` for the same reason every other
+/// relayout does: it despawns live `Button` entities.
+pub fn relayout_settings_menu(
+    mut commands: Commands,
+    mut relayout_events: MessageReader<crate::layout::RelayoutEvent>,
+    asset_server: Res<AssetServer>,
+    pending: Res<PendingImport>,
+    toggles: Toggles,
+    window_query: Query<&Window>,
+    menu_query: Query<Entity, With<SettingsMenu>>,
+) {
+    let resized = relayout_events.read().next().is_some();
+    if !resized && !pending.is_changed() && !toggles.is_changed() {
+        return;
+    }
+
+    let Ok(window) = window_query.single() else {
+        return;
+    };
+
+    for entity in menu_query.iter() {
+        commands.entity(entity).despawn();
+    }
+
+    build_settings_menu(
+        &mut commands,
+        &asset_server,
+        &pending,
+        &toggles,
+        theme::content_width(window.width()),
+    );
+}
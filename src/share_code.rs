@@ -0,0 +1,493 @@
+//! Share codes: a finished run, small enough to paste into a chat.
+//!
+//! A code carries what `RunLog` does — the mode, the seed and every move with
+//! its time — plus the score the sender says it reached. The score is there to
+//! be checked, not believed: `verify` plays the log again and only a code whose
+//! moves reach that score, and that the live game could have made, is shown as
+//! real. Editing the number in a code makes it fail; editing the moves makes a
+//! different run, which is then judged on what it actually scores.
+//!
+//! The format is hand-rolled for the same reason the stored scores are: it is
+//! a few dozen lines, and a serialization crate would be a dependency for one
+//! string. Varints keep a typical run to a couple hundred characters, and the
+//! text is URL-safe base64 so it survives being pasted anywhere.
+//!
+//! Layout, after the `CP` prefix and before the base64:
+//!
+//! ```text
+//! version u8 | mode key (len u8, bytes) | vision key (len u8, bytes)
+//! metric key (len u8, bytes) | gamut key (len u8, bytes)
+//! seed | score | duration ms | count
+//! count x ( tag u8 | ms since previous | payload ) | FNV-1a 32 LE
+//! ```
+//!
+//! Every number but the version, the tags and the checksum is a varint. A
+//! window is its two `f32`s as they were; a pick is its whole-unit point,
+//! zigzagged since the board is centred on the origin.
+//!
+//! The colour vision key is there because the boards are dealt for it: a run
+//! played with a deficiency set replays only on the boards it was played on.
+//! Version 1 codes were written before the setting existed and are read with
+//! typical vision, which is what every run then was played with. The metric
+//! key is there for the same reason, and version 2 codes, which predate it,
+//! are read in Oklab. So is the gamut key, and version 3 codes are read in
+//! sRGB.
+
+use bevy::prelude::*;
+
+use crate::cvd::ColorVision;
+use crate::encoding::{base64_decode, base64_encode, fnv1a, write_varint, Reader};
+use crate::game::puzzle::components::{
+    ColorPuzzle, GameHistory, GameMode, GameTimer, LoggedAction, PowerUp, PowerUps, RunAction,
+    RunLog,
+};
+use crate::game::replay::components::{Replay, RunState};
+use crate::oklab::{Gamut, Metric};
+
+const PREFIX: &str = "CP";
+
+/// Bumped whenever the layout changes. A code from a version this build does
+/// not know is refused rather than guessed at: the same bytes would decode to
+/// a different run.
+const VERSION: u8 = 4;
+/// The version before colour vision was carried, still read.
+const VERSION_TYPICAL_ONLY: u8 = 1;
+/// The version before the metric was carried, still read.
+const VERSION_OKLAB_ONLY: u8 = 2;
+/// The version before the gamut was carried, still read.
+const VERSION_SRGB_ONLY: u8 = 3;
+
+const TAG_WINDOW: u8 = 0;
+const TAG_PICK: u8 = 1;
+const TAG_POWER_UP: u8 = 2;
+
+/// A window no screen the game runs on has. `set_window_size` is trusted with
+/// whatever it is given, and a board cut against a window a mile wide would
+/// take a while to deal.
+const MAX_WINDOW_SIDE: f32 = 16_384.0;
+
+/// A run read back from a code.
+#[derive(Debug, Clone)]
+pub struct ShareCode {
+    pub log: RunLog,
+    /// The score the sender says the run reached. Unchecked until `verify`.
+    pub score: usize,
+}
+
+impl ShareCode {
+    /// The calendar day of a daily run: its seed is the day number.
+    pub fn day(&self) -> Option<u64> {
+        self.log.game_mode.is_daily().then_some(self.log.seed)
+    }
+
+    /// The window the first board was dealt on. A challenger plays on it, so
+    /// their boards are cut the same way the sender's were.
+    pub fn board_size(&self) -> Option<Vec2> {
+        self.log.actions.iter().find_map(|logged| match logged.action {
+            RunAction::Window(size) => Some(size),
+            _ => None,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShareCodeError {
+    /// Not a code at all, or one cut short.
+    Malformed,
+    /// A code from a build that writes them differently.
+    Version,
+    /// The bytes were changed after the code was made.
+    Checksum,
+    UnknownMode,
+    /// A move the live game would have refused.
+    Impossible,
+    /// The moves are fine but do not reach the score the code claims.
+    ScoreMismatch,
+}
+
+impl ShareCodeError {
+    /// What the menu says when a pasted code is refused.
+    pub fn message(&self) -> &'static str {
+        match self {
+            ShareCodeError::Malformed | ShareCodeError::Checksum => "CODIGO INVALIDO",
+            ShareCodeError::Version => "CODIGO DE OUTRA VERSAO",
+            ShareCodeError::UnknownMode => "MODO DESCONHECIDO",
+            ShareCodeError::Impossible => "JOGADA IMPOSSIVEL",
+            ShareCodeError::ScoreMismatch => "PONTOS NAO CONFEREM",
+        }
+    }
+}
+
+/// The code for a finished run, or `None` for a run that was continued: its log
+/// starts partway in, at a score nothing in the code could account for. Also
+/// `None` for a custom run, whose rules the code has no room for; a mode key
+/// alone would deal the friend a different game.
+pub fn encode(log: &RunLog, score: usize) -> Option<String> {
+    if !log.is_whole_run() || log.game_mode.is_custom() {
+        return None;
+    }
+
+    let mut bytes = vec![VERSION];
+    let key = log.game_mode.storage_key().as_bytes();
+    bytes.push(key.len() as u8);
+    bytes.extend_from_slice(key);
+    let vision = log.color_vision.storage_key().as_bytes();
+    bytes.push(vision.len() as u8);
+    bytes.extend_from_slice(vision);
+    let metric = log.metric.storage_key().as_bytes();
+    bytes.push(metric.len() as u8);
+    bytes.extend_from_slice(metric);
+    let gamut = log.gamut.storage_key().as_bytes();
+    bytes.push(gamut.len() as u8);
+    bytes.extend_from_slice(gamut);
+    write_varint(&mut bytes, log.seed);
+    write_varint(&mut bytes, score as u64);
+    write_varint(&mut bytes, millis(log.duration()));
+    write_varint(&mut bytes, log.actions.len() as u64);
+
+    let mut previous = 0;
+    for logged in &log.actions {
+        let at = millis(logged.seconds);
+        let tag = match logged.action {
+            RunAction::Window(_) => TAG_WINDOW,
+            RunAction::Pick(_) => TAG_PICK,
+            RunAction::PowerUp(_) => TAG_POWER_UP,
+        };
+        bytes.push(tag);
+        write_varint(&mut bytes, at.saturating_sub(previous));
+        previous = at.max(previous);
+
+        match logged.action {
+            RunAction::Window(size) => {
+                bytes.extend_from_slice(&size.x.to_bits().to_le_bytes());
+                bytes.extend_from_slice(&size.y.to_bits().to_le_bytes());
+            }
+            RunAction::Pick(position) => {
+                write_varint(&mut bytes, zigzag(position.x.round() as i64));
+                write_varint(&mut bytes, zigzag(position.y.round() as i64));
+            }
+            RunAction::PowerUp(kind) => bytes.push(match kind {
+                PowerUp::ExtraLife => 0,
+                PowerUp::EliminateWrong => 1,
+            }),
+        }
+    }
+
+    let checksum = fnv1a(&bytes);
+    bytes.extend_from_slice(&checksum.to_le_bytes());
+
+    Some(format!("{PREFIX}{}", base64_encode(&bytes)))
+}
+
+/// Reads a code back. Whitespace anywhere is ignored, since chat apps like to
+/// wrap long words.
+pub fn decode(text: &str) -> Result<ShareCode, ShareCodeError> {
+    let text: String = text.chars().filter(|c| !c.is_whitespace()).collect();
+    let body = text.strip_prefix(PREFIX).ok_or(ShareCodeError::Malformed)?;
+    let bytes = base64_decode(body).ok_or(ShareCodeError::Malformed)?;
+
+    if bytes.len() < 5 {
+        return Err(ShareCodeError::Malformed);
+    }
+    let (payload, checksum) = bytes.split_at(bytes.len() - 4);
+    // The version is read before the checksum is: another version may not
+    // checksum the same way, and saying so is more use than "invalid".
+    let version = payload[0];
+    if ![VERSION, VERSION_SRGB_ONLY, VERSION_OKLAB_ONLY, VERSION_TYPICAL_ONLY].contains(&version) {
+        return Err(ShareCodeError::Version);
+    }
+    if fnv1a(payload).to_le_bytes() != checksum {
+        return Err(ShareCodeError::Checksum);
+    }
+
+    let mut reader = Reader::new(payload, ShareCodeError::Malformed);
+    reader.skip(1);
+    let key_len = reader.byte()? as usize;
+    let key = reader.take(key_len)?;
+    let game_mode = GameMode::iter()
+        .find(|mode| mode.storage_key().as_bytes() == key)
+        .ok_or(ShareCodeError::UnknownMode)?;
+    let color_vision = if version == VERSION_TYPICAL_ONLY {
+        ColorVision::Typical
+    } else {
+        let key_len = reader.byte()? as usize;
+        let key = reader.take(key_len)?;
+        ColorVision::iter()
+            .find(|vision| vision.storage_key().as_bytes() == key)
+            .ok_or(ShareCodeError::Malformed)?
+    };
+    let metric = if version >= VERSION_SRGB_ONLY {
+        let key_len = reader.byte()? as usize;
+        let key = reader.take(key_len)?;
+        Metric::iter()
+            .find(|metric| metric.storage_key().as_bytes() == key)
+            .ok_or(ShareCodeError::Malformed)?
+    } else {
+        Metric::Oklab
+    };
+    let gamut = if version == VERSION {
+        let key_len = reader.byte()? as usize;
+        let key = reader.take(key_len)?;
+        Gamut::iter()
+            .find(|gamut| gamut.storage_key().as_bytes() == key)
+            .ok_or(ShareCodeError::Malformed)?
+    } else {
+        Gamut::Srgb
+    };
+
+    let seed = reader.varint()?;
+    let score = reader.varint()? as usize;
+    let duration = reader.varint()?;
+    let count = reader.varint()? as usize;
+    // Every action takes at least two bytes, so a count past that is a lie
+    // that would otherwise be allocated for.
+    if count > reader.remaining() / 2 {
+        return Err(ShareCodeError::Malformed);
+    }
+
+    let mut log = RunLog::fresh(seed, game_mode);
+    log.color_vision = color_vision;
+    log.metric = metric;
+    log.gamut = gamut;
+    let mut at = 0u64;
+    for _ in 0..count {
+        let tag = reader.byte()?;
+        at = at.checked_add(reader.varint()?).ok_or(ShareCodeError::Malformed)?;
+
+        let action = match tag {
+            TAG_WINDOW => {
+                let width = reader.f32()?;
+                let height = reader.f32()?;
+                let sane = |side: f32| (1.0..=MAX_WINDOW_SIDE).contains(&side);
+                if !sane(width) || !sane(height) {
+                    return Err(ShareCodeError::Malformed);
+                }
+                RunAction::Window(Vec2::new(width, height))
+            }
+            TAG_PICK => {
+                let x = unzigzag(reader.varint()?);
+                let y = unzigzag(reader.varint()?);
+                RunAction::Pick(Vec2::new(x as f32, y as f32))
+            }
+            TAG_POWER_UP => RunAction::PowerUp(match reader.byte()? {
+                0 => PowerUp::ExtraLife,
+                1 => PowerUp::EliminateWrong,
+                _ => return Err(ShareCodeError::Malformed),
+            }),
+            _ => return Err(ShareCodeError::Malformed),
+        };
+
+        log.actions.push(LoggedAction {
+            seconds: at as f32 / 1000.0,
+            action,
+        });
+    }
+
+    if reader.remaining() != 0 || duration < at {
+        return Err(ShareCodeError::Malformed);
+    }
+    log.finish_at(duration as f32 / 1000.0);
+
+    Ok(ShareCode { log, score })
+}
+
+/// Plays the code's run through and returns the score it really reached.
+///
+/// Runs on scratch resources of its own, so it can be called from a menu
+/// without touching the run on the board.
+pub fn verify(code: &ShareCode) -> Result<usize, ShareCodeError> {
+    let mut puzzle = ColorPuzzle::default();
+    let mut game_timer = GameTimer::default();
+    let mut history = GameHistory::default();
+    let mut power_ups = PowerUps::default();
+    let mut run = RunState {
+        puzzle: &mut puzzle,
+        game_timer: &mut game_timer,
+        history: &mut history,
+        power_ups: &mut power_ups,
+    };
+
+    let mut replay = Replay::start(code.log.clone(), &mut run);
+    replay.advance(code.log.duration(), &mut run);
+
+    if !replay.is_possible() {
+        return Err(ShareCodeError::Impossible);
+    }
+
+    let score = run.puzzle.get_score();
+    if score != code.score {
+        return Err(ShareCodeError::ScoreMismatch);
+    }
+    Ok(score)
+}
+
+/// Rounded, not truncated: the log already keeps milliseconds, and `8.7` is
+/// `8.699999` as an `f32`.
+fn millis(seconds: f32) -> u64 {
+    (seconds.max(0.0) * 1000.0).round() as u64
+}
+
+fn zigzag(value: i64) -> u64 {
+    ((value << 1) ^ (value >> 63)) as u64
+}
+
+fn unzigzag(value: u64) -> i64 {
+    ((value >> 1) as i64) ^ -((value & 1) as i64)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+    use crate::game::puzzle::components::RunSeed;
+
+    /// A run the way the live game would log it, with picks spaced wide enough
+    /// for a `Memory` preview to have ended. Returns the log and its score.
+    fn play(game_mode: GameMode, seed: u64) -> (RunLog, usize) {
+        play_seeing(game_mode, seed, ColorVision::Typical)
+    }
+
+    fn play_seeing(game_mode: GameMode, seed: u64, vision: ColorVision) -> (RunLog, usize) {
+        play_with(game_mode, seed, vision, Metric::Oklab, Gamut::Srgb)
+    }
+
+    fn play_with(
+        game_mode: GameMode,
+        seed: u64,
+        vision: ColorVision,
+        metric: Metric,
+        gamut: Gamut,
+    ) -> (RunLog, usize) {
+        let mut puzzle = ColorPuzzle::default();
+        puzzle.color_vision = vision;
+        puzzle.metric = metric;
+        puzzle.gamut = gamut;
+        let mut game_timer = GameTimer::default();
+        let mut seed = RunSeed::new(seed);
+
+        puzzle.setup(&game_mode);
+        game_timer.timer = puzzle.setup_timer();
+
+        let mut log = RunLog::default();
+        log.start(&seed, &puzzle, PowerUps::default());
+        puzzle.set_window_size(480.0, 800.0);
+        log.record(RunAction::Window(Vec2::new(480.0, 800.0)));
+        puzzle.generate_colors(&mut seed.rng());
+
+        for index in 0..6 {
+            log.tick(2.9);
+            game_timer.timer.tick(Duration::from_secs_f32(2.9));
+            let outlines = puzzle.piece_outlines();
+            let (answer, _) = outlines[puzzle.get_correct_color_index()].clone();
+            let position = if index == 3 { Vec2::new(-9_000.0, 9_000.0) } else { answer.round() };
+
+            log.record(RunAction::Pick(position));
+            seed.advance();
+            let scored = puzzle.pick_hits(position);
+            puzzle.resolve_pick(scored, &mut game_timer);
+            if !scored {
+                log.tick(puzzle.rules.hold_seconds());
+            }
+            puzzle.generate_colors(&mut seed.rng());
+        }
+        log.tick(1.0);
+
+        (log, puzzle.get_score())
+    }
+
+    #[test]
+    fn a_code_reads_back_as_the_run_it_was_made_from() {
+        for game_mode in [GameMode::Infinite, GameMode::Memory, GameMode::Mosaic] {
+            let (log, score) = play(game_mode, 11);
+            let text = encode(&log, score).unwrap();
+            let code = decode(&format!(" {}\n{} ", &text[..10], &text[10..])).unwrap();
+
+            assert_eq!(code.log.actions, log.actions);
+            assert_eq!(code.log.game_mode, game_mode);
+            assert!(score > 0);
+            assert_eq!(verify(&code), Ok(score), "{game_mode:?}");
+        }
+    }
+
+    /// The boards depend on the sender's colour vision, so the code carries
+    /// it and the check deals with it.
+    #[test]
+    fn a_code_keeps_the_colour_vision_it_was_played_with() {
+        let (log, score) = play_seeing(GameMode::Infinite, 5, ColorVision::Deutan);
+        let code = decode(&encode(&log, score).unwrap()).unwrap();
+
+        assert_eq!(code.log.color_vision, ColorVision::Deutan);
+        assert_eq!(verify(&code), Ok(score));
+    }
+
+    #[test]
+    fn a_code_keeps_the_metric_it_was_played_in() {
+        let (log, score) = play_with(
+            GameMode::Infinite,
+            5,
+            ColorVision::Typical,
+            Metric::Ciede2000,
+            Gamut::Srgb,
+        );
+        let code = decode(&encode(&log, score).unwrap()).unwrap();
+
+        assert_eq!(code.log.metric, Metric::Ciede2000);
+        assert_eq!(verify(&code), Ok(score));
+    }
+
+    /// A P3 run replays in P3 wherever it is checked, whatever the checking
+    /// window could show.
+    #[test]
+    fn a_code_keeps_the_gamut_it_was_dealt_in() {
+        let (log, score) = play_with(
+            GameMode::Infinite,
+            5,
+            ColorVision::Typical,
+            Metric::Oklab,
+            Gamut::DisplayP3,
+        );
+        let code = decode(&encode(&log, score).unwrap()).unwrap();
+
+        assert_eq!(code.log.gamut, Gamut::DisplayP3);
+        assert_eq!(verify(&code), Ok(score));
+    }
+
+    #[test]
+    fn an_edited_score_is_caught() {
+        let (log, score) = play(GameMode::Infinite, 3);
+
+        // Re-encoded rather than edited in place, so the checksum is right and
+        // only the replay can tell.
+        let code = decode(&encode(&log, score + 100).unwrap()).unwrap();
+        assert_eq!(verify(&code), Err(ShareCodeError::ScoreMismatch));
+
+        let mut text = encode(&log, score).unwrap();
+        let last = text.pop().unwrap();
+        text.push(if last == 'A' { 'B' } else { 'A' });
+        assert!(decode(&text).is_err());
+    }
+
+    #[test]
+    fn a_pick_the_game_would_have_refused_is_caught() {
+        let (mut log, score) = play(GameMode::Infinite, 5);
+        // A pick slipped in before the first board was dealt.
+        log.actions.insert(
+            0,
+            LoggedAction {
+                seconds: 0.0,
+                action: RunAction::Pick(Vec2::ZERO),
+            },
+        );
+
+        let code = decode(&encode(&log, score).unwrap()).unwrap();
+        assert_eq!(verify(&code), Err(ShareCodeError::Impossible));
+    }
+
+    #[test]
+    fn a_continued_run_has_no_code() {
+        let (mut log, score) = play(GameMode::Infinite, 9);
+        log.start_score = 4;
+        assert_eq!(encode(&log, score), None);
+    }
+}
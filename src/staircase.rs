@@ -0,0 +1,193 @@
+//! A transformed up-down staircase, for `Adaptive`.
+//!
+//! The level curve hands every player the same colour distance at the same
+//! score. A player with a sharp eye spends the first twenty rounds on deltas
+//! they could read from across the room, and one with a dull screen runs out
+//! of lives on a distance they never had a chance at. Neither learns where
+//! their own limit is.
+//!
+//! A staircase finds it. Two right picks in a row make the next board harder,
+//! one miss makes it easier, and the delta settles where a pick is right about
+//! seven times in ten (Levitt's 2-down/1-up rule). That is hard enough to be a
+//! test and easy enough that a run is mostly hits.
+//!
+//! Steps are taken in log space, so a step near the floor is as noticeable as
+//! one near the ceiling, and they shrink every time the direction turns: big
+//! strides while the staircase is still looking, small ones once it has found
+//! the level it hovers around.
+//!
+//! Where it turns round is also the measurement. The delta at each reversal is
+//! a point the player was just on the edge of, and their geometric mean is
+//! the estimate `jnd` reports as the player's threshold.
+//!
+//! Free of game state, like `fairness` and `wfc`, so the rule can be tested on
+//! made-up picks.
+
+/// Right picks in a row before the delta shrinks.
+const DOWN_AFTER: usize = 2;
+
+/// The first steps change the delta by half again, which gets from the ceiling
+/// to a typical threshold in a handful of rounds.
+const START_STEP: f32 = 0.405; // ln 1.5
+/// Steps never get finer than a tenth. Any smaller and a run of bad luck at
+/// the end of a long run would take dozens of rounds to climb out of.
+const MIN_STEP: f32 = 0.095; // ln 1.1
+/// How much a step shrinks at each reversal.
+const STEP_SHRINK: f32 = 0.7;
+/// Reversals left out of the estimate. The first turns come at the end of the
+/// big strides down from the ceiling, and say more about the step size than
+/// about the player.
+const SETTLING_REVERSALS: usize = 2;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Direction {
+    Harder,
+    Easier,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Staircase {
+    log_delta: f32,
+    step: f32,
+    floor: f32,
+    ceiling: f32,
+    /// Right picks since the last move.
+    run: usize,
+    last_move: Option<Direction>,
+    reversals: usize,
+    /// Sum of the log delta at every reversal past the settling ones.
+    reversal_log_sum: f32,
+}
+
+impl Staircase {
+    /// A staircase starting at `ceiling`, the easiest delta it will hand out,
+    /// and never going below `floor`.
+    pub fn new(floor: f32, ceiling: f32) -> Self {
+        Self {
+            log_delta: ceiling.ln(),
+            step: START_STEP,
+            floor: floor.ln(),
+            ceiling: ceiling.ln(),
+            run: 0,
+            last_move: None,
+            reversals: 0,
+            reversal_log_sum: 0.0,
+        }
+    }
+
+    /// The delta the next board should be dealt at.
+    pub fn delta(&self) -> f32 {
+        self.log_delta.exp()
+    }
+
+    /// Times the staircase has turned round.
+    pub fn reversals(&self) -> usize {
+        self.reversals
+    }
+
+    /// The delta the staircase hovers around: the geometric mean of where it
+    /// turned, once it has settled. `None` until it has turned past the
+    /// settling reversals at least once.
+    pub fn threshold(&self) -> Option<f32> {
+        let counted = self.reversals.checked_sub(SETTLING_REVERSALS)?;
+        if counted == 0 {
+            return None;
+        }
+        Some((self.reversal_log_sum / counted as f32).exp())
+    }
+
+    /// Moves on one pick.
+    pub fn record(&mut self, correct: bool) {
+        if correct {
+            self.run += 1;
+            if self.run >= DOWN_AFTER {
+                self.step(Direction::Harder);
+            }
+        } else {
+            self.step(Direction::Easier);
+        }
+    }
+
+    fn step(&mut self, direction: Direction) {
+        self.run = 0;
+        // A turn means the staircase has stepped past the threshold one way
+        // and then the other, so it is close, and the strides get shorter.
+        if self.last_move.is_some_and(|last| last != direction) {
+            self.reversals += 1;
+            if self.reversals > SETTLING_REVERSALS {
+                self.reversal_log_sum += self.log_delta;
+            }
+            self.step = (self.step * STEP_SHRINK).max(MIN_STEP);
+        }
+        self.last_move = Some(direction);
+
+        let moved = match direction {
+            Direction::Harder => self.log_delta - self.step,
+            Direction::Easier => self.log_delta + self.step,
+        };
+        self.log_delta = moved.clamp(self.floor, self.ceiling);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn two_hits_make_it_harder_and_one_miss_easier() {
+        let mut staircase = Staircase::new(0.005, 0.05);
+        let start = staircase.delta();
+
+        staircase.record(true);
+        assert_eq!(staircase.delta(), start);
+        staircase.record(true);
+        let harder = staircase.delta();
+        assert!(harder < start);
+
+        staircase.record(false);
+        assert!(staircase.delta() > harder);
+        assert_eq!(staircase.reversals(), 1);
+        assert_eq!(staircase.threshold(), None);
+
+        // A hit then a miss is not two hits: the run starts over.
+        let before = staircase.delta();
+        staircase.record(true);
+        staircase.record(false);
+        assert!(staircase.delta() > before);
+    }
+
+    #[test]
+    fn the_delta_stays_between_floor_and_ceiling() {
+        let mut staircase = Staircase::new(0.005, 0.05);
+        for _ in 0..10 {
+            staircase.record(false);
+        }
+        assert!((staircase.delta() - 0.05).abs() < 1e-6);
+
+        for _ in 0..200 {
+            staircase.record(true);
+        }
+        assert!((staircase.delta() - 0.005).abs() < 1e-6);
+    }
+
+    /// A simulated player who sees any delta above 0.02 and guesses one in
+    /// ten below it: the staircase ends up hovering around their threshold.
+    #[test]
+    fn it_settles_near_the_players_threshold() {
+        let mut staircase = Staircase::new(0.005, 0.05);
+        let mut recent = Vec::new();
+        for round in 0..400 {
+            let correct = staircase.delta() > 0.02 || round % 10 == 0;
+            staircase.record(correct);
+            if round >= 200 {
+                recent.push(staircase.delta());
+            }
+        }
+
+        let mean = recent.iter().sum::<f32>() / recent.len() as f32;
+        assert!((0.014..0.03).contains(&mean), "settled at {mean}");
+
+        let threshold = staircase.threshold().unwrap();
+        assert!((0.014..0.03).contains(&threshold), "estimated {threshold}");
+    }
+}
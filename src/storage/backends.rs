@@ -0,0 +1,388 @@
+//! The places values can be kept.
+//!
+//! `LocalStorage` in the browser, `FileStorage` on the desktop, and
+//! `MemoryStorage` for a desktop with nowhere to write and for tests, which
+//! get a store of their own that starts empty and is gone when they finish.
+
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+
+use super::StorageBackend;
+
+/// The browser's `localStorage`.
+#[cfg(target_arch = "wasm32")]
+pub struct LocalStorage;
+
+#[cfg(target_arch = "wasm32")]
+impl LocalStorage {
+    fn storage() -> Option<web_sys::Storage> {
+        web_sys::window()?.local_storage().ok()?
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+impl StorageBackend for LocalStorage {
+    fn load(&self, key: &str) -> Option<String> {
+        Self::storage()?.get_item(key).ok()?
+    }
+
+    fn save(&self, key: &str, value: &str) {
+        // Storage can be unavailable (private mode, quota). Losing a best
+        // score is not worth interrupting play over.
+        if let Some(storage) = Self::storage() {
+            let _ = storage.set_item(key, value);
+        }
+    }
+
+    fn remove(&self, key: &str) {
+        if let Some(storage) = Self::storage() {
+            let _ = storage.remove_item(key);
+        }
+    }
+
+    fn keys(&self) -> Vec<String> {
+        let Some(storage) = Self::storage() else {
+            return Vec::new();
+        };
+        let length = storage.length().unwrap_or(0);
+        (0..length)
+            .filter_map(|index| storage.key(index).ok().flatten())
+            .collect()
+    }
+}
+
+/// Values held for the session and no longer.
+#[derive(Default)]
+pub struct MemoryStorage {
+    values: Mutex<BTreeMap<String, String>>,
+}
+
+impl StorageBackend for MemoryStorage {
+    fn load(&self, key: &str) -> Option<String> {
+        self.values.lock().ok()?.get(key).cloned()
+    }
+
+    fn save(&self, key: &str, value: &str) {
+        if let Ok(mut values) = self.values.lock() {
+            values.insert(key.to_string(), value.to_string());
+        }
+    }
+
+    fn remove(&self, key: &str) {
+        if let Ok(mut values) = self.values.lock() {
+            values.remove(key);
+        }
+    }
+
+    fn keys(&self) -> Vec<String> {
+        self.values
+            .lock()
+            .map(|values| values.keys().cloned().collect())
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+pub use file::FileStorage;
+
+/// The desktop backend: every key in one text file, `key=value` a line.
+///
+/// Read once, when the store is opened, and kept in memory after that; every
+/// change writes the whole file again. It is a handful of short lines, and
+/// writing it whole is what makes the write atomic: the new contents go to a
+/// temporary file beside it, which is then renamed over the old one, so a
+/// crash or a killed process leaves either the old file or the new one and
+/// never half of each.
+///
+/// A file that cannot be read as text is moved aside to `storage.corrupt`
+/// rather than overwritten by the next save, and the game carries on as if
+/// nothing had been stored. A line that does not parse is skipped on its own;
+/// the rest of the file still counts.
+///
+/// A file that is there but cannot be read at all (no permission, a failing
+/// disk, a directory by that name) is left alone, and nothing is written for
+/// the rest of the session. Every save writes the whole file, so the first
+/// one would otherwise replace the player's progress with the little this
+/// session has seen.
+#[cfg(not(target_arch = "wasm32"))]
+mod file {
+    use std::collections::BTreeMap;
+    use std::fs;
+    use std::io::{ErrorKind, Write};
+    use std::path::{Path, PathBuf};
+    use std::sync::Mutex;
+
+    use bevy::log::warn;
+
+    use crate::storage::StorageBackend;
+
+    const FILE_NAME: &str = "storage.txt";
+
+    /// Overrides where the file lives. For running two copies side by side, or
+    /// a test that must not touch the real progress.
+    const DIR_VARIABLE: &str = "COLOR_PUZZLE_DATA_DIR";
+
+    pub struct FileStorage {
+        path: PathBuf,
+        values: Mutex<BTreeMap<String, String>>,
+        /// Cleared when the file was there and could not be read.
+        writable: bool,
+    }
+
+    impl FileStorage {
+        /// The store in the platform's data directory, or `None` on a machine
+        /// that does not say where that is.
+        pub fn open() -> Option<Self> {
+            Some(Self::at(path()?))
+        }
+
+        pub fn at(path: PathBuf) -> Self {
+            let (values, writable) = match read(&path) {
+                Some(values) => (values, true),
+                None => (BTreeMap::new(), false),
+            };
+            Self {
+                path,
+                values: Mutex::new(values),
+                writable,
+            }
+        }
+
+        fn change(&self, apply: impl FnOnce(&mut BTreeMap<String, String>) -> bool) {
+            let Ok(mut values) = self.values.lock() else {
+                return;
+            };
+            if !apply(&mut values) || !self.writable {
+                return;
+            }
+
+            // As in the browser: a save that fails (a read-only home, a full
+            // disk) is not worth interrupting play over. The value is still
+            // held for the rest of the session.
+            if let Err(error) = write(&self.path, &values) {
+                warn!("could not save progress to {}: {}", self.path.display(), error);
+            }
+        }
+    }
+
+    impl StorageBackend for FileStorage {
+        fn load(&self, key: &str) -> Option<String> {
+            self.values.lock().ok()?.get(key).cloned()
+        }
+
+        fn save(&self, key: &str, value: &str) {
+            self.change(|values| {
+                if values.get(key).is_some_and(|stored| stored == value) {
+                    return false;
+                }
+                values.insert(key.to_string(), value.to_string());
+                true
+            });
+        }
+
+        fn remove(&self, key: &str) {
+            self.change(|values| values.remove(key).is_some());
+        }
+
+        fn keys(&self) -> Vec<String> {
+            self.values
+                .lock()
+                .map(|values| values.keys().cloned().collect())
+                .unwrap_or_default()
+        }
+    }
+
+    /// Where the file goes: the platform's per-user data directory, under the
+    /// game's name. Worked out from the environment by hand rather than with a
+    /// crate, which would be a dependency for three lines.
+    fn path() -> Option<PathBuf> {
+        let dir = match std::env::var_os(DIR_VARIABLE) {
+            Some(dir) => PathBuf::from(dir),
+            None => data_dir()?.join("color-puzzle"),
+        };
+        Some(dir.join(FILE_NAME))
+    }
+
+    #[cfg(target_os = "windows")]
+    fn data_dir() -> Option<PathBuf> {
+        std::env::var_os("APPDATA").map(PathBuf::from)
+    }
+
+    #[cfg(target_os = "macos")]
+    fn data_dir() -> Option<PathBuf> {
+        let home = std::env::var_os("HOME")?;
+        Some(PathBuf::from(home).join("Library/Application Support"))
+    }
+
+    #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+    fn data_dir() -> Option<PathBuf> {
+        if let Some(dir) = std::env::var_os("XDG_DATA_HOME").filter(|dir| !dir.is_empty()) {
+            return Some(PathBuf::from(dir));
+        }
+        let home = std::env::var_os("HOME")?;
+        Some(PathBuf::from(home).join(".local/share"))
+    }
+
+    /// What the file holds, or `None` if it is there and could not be read,
+    /// in which case it must not be written over.
+    fn read(path: &Path) -> Option<BTreeMap<String, String>> {
+        let bytes = match fs::read(path) {
+            Ok(bytes) => bytes,
+            // Nothing stored yet, which is every first launch.
+            Err(error) if error.kind() == ErrorKind::NotFound => return Some(BTreeMap::new()),
+            Err(error) => {
+                warn!(
+                    "could not read progress from {}: {}; nothing will be saved this session",
+                    path.display(),
+                    error
+                );
+                return None;
+            }
+        };
+
+        match String::from_utf8(bytes) {
+            Ok(text) => Some(parse(&text)),
+            Err(_) => {
+                let aside = path.with_extension("corrupt");
+                warn!(
+                    "progress file {} is unreadable; moved to {}",
+                    path.display(),
+                    aside.display()
+                );
+                let _ = fs::rename(path, aside);
+                Some(BTreeMap::new())
+            }
+        }
+    }
+
+    fn write(path: &Path, values: &BTreeMap<String, String>) -> std::io::Result<()> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+
+        let temporary = path.with_extension("tmp");
+        let mut file = fs::File::create(&temporary)?;
+        file.write_all(render(values).as_bytes())?;
+        // On disk before the rename, or a power cut can leave the rename done
+        // and the contents not.
+        file.sync_all()?;
+        drop(file);
+
+        fs::rename(&temporary, path)
+    }
+
+    /// One `key=value` a line. The key ends at the first `=`: keys never hold
+    /// one, values often do. A newline or a backslash in a value is escaped,
+    /// since a stored value is otherwise free to hold anything.
+    fn render(values: &BTreeMap<String, String>) -> String {
+        let mut text = String::new();
+        for (key, value) in values {
+            text.push_str(key);
+            text.push('=');
+            for c in value.chars() {
+                match c {
+                    '\\' => text.push_str("\\\\"),
+                    '\n' => text.push_str("\\n"),
+                    '\r' => text.push_str("\\r"),
+                    c => text.push(c),
+                }
+            }
+            text.push('\n');
+        }
+        text
+    }
+
+    fn parse(text: &str) -> BTreeMap<String, String> {
+        let mut values = BTreeMap::new();
+        for line in text.lines() {
+            let Some((key, raw)) = line.split_once('=') else {
+                continue;
+            };
+            if key.is_empty() {
+                continue;
+            }
+
+            let mut value = String::with_capacity(raw.len());
+            let mut chars = raw.chars();
+            while let Some(c) = chars.next() {
+                if c != '\\' {
+                    value.push(c);
+                    continue;
+                }
+                match chars.next() {
+                    Some('n') => value.push('\n'),
+                    Some('r') => value.push('\r'),
+                    Some(other) => value.push(other),
+                    None => {}
+                }
+            }
+            values.insert(key.to_string(), value);
+        }
+        values
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[test]
+        fn values_come_back_as_they_were_written() {
+            let dir = std::env::temp_dir().join(format!("color-puzzle-{}", std::process::id()));
+            let path = dir.join(FILE_NAME);
+
+            let store = FileStorage::at(path.clone());
+            store.save("color_puzzle.best", "infinite=12;mosaic=3");
+            store.save("odd", "a\\b\nc=d");
+            store.save("gone", "1");
+            store.remove("gone");
+            assert!(!path.with_extension("tmp").exists());
+
+            let reopened = FileStorage::at(path);
+            assert_eq!(reopened.keys(), vec!["color_puzzle.best", "odd"]);
+            assert_eq!(reopened.load("odd").as_deref(), Some("a\\b\nc=d"));
+
+            fs::remove_dir_all(dir).unwrap();
+        }
+
+        #[test]
+        fn a_corrupt_file_is_set_aside_and_read_as_empty() {
+            let dir = std::env::temp_dir()
+                .join(format!("color-puzzle-corrupt-{}", std::process::id()));
+            fs::create_dir_all(&dir).unwrap();
+            let path = dir.join(FILE_NAME);
+            fs::write(&path, [0xff, 0xfe, b'=', 0x80]).unwrap();
+
+            assert_eq!(read(&path), Some(BTreeMap::new()));
+            assert!(!path.exists());
+            assert!(path.with_extension("corrupt").exists());
+
+            // A line without a key is dropped; the rest is kept.
+            let values = parse("=lost\ngarbage\ncolor_puzzle.volume=4\n");
+            assert_eq!(values.len(), 1);
+            assert_eq!(values["color_puzzle.volume"], "4");
+
+            fs::remove_dir_all(dir).unwrap();
+        }
+
+        /// A file that is there but cannot be read is not taken for an empty
+        /// one: saving carries on in memory, and what is on disk stays as it
+        /// was.
+        #[test]
+        fn a_file_that_cannot_be_read_is_never_written_over() {
+            let dir = std::env::temp_dir()
+                .join(format!("color-puzzle-unreadable-{}", std::process::id()));
+            // A directory where the file should be fails to read on every
+            // platform, whoever runs the test.
+            let path = dir.join(FILE_NAME);
+            fs::create_dir_all(path.join("kept")).unwrap();
+
+            let store = FileStorage::at(path.clone());
+            store.save("color_puzzle.volume", "4");
+            assert_eq!(store.load("color_puzzle.volume").as_deref(), Some("4"));
+            assert!(path.join("kept").is_dir());
+            assert!(!path.with_extension("tmp").exists());
+
+            fs::remove_dir_all(dir).unwrap();
+        }
+    }
+}
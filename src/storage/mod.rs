@@ -0,0 +1,70 @@
+//! Tiny key/value persistence.
+//!
+//! The game ships as WebAssembly on GitHub Pages, where "persistent" means the
+//! browser's `localStorage`. Native builds keep the same keys and the same
+//! values in one file in the platform's data directory, so a desktop session
+//! picks up where the last one stopped rather than starting from nothing.
+//!
+//! A best score that survives a reload is what turns a session into a series:
+//! without a stored number there is nothing to beat, and the next run has no
+//! stake.
+//!
+//! Each stored thing still owns its own format — `BestScores` knows what its
+//! string looks like, and nothing here does. What lives here is where the
+//! strings go (`StorageBackend`) and which version of those formats is on disk
+//! (`schema`), so that changing one is a numbered migration rather than a
+//! parser that has to keep reading every shape the value ever had.
+
+mod backends;
+pub mod schema;
+pub mod transfer;
+
+use std::sync::OnceLock;
+
+pub use backends::MemoryStorage;
+
+/// Somewhere string values can be kept under string keys.
+///
+/// Failures are swallowed rather than returned: there is nothing the game can
+/// do about a full disk or a private window except carry on, and every caller
+/// would end up writing the same `let _ =`.
+pub trait StorageBackend: Send + Sync {
+    fn load(&self, key: &str) -> Option<String>;
+    fn save(&self, key: &str, value: &str);
+    fn remove(&self, key: &str);
+    /// Every key held, this game's and anything else's.
+    fn keys(&self) -> Vec<String>;
+}
+
+static BACKEND: OnceLock<Box<dyn StorageBackend>> = OnceLock::new();
+
+/// The store the game reads and writes, brought up to the current schema the
+/// first time it is asked for — so no value is ever read in an old format.
+pub fn backend() -> &'static dyn StorageBackend {
+    BACKEND
+        .get_or_init(|| {
+            let backend = platform_backend();
+            schema::migrate(backend.as_ref());
+            backend
+        })
+        .as_ref()
+}
+
+#[cfg(target_arch = "wasm32")]
+fn platform_backend() -> Box<dyn StorageBackend> {
+    Box::new(backends::LocalStorage)
+}
+
+/// The data directory's file, or memory on a machine that has no data
+/// directory to speak of.
+#[cfg(not(target_arch = "wasm32"))]
+fn platform_backend() -> Box<dyn StorageBackend> {
+    match backends::FileStorage::open() {
+        Some(file) => Box::new(file),
+        None => Box::new(MemoryStorage::default()),
+    }
+}
+
+pub fn save(key: &str, value: &str) {
+    backend().save(key, value);
+}
@@ -0,0 +1,117 @@
+//! Which version of the stored formats is on disk, and how to get from each
+//! version to the next.
+//!
+//! The version is one more key beside the data, `color_puzzle.schema`. Data
+//! written before it existed has no such key and counts as version 0. On the
+//! first read of a session every step from the stored version to
+//! `CURRENT_VERSION` runs in order, each stamping its own number as it
+//! finishes, so a session that dies halfway resumes from the step it was on
+//! rather than running one twice.
+//!
+//! Changing a stored format means: bump `CURRENT_VERSION`, add a step that
+//! rewrites the old values into the new shape, and make the type's parser read
+//! the new shape only. The parser stays small, and the old shapes are written
+//! down in exactly one place, with a test.
+//!
+//! Data from a *newer* build is left as it is. Rewriting it would need steps
+//! this build does not have, and every loader already skips what it does not
+//! recognise, so reading it as well as possible and writing nothing back is
+//! the kindest thing to do with it.
+
+use bevy::log::warn;
+
+use super::StorageBackend;
+use crate::game::score::resources::SavedRun;
+
+pub const SCHEMA_KEY: &str = "color_puzzle.schema";
+
+pub const CURRENT_VERSION: u32 = 1;
+
+/// One step, from the version it is indexed at to the one after.
+type Migration = fn(&dyn StorageBackend);
+
+const MIGRATIONS: [Migration; CURRENT_VERSION as usize] = [v0_to_v1];
+
+/// The version the store's values are in. Zero for a store written before
+/// versions were, or one with nothing in it yet.
+pub fn stored_version(store: &dyn StorageBackend) -> u32 {
+    store
+        .load(SCHEMA_KEY)
+        .and_then(|raw| raw.trim().parse().ok())
+        .unwrap_or(0)
+}
+
+/// Brings the store up to `CURRENT_VERSION`. Returns the version it ends on,
+/// which is the stored one when that is newer than this build knows.
+pub fn migrate(store: &dyn StorageBackend) -> u32 {
+    let stored = stored_version(store);
+    if stored > CURRENT_VERSION {
+        warn!(
+            "stored progress is schema {}, newer than this build's {}; leaving it as it is",
+            stored, CURRENT_VERSION
+        );
+        return stored;
+    }
+
+    for version in stored..CURRENT_VERSION {
+        MIGRATIONS[version as usize](store);
+        store.save(SCHEMA_KEY, &(version + 1).to_string());
+    }
+
+    CURRENT_VERSION
+}
+
+/// The formats as they stood before there was a version.
+///
+/// - `saved_run` entries came in three shapes, `score`, `score:lives` and
+///   `score:lives:life=n,cut=n`, as each tail was added. All are rewritten in
+///   the full shape.
+/// - `muted` was replaced by `volume` and deliberately never carried over (see
+///   `Volume::load`). It is dropped rather than left to be read by mistake.
+fn v0_to_v1(store: &dyn StorageBackend) {
+    if let Some(raw) = store.load(SavedRun::STORAGE_KEY) {
+        store.save(SavedRun::STORAGE_KEY, &SavedRun::migrate_v1(&raw));
+    }
+    store.remove("color_puzzle.muted");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::puzzle::components::GameMode;
+    use crate::storage::MemoryStorage;
+
+    #[test]
+    fn an_unversioned_store_is_brought_up_to_date() {
+        let store = MemoryStorage::default();
+        store.save(SavedRun::STORAGE_KEY, "infinite=14;memory=6:2;mosaic=9:1:life=1,cut=0");
+        store.save("color_puzzle.muted", "1");
+
+        assert_eq!(migrate(&store), CURRENT_VERSION);
+        assert_eq!(stored_version(&store), CURRENT_VERSION);
+        assert_eq!(store.load("color_puzzle.muted"), None);
+
+        let runs = SavedRun::load_from(&store);
+        let infinite = runs.get(GameMode::Infinite).unwrap();
+        assert_eq!((infinite.score, infinite.lives), (14, 3));
+        assert_eq!(runs.get(GameMode::Memory).unwrap().lives, 2);
+        assert_eq!(runs.get(GameMode::Mosaic).unwrap().power_ups.count(
+            crate::game::puzzle::components::PowerUp::ExtraLife
+        ), 1);
+
+        // Running again changes nothing.
+        let before = store.load(SavedRun::STORAGE_KEY);
+        migrate(&store);
+        assert_eq!(store.load(SavedRun::STORAGE_KEY), before);
+    }
+
+    #[test]
+    fn a_store_from_a_newer_build_is_left_alone() {
+        let store = MemoryStorage::default();
+        store.save(SCHEMA_KEY, &(CURRENT_VERSION + 1).to_string());
+        store.save("color_puzzle.muted", "1");
+
+        assert_eq!(migrate(&store), CURRENT_VERSION + 1);
+        assert_eq!(store.load("color_puzzle.muted").as_deref(), Some("1"));
+    }
+}
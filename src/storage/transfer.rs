@@ -0,0 +1,384 @@
+//! Moving all progress from one browser or machine to another.
+//!
+//! Every `color_puzzle.*` value but the day's attempt goes out as one text
+//! blob. Pasting it into the other copy of the game replaces what that copy
+//! had, once the player has seen what will change.
+//!
+//! The blob carries the schema its values are in, and an import runs them
+//! through `schema::migrate`, so progress exported by an older build comes in
+//! in today's shapes. A blob from a *newer* build is refused outright. This
+//! build cannot read those values, and writing them in would hand every loader
+//! data it would half understand and then save back over.
+//!
+//! Layout, after the `CPSAVE` prefix and before the base64:
+//!
+//! ```text
+//! format u8 | schema | count | count x ( key, value ) | FNV-1a 32 LE
+//! ```
+//!
+//! The schema and the count are varints. Keys and values are length-prefixed
+//! UTF-8. The checksum is there to catch a blob cut short in a chat window. It
+//! does not stop anyone editing their own records, and nothing could: the
+//! values are already sitting in their own browser to edit.
+
+use std::collections::BTreeMap;
+
+use super::schema::{self, CURRENT_VERSION, SCHEMA_KEY};
+use super::{MemoryStorage, StorageBackend};
+use crate::encoding::{base64_decode, base64_encode, fnv1a, write_bytes, write_varint, Reader};
+use crate::game::achievements::Achievements;
+use crate::game::practice::Practice;
+use crate::game::puzzle::components::GameMode;
+use crate::game::score::resources::{BestScores, SavedRun};
+use crate::vision_test::resources::VisionProfile;
+
+const PREFIX: &str = "CPSAVE";
+
+/// Bumped if the layout above changes. The schema of the values inside is a
+/// separate number and moves on its own.
+const FORMAT: u8 = 1;
+
+/// Every key the game owns starts with this.
+const KEY_PREFIX: &str = "color_puzzle.";
+
+/// Owned by the game but not progress. The schema travels in the header, and
+/// the share request is a message to the page that is gone once it is read.
+///
+/// The daily attempt stays where it was played. It is what holds a copy to
+/// one scored attempt a day, and importing a blob made before today's run, or
+/// an empty one, would otherwise clear it and open the day again.
+const NOT_EXPORTED: [&str; 3] = [
+    SCHEMA_KEY,
+    "color_puzzle.share_request",
+    "color_puzzle.daily",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TransferError {
+    /// Not a progress code, or one cut short.
+    Malformed,
+    /// The text was changed after it was made.
+    Checksum,
+    /// Made by a build newer than this one.
+    Newer,
+}
+
+impl TransferError {
+    /// What the settings screen says when a paste is refused.
+    pub fn message(&self) -> &'static str {
+        match self {
+            TransferError::Malformed | TransferError::Checksum => "CODIGO INVALIDO",
+            TransferError::Newer => "CODIGO DE UMA VERSAO MAIS NOVA",
+        }
+    }
+}
+
+/// Progress read from a blob and brought up to this build's schema, waiting
+/// for the player to say yes.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Import {
+    values: BTreeMap<String, String>,
+}
+
+fn is_exported(key: &str) -> bool {
+    key.starts_with(KEY_PREFIX) && !NOT_EXPORTED.contains(&key)
+}
+
+fn exported_values(store: &dyn StorageBackend) -> BTreeMap<String, String> {
+    store
+        .keys()
+        .into_iter()
+        .filter(|key| is_exported(key))
+        .filter_map(|key| store.load(&key).map(|value| (key, value)))
+        .collect()
+}
+
+/// Everything the store holds for the game, as one blob.
+pub fn export(store: &dyn StorageBackend) -> String {
+    let values = exported_values(store);
+
+    let mut bytes = vec![FORMAT];
+    write_varint(&mut bytes, schema::stored_version(store) as u64);
+    write_varint(&mut bytes, values.len() as u64);
+    for (key, value) in &values {
+        write_bytes(&mut bytes, key.as_bytes());
+        write_bytes(&mut bytes, value.as_bytes());
+    }
+
+    let checksum = fnv1a(&bytes);
+    bytes.extend_from_slice(&checksum.to_le_bytes());
+
+    format!("{PREFIX}{}", base64_encode(&bytes))
+}
+
+/// Reads a blob back and migrates it. Whitespace anywhere is ignored, as it
+/// is in share codes.
+pub fn decode(text: &str) -> Result<Import, TransferError> {
+    let text: String = text.chars().filter(|c| !c.is_whitespace()).collect();
+    let body = text.strip_prefix(PREFIX).ok_or(TransferError::Malformed)?;
+    let bytes = base64_decode(body).ok_or(TransferError::Malformed)?;
+
+    if bytes.len() < 5 {
+        return Err(TransferError::Malformed);
+    }
+    let (payload, checksum) = bytes.split_at(bytes.len() - 4);
+    if fnv1a(payload).to_le_bytes() != checksum {
+        return Err(TransferError::Checksum);
+    }
+
+    let mut reader = Reader::new(payload, TransferError::Malformed);
+    match reader.byte()? {
+        FORMAT => {}
+        // A later layout can only have come from a later build.
+        format if format > FORMAT => return Err(TransferError::Newer),
+        _ => return Err(TransferError::Malformed),
+    }
+
+    let version = reader.varint()?;
+    if version > CURRENT_VERSION as u64 {
+        return Err(TransferError::Newer);
+    }
+
+    // Each pair is at least its two length bytes, so a count the rest of the
+    // blob could not hold is a damaged blob, not a reason to allocate.
+    let count = reader.varint()?;
+    if count > (reader.remaining() / 2) as u64 {
+        return Err(TransferError::Malformed);
+    }
+
+    let store = MemoryStorage::default();
+    for _ in 0..count {
+        let key = reader.string()?;
+        let value = reader.string()?;
+        if is_exported(&key) {
+            store.save(&key, &value);
+        }
+    }
+    if reader.remaining() != 0 {
+        return Err(TransferError::Malformed);
+    }
+
+    store.save(SCHEMA_KEY, &version.to_string());
+    schema::migrate(&store);
+
+    Ok(Import {
+        values: exported_values(&store),
+    })
+}
+
+impl Import {
+    fn store(&self) -> MemoryStorage {
+        let store = MemoryStorage::default();
+        for (key, value) in &self.values {
+            store.save(key, value);
+        }
+        store
+    }
+
+    /// What importing would change in `current`, a line per thing, read
+    /// through the same loaders the game uses so the lines say what the player
+    /// would see rather than what the strings are.
+    ///
+    /// Losses are listed as plainly as gains. An import replaces, it does not
+    /// merge, and a best score going down is the line most worth reading.
+    pub fn preview(&self, current: &dyn StorageBackend) -> Vec<String> {
+        let incoming = self.store();
+        let mut lines = Vec::new();
+
+        let (before, after) = (BestScores::load_from(current), BestScores::load_from(&incoming));
+        for mode in GameMode::iter() {
+            if before.get(mode) != after.get(mode) {
+                lines.push(format!(
+                    "RECORDE {}: {} -> {}",
+                    mode.as_str().to_uppercase(),
+                    before.get(mode),
+                    after.get(mode)
+                ));
+            }
+        }
+
+        let (before, after) = (
+            Achievements::load_from(current),
+            Achievements::load_from(&incoming),
+        );
+        if before.unlocked_count() != after.unlocked_count() {
+            lines.push(format!(
+                "METAS: {} -> {} DE {}",
+                before.unlocked_count(),
+                after.unlocked_count(),
+                Achievements::total()
+            ));
+        }
+        if before.modes_played_count() != after.modes_played_count() {
+            lines.push(format!(
+                "MODOS JOGADOS: {} -> {}",
+                before.modes_played_count(),
+                after.modes_played_count()
+            ));
+        }
+
+        let (before, after) = (SavedRun::load_from(current), SavedRun::load_from(&incoming));
+        for mode in GameMode::iter() {
+            let score = |runs: &SavedRun| runs.get(mode).map(|run| run.score);
+            if score(&before) == score(&after) {
+                continue;
+            }
+            let describe = |score: Option<usize>| match score {
+                Some(score) => score.to_string(),
+                None => "-".to_string(),
+            };
+            lines.push(format!(
+                "PARTIDA SALVA {}: {} -> {}",
+                mode.as_str().to_uppercase(),
+                describe(score(&before)),
+                describe(score(&after))
+            ));
+        }
+
+        let (before, after) = (Practice::load_from(current), Practice::load_from(&incoming));
+        if before.picks() != after.picks() {
+            lines.push(format!("JOGADAS DE TREINO: {} -> {}", before.picks(), after.picks()));
+        }
+
+        let (before, after) = (
+            VisionProfile::load_from(current),
+            VisionProfile::load_from(&incoming),
+        );
+        if before.tests() != after.tests() {
+            lines.push(format!("TESTES DE VISAO: {} -> {}", before.tests(), after.tests()));
+        }
+
+        // The volume and the custom game's dials have no line of their own:
+        // neither is progress anyone moves a save for. They are still
+        // replaced, and the list should not claim otherwise.
+        if lines.is_empty() && self.values != exported_values(current) {
+            lines.push("SO AJUSTES MUDAM".to_string());
+        }
+        if lines.is_empty() {
+            lines.push("NADA MUDA".to_string());
+        }
+
+        lines
+    }
+
+    /// Replaces everything the game keeps in `store` with the import, but for
+    /// what is not exported, which is left as it is.
+    ///
+    /// Keys the import does not have are removed rather than kept. Keeping
+    /// them would make the result a mix of both copies that neither of them
+    /// ever was, such as a saved run for a mode the imported scores never
+    /// reached.
+    pub fn apply(&self, store: &dyn StorageBackend) {
+        for key in store.keys() {
+            if is_exported(&key) && !self.values.contains_key(&key) {
+                store.remove(&key);
+            }
+        }
+        for (key, value) in &self.values {
+            store.save(key, value);
+        }
+        store.save(SCHEMA_KEY, &CURRENT_VERSION.to_string());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::score::resources::DailyChallenge;
+
+    fn store(values: &[(&str, &str)]) -> MemoryStorage {
+        let store = MemoryStorage::default();
+        for (key, value) in values {
+            store.save(key, value);
+        }
+        store
+    }
+
+    #[test]
+    fn progress_moves_from_one_store_to_another() {
+        let from = store(&[
+            (SCHEMA_KEY, "1"),
+            ("color_puzzle.best_scores", "infinite=40;mosaic=12"),
+            ("color_puzzle.achievements", "first_point,streak_5"),
+            ("color_puzzle.share_request", "score=40"),
+            ("other_site.token", "secret"),
+        ]);
+        let to = store(&[
+            ("color_puzzle.best_scores", "infinite=55"),
+            ("color_puzzle.saved_run", "memory=6:2:life=0,cut=0"),
+            ("other_site.token", "kept"),
+        ]);
+
+        let import = decode(&export(&from)).unwrap();
+        assert_eq!(
+            import.preview(&to),
+            vec![
+                "RECORDE INFINITO: 55 -> 40",
+                "RECORDE MOSAICO: 0 -> 12",
+                "METAS: 0 -> 2 DE 11",
+                "PARTIDA SALVA MEMORIA: 6 -> -",
+            ]
+        );
+
+        import.apply(&to);
+        assert_eq!(to.load("color_puzzle.best_scores"), from.load("color_puzzle.best_scores"));
+        assert_eq!(to.load("color_puzzle.saved_run"), None);
+        assert_eq!(to.load("color_puzzle.share_request"), None);
+        assert_eq!(to.load("other_site.token").as_deref(), Some("kept"));
+        assert_eq!(schema::stored_version(&to), CURRENT_VERSION);
+
+        assert_eq!(import.preview(&to), vec!["NADA MUDA"]);
+    }
+
+    /// Neither a blob from before today's run nor an empty one gives the day's
+    /// scored attempt back, and one made after it does not carry it over.
+    #[test]
+    fn an_import_keeps_the_local_daily_attempt() {
+        let played = store(&[("color_puzzle.daily", "day=20380;score=17")]);
+        let before = store(&[("color_puzzle.daily", "day=20379;score=4")]);
+
+        for from in [before, MemoryStorage::default()] {
+            let to = store(&[("color_puzzle.daily", "day=20380;score=17")]);
+            decode(&export(&from)).unwrap().apply(&to);
+            assert_eq!(to.load("color_puzzle.daily").as_deref(), Some("day=20380;score=17"));
+            assert_eq!(DailyChallenge::load_from(&to).result_for(20380), Some(17));
+        }
+
+        let fresh = MemoryStorage::default();
+        let import = decode(&export(&played)).unwrap();
+        assert_eq!(import.preview(&fresh), vec!["NADA MUDA"]);
+        import.apply(&fresh);
+        assert_eq!(fresh.load("color_puzzle.daily"), None);
+    }
+
+    #[test]
+    fn an_old_blob_is_migrated_and_a_newer_one_refused() {
+        let old = store(&[
+            ("color_puzzle.saved_run", "infinite=14"),
+            ("color_puzzle.muted", "1"),
+        ]);
+        let import = decode(&export(&old)).unwrap();
+        let migrated = import.store();
+        assert_eq!(migrated.load("color_puzzle.muted"), None);
+        assert_eq!(SavedRun::load_from(&migrated).get(GameMode::Infinite).unwrap().lives, 3);
+
+        let newer = store(&[(SCHEMA_KEY, &(CURRENT_VERSION + 1).to_string())]);
+        assert_eq!(decode(&export(&newer)), Err(TransferError::Newer));
+    }
+
+    #[test]
+    fn a_damaged_blob_is_refused() {
+        let text = export(&store(&[("color_puzzle.volume", "2")]));
+
+        assert_eq!(decode("CPSAVE"), Err(TransferError::Malformed));
+        assert!(decode(&text[..text.len() - 3]).is_err());
+
+        let mut changed = text.into_bytes();
+        let at = changed.len() - 8;
+        changed[at] = if changed[at] == b'A' { b'B' } else { b'A' };
+        assert_eq!(
+            decode(&String::from_utf8(changed).unwrap()),
+            Err(TransferError::Checksum)
+        );
+    }
+}
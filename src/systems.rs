@@ -1,5 +1,6 @@
 use bevy::app::AppExit;
 use bevy::prelude::*;
+use crate::oklab;
 use crate::AppState;
 
 
@@ -13,35 +14,66 @@ use crate::AppState;
 /// That sweep is the round's second channel of information, and the reason the
 /// board can be a regular lattice full of deliberate holes without the round
 /// becoming a lottery: the player who watched knows which hole appeared last.
+///
+/// So what the ground shows between two stops matters as much as the stops.
+/// A lerp in sRGB goes through greyer, darker colours than either end, and on
+/// a board full of near neighbours one of those can sit close enough to a
+/// group to half-melt it on the way past. The sweep runs in Oklab instead,
+/// where the straight line between two colours is the one the eye sees as
+/// straight, and nothing between them looks like anything else on the board.
 #[derive(Component, Debug, Reflect)]
 pub struct BackgroundTranstion {
     /// Where the ground starts, then every color it visits. The last entry is
     /// the answer's color.
-    path: Vec<Color>,
+    path: Vec<SweepStop>,
     time: f32,
     current_time: f32,
 }
 
+/// A color on the ground's path, and how the ground arrives at it.
+#[derive(Debug, Clone, Copy, Reflect)]
+pub struct SweepStop {
+    pub color: Color,
+    pub easing: Easing,
+}
+
+/// The shape of one leg of the sweep, from the stop before to this one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Reflect)]
+pub enum Easing {
+    /// Leaves at speed and slows into the stop. For the first leg, which
+    /// starts from wherever the last round left the ground: nobody is
+    /// watching that colour, so there is no reason to linger on it.
+    Out,
+    /// Slow at both ends. The ground comes to rest on each group's colour
+    /// for a moment, which is the moment the group melts, instead of
+    /// sliding through it in a frame.
+    InOut,
+}
+
+impl Easing {
+    /// Maps the leg's elapsed share, 0..1, to how far along it the ground is.
+    pub fn apply(&self, t: f32) -> f32 {
+        let t = t.clamp(0.0, 1.0);
+        match self {
+            Easing::Out => 1.0 - (1.0 - t) * (1.0 - t),
+            Easing::InOut => t * t * (3.0 - 2.0 * t),
+        }
+    }
+}
+
 impl Default for BackgroundTranstion {
     fn default() -> Self {
         Self {
-            path: vec![Color::srgb(0.0, 0.0, 0.0)],
+            path: vec![SweepStop {
+                color: Color::srgb(0.0, 0.0, 0.0),
+                easing: Easing::InOut,
+            }],
             time: 1.0,
             current_time: 1.0,
         }
     }
 }
 
-/// Componentwise lerp between two colors.
-pub fn lerp_color(from: Color, to: Color, amount: f32) -> Color {
-    let amount = amount.clamp(0.0, 1.0);
-    Color::srgb(
-        from.to_srgba().red + (to.to_srgba().red - from.to_srgba().red) * amount,
-        from.to_srgba().green + (to.to_srgba().green - from.to_srgba().green) * amount,
-        from.to_srgba().blue + (to.to_srgba().blue - from.to_srgba().blue) * amount,
-    )
-}
-
 impl BackgroundTranstion {
     pub fn is_in_transition(&self) -> bool {
         self.current_time < self.time
@@ -50,25 +82,36 @@ impl BackgroundTranstion {
     /// Starts a sweep from the ground's current color through `stops`, which
     /// must end on the color the ground is to settle at.
     pub fn sweep(&mut self, from: Color, stops: Vec<Color>, seconds: f32) {
-        self.path = std::iter::once(from).chain(stops).collect();
+        let start = SweepStop {
+            color: from,
+            easing: Easing::InOut,
+        };
+        let stops = stops.into_iter().enumerate().map(|(index, color)| SweepStop {
+            color,
+            easing: if index == 0 { Easing::Out } else { Easing::InOut },
+        });
+        self.path = std::iter::once(start).chain(stops).collect();
         self.time = seconds.max(0.001);
         self.current_time = 0.0;
     }
 
     /// Parks the ground on one color, for the screens that are not a round.
     pub fn set_solid(&mut self, color: Color) {
-        self.path = vec![color];
+        self.path = vec![SweepStop {
+            color,
+            easing: Easing::InOut,
+        }];
         self.current_time = self.time;
     }
 
     pub fn get_current_color(&self) -> Color {
-        let Some(first) = self.path.first().copied() else {
+        let Some(first) = self.path.first() else {
             return Color::BLACK;
         };
 
         let segments = self.path.len().saturating_sub(1);
         if segments == 0 {
-            return first;
+            return first.color;
         }
 
         // Every stop gets the same slice of the second, so a five-color round
@@ -76,11 +119,12 @@ impl BackgroundTranstion {
         // dragging and the other rushing.
         let travelled = (self.current_time / self.time).clamp(0.0, 1.0) * segments as f32;
         let segment = (travelled.floor() as usize).min(segments - 1);
+        let to = self.path[segment + 1];
 
-        lerp_color(
-            self.path[segment],
-            self.path[segment + 1],
-            travelled - segment as f32,
+        oklab::mix(
+            self.path[segment].color,
+            to.color,
+            to.easing.apply(travelled - segment as f32),
         )
     }
 
@@ -149,3 +193,35 @@ pub fn exit_game(
         app_exit_event_writer.write(AppExit::Success);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(sweep: &mut BackgroundTranstion, share: f32) -> oklab::Oklab {
+        sweep.current_time = sweep.time * share;
+        oklab::from_color(sweep.get_current_color())
+    }
+
+    #[test]
+    fn the_sweep_rests_on_every_stop_and_keeps_lightness_between_them() {
+        let red = Color::srgb(0.9, 0.2, 0.2);
+        let green = Color::srgb(0.2, 0.8, 0.3);
+        let mut sweep = BackgroundTranstion::default();
+        sweep.sweep(Color::BLACK, vec![red, green], 1.0);
+
+        let close = |a: oklab::Oklab, b: Color| oklab::euclidean(a, oklab::from_color(b)) < 1e-3;
+        assert!(close(at(&mut sweep, 0.5), red));
+        assert!(close(at(&mut sweep, 1.0), green));
+        // Halfway through a leg is halfway in Oklab, whatever the easing.
+        assert!(close(at(&mut sweep, 0.75), oklab::mix(red, green, 0.5)));
+
+        // An sRGB lerp from red to green sinks darker than either end on the
+        // way. In Oklab the lightness runs straight from one to the other.
+        let (low, high) = (oklab::from_color(red).l, oklab::from_color(green).l);
+        for step in 0..=20 {
+            let l = at(&mut sweep, 0.5 + step as f32 / 40.0).l;
+            assert!(l >= low.min(high) - 1e-3 && l <= low.max(high) + 1e-3, "{l}");
+        }
+    }
+}
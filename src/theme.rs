@@ -55,6 +55,7 @@ pub const ACCENT: Color = Color::srgb(0.961, 0.647, 0.141);
 pub const LIME: Color = Color::srgb(0.639, 0.776, 0.078);
 pub const INFO: Color = Color::srgb(0.231, 0.510, 0.965);
 pub const PINK: Color = Color::srgb(0.925, 0.282, 0.600);
+pub const TEAL: Color = Color::srgb(0.078, 0.722, 0.651);
 
 // --- Buttons ---------------------------------------------------------------
 
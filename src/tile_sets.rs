@@ -0,0 +1,376 @@
+//! The tile sets `Mosaic` is dealt from, and which levels get which.
+//!
+//! A single fixed set of pipes went stale: after a few dozen rounds the player
+//! knew every piece, and the odd one out was the piece they had learned never
+//! to expect rather than the one the pattern ruled out. Sets are now data in
+//! `assets/tiles.ron` — arms with labels, tiles with weights, and which
+//! labels join — so a new family of pieces is an edit to that file, and the
+//! solver, the fairness check and the renderer take whatever it describes.
+//!
+//! Built into the binary and read once, like `assets/modes.ron` and for the
+//! same reasons; see `modes`. `main` reads it before the window opens, and
+//! `the_bundled_sets_are_valid` before that.
+
+use std::sync::OnceLock;
+
+use rand::prelude::*;
+use serde::Deserialize;
+
+use crate::asset_file::{self, FileError};
+use crate::wfc::{ArmStyle, Label, TileSet, TileShape, EDGES, MAX_POOL, NO_ARM};
+
+const FILE: &str = "assets/tiles.ron";
+
+const BUNDLED: &str = include_str!("../assets/tiles.ron");
+
+/// A set as the file writes it. See the file's header for what each field
+/// means, and `read_set` for what is checked before it becomes a [`TileSet`].
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct SetEntry {
+    name: String,
+    #[serde(default)]
+    from_level: Option<usize>,
+    #[serde(default)]
+    to_level: Option<usize>,
+    arms: Vec<ArmEntry>,
+    tiles: Vec<TileEntry>,
+    #[serde(default)]
+    joins: Vec<(String, String)>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ArmEntry {
+    label: String,
+    width: f32,
+    #[serde(default)]
+    lighten: f32,
+    #[serde(default)]
+    darken: f32,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct TileEntry {
+    name: String,
+    edges: Vec<String>,
+    #[serde(default)]
+    weight: Option<u32>,
+}
+
+/// A set and the levels it is dealt at.
+#[derive(Debug, Clone, PartialEq)]
+struct Band {
+    set: TileSet,
+    from_level: usize,
+    to_level: Option<usize>,
+}
+
+impl Band {
+    fn covers(&self, level: usize) -> bool {
+        level >= self.from_level && self.to_level.is_none_or(|last| level <= last)
+    }
+}
+
+/// Every set, in file order, each with its band.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TileSetTable {
+    bands: Vec<Band>,
+}
+
+impl TileSetTable {
+    /// The set a tile says it came from.
+    pub fn get(&self, id: u8) -> &TileSet {
+        // Ids are positions in this table, and only ever handed out by it.
+        &self.bands[id as usize].set
+    }
+
+    /// Every set, for tests that hold each one to the same rules.
+    #[cfg(test)]
+    pub fn sets(&self) -> impl Iterator<Item = &TileSet> {
+        self.bands.iter().map(|band| &band.set)
+    }
+
+    /// One of the sets dealt at `level`, picked with the round's generator so
+    /// that a seed always deals the same set.
+    pub fn for_level(&self, level: usize, rng: &mut impl Rng) -> &TileSet {
+        let level = level.max(1);
+        let sets: Vec<&TileSet> = self
+            .bands
+            .iter()
+            .filter(|band| band.covers(level))
+            .map(|band| &band.set)
+            .collect();
+        // `parse` refuses a table that leaves any level without one.
+        sets.choose(rng)
+            .copied()
+            .expect("every level has a tile set")
+    }
+}
+
+static TABLE: OnceLock<TileSetTable> = OnceLock::new();
+
+/// The bundled table. Panics on a broken file, with the reason; `Mosaic` has
+/// nothing to deal without one.
+pub fn table() -> &'static TileSetTable {
+    TABLE.get_or_init(|| parse(BUNDLED).unwrap_or_else(|error| panic!("{error}")))
+}
+
+/// Reads the file. RON does the reading; what is checked here is what RON
+/// cannot know, such as a tile naming an arm its set does not have.
+pub fn parse(source: &str) -> Result<TileSetTable, FileError> {
+    let error = |reason: String| FileError {
+        file: FILE,
+        line: 0,
+        reason,
+    };
+
+    let entries: Vec<SetEntry> = asset_file::deserialize(FILE, source)?;
+    if entries.len() > u8::MAX as usize + 1 {
+        return Err(error("too many sets".to_string()));
+    }
+
+    let mut bands: Vec<Band> = vec![];
+    for (id, entry) in entries.into_iter().enumerate() {
+        let name = entry.name.clone();
+        if bands.iter().any(|band| band.set.name == name) {
+            return Err(error(format!("set `{name}` defined twice")));
+        }
+        let band =
+            read_set(entry, id as u8).map_err(|reason| error(format!("set `{name}`: {reason}")))?;
+        bands.push(band);
+    }
+
+    // Every level up to one past the last band that ends, which is also where
+    // a set has to run on forever.
+    let last = bands
+        .iter()
+        .map(|band| band.to_level.unwrap_or(band.from_level))
+        .max()
+        .unwrap_or(0);
+    if let Some(level) = (1..=last + 1).find(|level| !bands.iter().any(|band| band.covers(*level)))
+    {
+        return Err(error(format!("no set is dealt at level {level}")));
+    }
+
+    Ok(TileSetTable { bands })
+}
+
+fn read_set(entry: SetEntry, id: u8) -> Result<Band, String> {
+    let from_level = entry.from_level.unwrap_or(1).max(1);
+    if entry.to_level.is_some_and(|last| last < from_level) {
+        return Err("`to_level` is before `from_level`".to_string());
+    }
+
+    let mut set = TileSet {
+        id,
+        name: entry.name,
+        arms: vec![],
+        shapes: vec![],
+        joins: vec![],
+    };
+    for arm in entry.arms {
+        let arm = read_arm(arm, &set)?;
+        set.arms.push(arm);
+    }
+    for tile in entry.tiles {
+        let name = tile.name.clone();
+        let shape = read_tile(tile, &set).map_err(|reason| format!("tile `{name}`: {reason}"))?;
+        set.shapes.push(shape);
+    }
+    for (a, b) in entry.joins {
+        let join = read_join(&a, &b, &set)?;
+        set.joins.push(join);
+    }
+    check(&set)?;
+
+    Ok(Band {
+        set,
+        from_level,
+        to_level: entry.to_level,
+    })
+}
+
+fn read_arm(arm: ArmEntry, set: &TileSet) -> Result<ArmStyle, String> {
+    let label = arm.label;
+    if label == "-" || label.contains(char::is_whitespace) || label.is_empty() {
+        return Err(format!("`{label}` cannot be a label"));
+    }
+    if set.arms.iter().any(|other| other.label == label) {
+        return Err(format!("label `{label}` given twice"));
+    }
+    if set.arms.len() + 1 > Label::MAX as usize {
+        return Err("too many arms".to_string());
+    }
+
+    // Written so that a NaN fails it too.
+    if !(arm.width > 0.0 && arm.width <= 1.0) {
+        return Err(format!(
+            "arm `{label}`: `width` must be more than 0 and at most 1"
+        ));
+    }
+
+    let shades = 0.0..=1.0;
+    if !shades.contains(&arm.lighten) || !shades.contains(&arm.darken) {
+        return Err(format!(
+            "arm `{label}`: `lighten` and `darken` go from 0 to 1"
+        ));
+    }
+    if arm.lighten > 0.0 && arm.darken > 0.0 {
+        return Err(format!("arm `{label}` can lighten or darken, not both"));
+    }
+
+    Ok(ArmStyle {
+        label,
+        width: arm.width,
+        shade: arm.lighten - arm.darken,
+    })
+}
+
+fn read_tile(tile: TileEntry, set: &TileSet) -> Result<TileShape, String> {
+    if set.shapes.iter().any(|shape| shape.name == tile.name) {
+        return Err("given twice".to_string());
+    }
+
+    if tile.edges.len() != EDGES {
+        return Err(format!("{} edges; a tile has {EDGES}", tile.edges.len()));
+    }
+    let mut edges = [NO_ARM; EDGES];
+    for (edge, label) in edges.iter_mut().zip(&tile.edges) {
+        *edge = label_of(set, label)?;
+    }
+
+    let weight = tile.weight.unwrap_or(1);
+    if !(1..=1000).contains(&weight) {
+        return Err("`weight` must be from 1 to 1000".to_string());
+    }
+
+    Ok(TileShape {
+        name: tile.name,
+        edges,
+        weight,
+    })
+}
+
+fn read_join(a: &str, b: &str, set: &TileSet) -> Result<(Label, Label), String> {
+    let (a, b) = (label_of(set, a)?, label_of(set, b)?);
+    // No arm fitting an arm would make every dead end legal, and a label
+    // always fits itself already.
+    if a == NO_ARM || b == NO_ARM || a == b {
+        return Err("a join is between two different arms".to_string());
+    }
+
+    Ok((a, b))
+}
+
+/// The label a tile or join names, `-` being no arm.
+fn label_of(set: &TileSet, label: &str) -> Result<Label, String> {
+    if label == "-" {
+        return Ok(NO_ARM);
+    }
+    match set.arms.iter().position(|arm| arm.label == label) {
+        Some(index) => Ok(index as Label + 1),
+        None => Err(format!("`{label}` is not an arm of the set")),
+    }
+}
+
+/// The checks that need the whole set.
+fn check(set: &TileSet) -> Result<(), String> {
+    if !set
+        .shapes
+        .iter()
+        .any(|shape| shape.edges == [NO_ARM; EDGES])
+    {
+        return Err("no empty tile".to_string());
+    }
+    if set.shapes.len() < 2 {
+        return Err("nothing but the empty tile".to_string());
+    }
+    let pool = set.pool().len();
+    if pool > MAX_POOL {
+        return Err(format!("{pool} tiles once rotated; the most is {MAX_POOL}"));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn the_bundled_sets_are_valid() {
+        let table = parse(BUNDLED).unwrap_or_else(|error| panic!("{error}"));
+        let mut rng = StdRng::seed_from_u64(3);
+        for level in 1..=40 {
+            table.for_level(level, &mut rng);
+        }
+        for (id, set) in table.sets().enumerate() {
+            assert_eq!(table.get(id as u8), set);
+        }
+    }
+
+    /// A set written with a join, which the bundled ones do not use: the
+    /// pipe that fits two bores fits both, and still not an empty edge.
+    #[test]
+    fn a_join_lets_two_labels_meet() {
+        let source = r#"[
+            (
+                name: "test",
+                arms: [(label: "wide", width: 0.4), (label: "narrow", width: 0.2)],
+                tiles: [
+                    (name: "empty", edges: ["-", "-", "-", "-", "-", "-"]),
+                    (name: "straight", edges: ["wide", "-", "-", "narrow", "-", "-"]),
+                ],
+                joins: [("wide", "narrow")],
+            ),
+        ]"#;
+        let table = parse(source).unwrap_or_else(|error| panic!("{error}"));
+        let set = table.get(0);
+
+        assert!(set.fits(1, 2));
+        assert!(set.fits(2, 1));
+        assert!(!set.fits(1, NO_ARM));
+        assert!(set.fits(NO_ARM, NO_ARM));
+    }
+
+    #[test]
+    fn a_broken_file_is_refused_with_the_reason() {
+        let refuse = |source: String| parse(&source).unwrap_err();
+        let straight = r#"edges: ["pipe", "-", "-", "pipe", "-", "-"]"#;
+
+        // A label no arm has, named with the set and tile it is in.
+        let typo = BUNDLED.replacen(straight, r#"edges: ["pipe", "-", "-", "pip", "-", "-"]"#, 1);
+        let reason = refuse(typo).reason;
+        assert!(reason.contains("`pipes`"));
+        assert!(reason.contains("`straight`"));
+        assert!(reason.contains("`pip`"));
+
+        let short = BUNDLED.replacen(straight, r#"edges: ["pipe", "-", "-", "pipe", "-"]"#, 1);
+        assert!(refuse(short).reason.contains("5 edges"));
+
+        // A field no set has, which RON refuses on the line it is on.
+        let unknown = BUNDLED.replacen("to_level: 6", "to_levle: 6", 1);
+        let line = BUNDLED
+            .lines()
+            .position(|line| line.contains("to_level: 6"))
+            .unwrap()
+            + 1;
+        let error = refuse(unknown);
+        assert_eq!(error.line, line);
+        assert!(error.reason.contains("to_levle"));
+
+        // The first levels left with nothing to deal.
+        let late = BUNDLED.replacen("from_level: 1", "from_level: 2", 1);
+        assert!(refuse(late).reason.contains("level 1"));
+
+        // A set that cannot close its border.
+        let crowded = BUNDLED.replacen(
+            r#"edges: ["-", "-", "-", "-", "-", "-"]"#,
+            r#"edges: ["pipe", "-", "-", "-", "-", "pipe"]"#,
+            1,
+        );
+        assert!(refuse(crowded).reason.contains("no empty tile"));
+    }
+}
@@ -0,0 +1,266 @@
+//! A preview of the whole game through someone else's eyes.
+//!
+//! `cvd` changes what the game deals. This changes nothing about the game and
+//! only what the screen shows: every colour on it, board, HUD and menus alike,
+//! is passed through a colour vision simulation on its way out. It is for
+//! whoever works on the palettes in `theme` or the generators, to see whether
+//! a board or a button still reads without borrowing a colour blind friend.
+//!
+//! It is not a post-process. There are no shaders anywhere in the game, and
+//! every colour on screen already sits in one of four places: a UI node's
+//! `BackgroundColor`, a `TextColor`, a lyon `Shape`, or the camera's clear
+//! colour. `filter_paint` rewrites those, one kind each, and remembers what
+//! the game had written so switching the filter off puts it back.
+//!
+//! The game writes colours all the time, for a hover or a fading pick effect.
+//! A colour that differs from the one the filter last showed is taken as a
+//! new one from the game, and filtered in turn. The game never reads a colour
+//! back to decide anything, so it cannot tell the filter is there. The one
+//! exception is an effect that fades its own colour's alpha: it fades the
+//! filtered colour, which the filter then filters again. Every simulation
+//! here is close to a projection, so that changes nothing anyone can see.
+//!
+//! Not persisted, unlike the settings beside it. It is a tool, and a player
+//! who found it should not start the next session in grey.
+
+use std::marker::PhantomData;
+
+use bevy::camera::ClearColorConfig;
+use bevy::ecs::component::Mutable;
+use bevy::prelude::*;
+use bevy_prototype_lyon::plugin::BuildShapes;
+use bevy_prototype_lyon::prelude::Shape;
+
+use crate::cvd::ColorVision;
+use crate::oklab;
+
+pub struct VisionFilterPlugin;
+
+impl Plugin for VisionFilterPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<VisionFilter>()
+            // Shapes have to be filtered before lyon turns them into meshes,
+            // or every repaint shows for a frame unfiltered. The UI is read
+            // straight from its components when the frame is drawn, so it
+            // goes last, after the relayouts in `PostUpdate` have spawned it.
+            .add_systems(PostUpdate, filter_paint::<Shape>.before(BuildShapes))
+            .add_systems(
+                Last,
+                (
+                    filter_paint::<BackgroundColor>,
+                    filter_paint::<TextColor>,
+                    filter_paint::<Camera>,
+                ),
+            );
+    }
+}
+
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum VisionFilter {
+    #[default]
+    Off,
+    Protan,
+    Deutan,
+    Tritan,
+    /// No cones at all: lightness alone. The one `cvd` does not deal for,
+    /// since a board of greys has nothing left but lightness to play with,
+    /// but the one that shows best whether the theme leans on hue.
+    Achromat,
+}
+
+impl VisionFilter {
+    pub fn iter() -> impl Iterator<Item = VisionFilter> {
+        [
+            VisionFilter::Off,
+            VisionFilter::Protan,
+            VisionFilter::Deutan,
+            VisionFilter::Tritan,
+            VisionFilter::Achromat,
+        ]
+        .into_iter()
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            VisionFilter::Off => "FILTRO: DESLIGADO",
+            VisionFilter::Protan => "FILTRO: PROTANOPIA",
+            VisionFilter::Deutan => "FILTRO: DEUTERANOPIA",
+            VisionFilter::Tritan => "FILTRO: TRITANOPIA",
+            VisionFilter::Achromat => "FILTRO: ACROMATOPSIA",
+        }
+    }
+
+    pub fn cycle(&mut self) {
+        let all: Vec<VisionFilter> = Self::iter().collect();
+        let at = all.iter().position(|filter| filter == self).unwrap_or(0);
+        *self = all[(at + 1) % all.len()];
+    }
+
+    /// `color` as it would be seen with the filter's deficiency.
+    pub fn simulate(&self, color: Color) -> Color {
+        match self {
+            VisionFilter::Off => color,
+            VisionFilter::Protan => ColorVision::Protan.simulate(color),
+            VisionFilter::Deutan => ColorVision::Deutan.simulate(color),
+            VisionFilter::Tritan => ColorVision::Tritan.simulate(color),
+            VisionFilter::Achromat => {
+                // Relative luminance, weighted in linear light the way the
+                // eye weighs the three primaries.
+                let srgba = color.to_srgba();
+                let luminance = 0.2126 * oklab::srgb_to_linear(srgba.red)
+                    + 0.7152 * oklab::srgb_to_linear(srgba.green)
+                    + 0.0722 * oklab::srgb_to_linear(srgba.blue);
+                let grey = oklab::linear_to_srgb(luminance.clamp(0.0, 1.0));
+                Color::srgba(grey, grey, grey, srgba.alpha)
+            }
+        }
+    }
+}
+
+/// A component the game paints with. Colours go in and out as a list, so a
+/// shape's fill and stroke travel together.
+pub trait Paint: Component<Mutability = Mutable> {
+    fn colors(&self) -> Vec<Color>;
+    fn paint(&mut self, colors: &[Color]);
+}
+
+impl Paint for BackgroundColor {
+    fn colors(&self) -> Vec<Color> {
+        vec![self.0]
+    }
+
+    fn paint(&mut self, colors: &[Color]) {
+        self.0 = colors[0];
+    }
+}
+
+impl Paint for TextColor {
+    fn colors(&self) -> Vec<Color> {
+        vec![self.0]
+    }
+
+    fn paint(&mut self, colors: &[Color]) {
+        self.0 = colors[0];
+    }
+}
+
+impl Paint for Shape {
+    fn colors(&self) -> Vec<Color> {
+        let fill = self.fill.iter().map(|fill| fill.color);
+        fill.chain(self.stroke.iter().map(|stroke| stroke.color))
+            .collect()
+    }
+
+    fn paint(&mut self, colors: &[Color]) {
+        let mut colors = colors.iter();
+        if let Some(fill) = self.fill.as_mut() {
+            fill.color = *colors.next().unwrap_or(&fill.color);
+        }
+        if let Some(stroke) = self.stroke.as_mut() {
+            stroke.color = *colors.next().unwrap_or(&stroke.color);
+        }
+    }
+}
+
+/// Only a camera that clears to a colour of its own has one to filter. The
+/// window's `ClearColor` is black, which every simulation leaves black.
+impl Paint for Camera {
+    fn colors(&self) -> Vec<Color> {
+        match self.clear_color {
+            ClearColorConfig::Custom(color) => vec![color],
+            _ => Vec::new(),
+        }
+    }
+
+    fn paint(&mut self, colors: &[Color]) {
+        if let ClearColorConfig::Custom(color) = &mut self.clear_color {
+            *color = colors[0];
+        }
+    }
+}
+
+/// What the game painted an entity with, and what the filter showed instead.
+#[derive(Component)]
+pub struct Filtered<T> {
+    original: Vec<Color>,
+    shown: Vec<Color>,
+    marker: PhantomData<fn() -> T>,
+}
+
+pub fn filter_paint<T: Paint>(
+    mut commands: Commands,
+    filter: Res<VisionFilter>,
+    mut query: Query<(Entity, &mut T, Option<&mut Filtered<T>>)>,
+) {
+    if *filter == VisionFilter::Off && !filter.is_changed() {
+        return;
+    }
+
+    for (entity, mut paint, memory) in query.iter_mut() {
+        if memory.is_some() && !paint.is_changed() && !filter.is_changed() {
+            continue;
+        }
+
+        let current = paint.colors();
+        let original = match &memory {
+            Some(memory) if memory.shown == current => memory.original.clone(),
+            _ => current.clone(),
+        };
+
+        if *filter == VisionFilter::Off {
+            if memory.is_some() {
+                paint.paint(&original);
+                commands.entity(entity).try_remove::<Filtered<T>>();
+            }
+            continue;
+        }
+
+        let shown: Vec<Color> = original
+            .iter()
+            .map(|color| filter.simulate(*color))
+            .collect();
+        // Left alone when nothing moved, so the change does not wake every
+        // system downstream that watches for one.
+        if shown != current {
+            paint.paint(&shown);
+        }
+
+        match memory {
+            Some(mut memory) => {
+                memory.original = original;
+                memory.shown = shown;
+            }
+            None => {
+                commands.entity(entity).try_insert(Filtered::<T> {
+                    original,
+                    shown,
+                    marker: PhantomData,
+                });
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn greys_pass_and_achromatopsia_keeps_only_lightness() {
+        let yellow = VisionFilter::Achromat
+            .simulate(Color::srgb(0.9, 0.8, 0.2))
+            .to_srgba();
+        let blue = VisionFilter::Achromat
+            .simulate(Color::srgb(0.2, 0.3, 0.9))
+            .to_srgba();
+        assert_eq!(yellow.red, yellow.green);
+        assert_eq!(yellow.green, yellow.blue);
+        assert!(yellow.red > blue.red);
+
+        let grey = Color::srgb(0.4, 0.4, 0.4);
+        for filter in VisionFilter::iter() {
+            let seen = filter.simulate(grey).to_srgba();
+            assert!((seen.green - 0.4).abs() < 1e-3, "{filter:?}");
+        }
+    }
+}
@@ -0,0 +1,23 @@
+use bevy::prelude::Component;
+
+/// Root of the vision test screen. Everything under it is despawned together.
+#[derive(Component)]
+pub struct VisionTestMenu;
+
+/// Starts a test, or another one after a result.
+#[derive(Component)]
+pub struct StartTestButton;
+
+/// One square of the board on the table.
+#[derive(Component)]
+pub struct VisionCell {
+    pub index: usize,
+}
+
+/// Abandons the test under way. Nothing is stored.
+#[derive(Component)]
+pub struct StopTestButton;
+
+/// Returns to the settings screen.
+#[derive(Component)]
+pub struct VisionBackButton;
@@ -0,0 +1,53 @@
+//! The vision test screen, reached from the settings screen.
+//!
+//! Runs `jnd`'s calibration one board at a time and keeps every result, so a
+//! player training their eye, or checking a new screen, has a number per axis
+//! to compare with the last time. The screen has three faces: what the test is
+//! and how the last one went, the boards, and the result next to the one
+//! before it.
+
+mod components;
+pub mod resources;
+mod styles;
+mod systems;
+
+use bevy::prelude::*;
+
+use crate::AppState;
+use resources::{VisionProfile, VisionTest};
+use systems::interactions::*;
+use systems::layout::*;
+
+pub struct VisionTestPlugin;
+
+impl Plugin for VisionTestPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<VisionProfile>()
+            .init_resource::<VisionTest>()
+            .add_systems(Startup, load_vision_profile)
+            .add_systems(OnEnter(AppState::VisionTest), spawn_vision_test)
+            .add_systems(
+                Update,
+                (
+                    interact_with_start_button,
+                    interact_with_vision_cell,
+                    interact_with_stop_button,
+                    interact_with_vision_back_button,
+                )
+                    .run_if(in_state(AppState::VisionTest)),
+            )
+            // Tears down live `Button` entities, so it runs after `Update`.
+            .add_systems(
+                PostUpdate,
+                relayout_vision_test.run_if(in_state(AppState::VisionTest)),
+            )
+            .add_systems(
+                OnExit(AppState::VisionTest),
+                (despawn_vision_test, forget_vision_test),
+            );
+    }
+}
+
+fn load_vision_profile(mut profile: ResMut<VisionProfile>) {
+    *profile = VisionProfile::load();
+}
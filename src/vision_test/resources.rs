@@ -0,0 +1,167 @@
+use bevy::prelude::*;
+
+use crate::game::puzzle::components::RunSeed;
+use crate::jnd::{Board, Calibration, Thresholds};
+use crate::storage::{self, StorageBackend};
+
+const STORAGE_KEY: &str = "color_puzzle.vision";
+
+/// Results kept. Enough for months of weekly tests, few enough that the value
+/// stays a line long.
+const HISTORY: usize = 30;
+
+/// One finished test and the day it was taken.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct VisionResult {
+    pub day: u64,
+    pub thresholds: Thresholds,
+}
+
+/// Every finished test, oldest first, persisted where the platform allows.
+///
+/// The history is the point. A single result mostly describes the screen it
+/// was taken on, and it is the next one, on the same screen, that says
+/// whether anything changed.
+#[derive(Resource, Debug, Default, Clone, PartialEq)]
+pub struct VisionProfile {
+    history: Vec<VisionResult>,
+}
+
+impl VisionProfile {
+    pub fn latest(&self) -> Option<&VisionResult> {
+        self.history.last()
+    }
+
+    /// The result before the latest, to compare it with.
+    pub fn previous(&self) -> Option<&VisionResult> {
+        self.history.iter().rev().nth(1)
+    }
+
+    pub fn tests(&self) -> usize {
+        self.history.len()
+    }
+
+    pub fn submit(&mut self, day: u64, thresholds: Thresholds) {
+        self.history.push(VisionResult { day, thresholds });
+        if self.history.len() > HISTORY {
+            self.history.remove(0);
+        }
+        storage::save(STORAGE_KEY, &self.serialize());
+    }
+
+    pub fn load() -> Self {
+        Self::load_from(storage::backend())
+    }
+
+    pub fn load_from(store: &dyn StorageBackend) -> Self {
+        store
+            .load(STORAGE_KEY)
+            .map(|raw| Self::deserialize(&raw))
+            .unwrap_or_default()
+    }
+
+    /// `day:lightness,red_green,blue_yellow` per test, separated by `;`.
+    fn serialize(&self) -> String {
+        self.history
+            .iter()
+            .map(|result| {
+                let t = result.thresholds;
+                format!(
+                    "{}:{:.4},{:.4},{:.4}",
+                    result.day, t.lightness, t.red_green, t.blue_yellow
+                )
+            })
+            .collect::<Vec<_>>()
+            .join(";")
+    }
+
+    /// A damaged entry is dropped on its own, so one bad write does not cost
+    /// the rest of the history.
+    fn deserialize(raw: &str) -> Self {
+        let history = raw
+            .split(';')
+            .filter_map(|entry| {
+                let (day, values) = entry.split_once(':')?;
+                let values: Vec<f32> = values
+                    .split(',')
+                    .map(|value| value.trim().parse::<f32>().ok())
+                    .collect::<Option<_>>()?;
+                let [lightness, red_green, blue_yellow] = values[..] else {
+                    return None;
+                };
+                Some(VisionResult {
+                    day: day.trim().parse().ok()?,
+                    thresholds: Thresholds {
+                        lightness,
+                        red_green,
+                        blue_yellow,
+                    },
+                })
+            })
+            .collect();
+
+        Self { history }
+    }
+}
+
+/// Where the screen is: explaining the test, running it, or showing how it
+/// went. The layout is rebuilt whenever this changes.
+#[derive(Resource, Debug, Default)]
+pub enum VisionTest {
+    #[default]
+    Intro,
+    Running {
+        /// Boxed: the three staircases dwarf the other two faces.
+        calibration: Box<Calibration>,
+        board: Board,
+        /// A fresh seed per test, moved on a board at a time, so the boards
+        /// come from the same generator the game rounds use.
+        seed: RunSeed,
+    },
+    /// Finished and stored. The result is read back from `VisionProfile`.
+    Done,
+}
+
+impl VisionTest {
+    /// A new test, on its first board.
+    pub fn start() -> Self {
+        let mut calibration = Calibration::default();
+        let mut seed = RunSeed::random();
+        let board = calibration
+            .deal(&mut seed.rng())
+            .expect("a new test has boards to deal");
+        seed.advance();
+
+        VisionTest::Running {
+            calibration: Box::new(calibration),
+            board,
+            seed,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn the_profile_survives_a_round_trip_and_a_damaged_entry() {
+        let mut profile = VisionProfile::default();
+        for day in 20_000..20_003 {
+            profile.history.push(VisionResult {
+                day,
+                thresholds: Thresholds {
+                    lightness: 0.0123,
+                    red_green: 0.025,
+                    blue_yellow: 0.0189,
+                },
+            });
+        }
+        let raw = profile.serialize();
+        assert_eq!(VisionProfile::deserialize(&raw), profile);
+
+        let damaged = format!("19000:0.01,oops,0.02;{raw}");
+        assert_eq!(VisionProfile::deserialize(&damaged), profile);
+        assert_eq!(VisionProfile::deserialize(""), VisionProfile::default());
+    }
+}
@@ -0,0 +1,48 @@
+//! Layout for the vision test screen. Colours and type come from `theme`.
+
+use bevy::prelude::*;
+
+use crate::theme;
+
+/// Squares stop growing here. Bigger squares are not easier to compare, and
+/// on a desktop window four of them would fill the screen.
+pub const MAX_CELL_SIZE: f32 = 96.0;
+
+pub fn menu_style() -> Node {
+    Node {
+        flex_direction: FlexDirection::Column,
+        justify_content: JustifyContent::Center,
+        align_items: AlignItems::Center,
+        width: Val::Percent(100.0),
+        height: Val::Percent(100.0),
+        padding: UiRect::vertical(Val::Px(theme::SPACE_MD)),
+        row_gap: Val::Px(theme::SPACE_XS),
+        ..Node::DEFAULT
+    }
+}
+
+/// Space between the text and the buttons under it.
+pub fn spacer_style() -> Node {
+    Node {
+        height: Val::Px(theme::SPACE_LG),
+        ..Node::DEFAULT
+    }
+}
+
+pub fn board_style() -> Node {
+    Node {
+        flex_direction: FlexDirection::Row,
+        justify_content: JustifyContent::Center,
+        column_gap: Val::Px(theme::SPACE_SM),
+        margin: UiRect::vertical(Val::Px(theme::SPACE_LG)),
+        ..Node::DEFAULT
+    }
+}
+
+pub fn cell_style(size: f32) -> Node {
+    Node {
+        width: Val::Px(size),
+        height: Val::Px(size),
+        ..Node::DEFAULT
+    }
+}
@@ -0,0 +1,108 @@
+use bevy::prelude::*;
+
+use crate::events::TransitionToStateEvent;
+use crate::vision_test::components::*;
+use crate::vision_test::resources::{VisionProfile, VisionTest};
+use crate::{clock, theme, AppState};
+
+pub fn interact_with_start_button(
+    mut button_query: Query<
+        (&Interaction, &mut BackgroundColor),
+        (Changed<Interaction>, With<StartTestButton>),
+    >,
+    mut test: ResMut<VisionTest>,
+) {
+    for (interaction, mut background_color) in button_query.iter_mut() {
+        match *interaction {
+            Interaction::Pressed => {
+                *background_color = theme::BUTTON_PRIMARY_PRESSED.into();
+                *test = VisionTest::start();
+            }
+            Interaction::Hovered => *background_color = theme::BUTTON_PRIMARY_HOVERED.into(),
+            Interaction::None => *background_color = theme::BUTTON_PRIMARY.into(),
+        }
+    }
+}
+
+/// Takes a pick and deals the next board, or stores the result when the test
+/// is over.
+///
+/// The squares do not light up on hover or say whether a pick was right. A
+/// hover tint changes the very colour being judged, and a verdict after every
+/// board has players second-guessing the next one instead of looking at it.
+pub fn interact_with_vision_cell(
+    cell_query: Query<(&Interaction, &VisionCell), Changed<Interaction>>,
+    mut test: ResMut<VisionTest>,
+    mut profile: ResMut<VisionProfile>,
+) {
+    for (interaction, cell) in cell_query.iter() {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        let VisionTest::Running {
+            calibration,
+            board,
+            seed,
+        } = test.as_mut()
+        else {
+            continue;
+        };
+
+        calibration.record(board, cell.index);
+        match calibration.deal(&mut seed.rng()) {
+            Some(next) => {
+                seed.advance();
+                *board = next;
+            }
+            None => {
+                if let Some(thresholds) = calibration.thresholds() {
+                    profile.submit(clock::today(), thresholds);
+                }
+                *test = VisionTest::Done;
+            }
+        }
+        // One pick per board, even if two squares report a press in the same
+        // frame.
+        break;
+    }
+}
+
+pub fn interact_with_stop_button(
+    mut button_query: Query<
+        (&Interaction, &mut BackgroundColor),
+        (Changed<Interaction>, With<StopTestButton>),
+    >,
+    mut test: ResMut<VisionTest>,
+) {
+    for (interaction, mut background_color) in button_query.iter_mut() {
+        match *interaction {
+            Interaction::Pressed => {
+                *background_color = theme::BUTTON_PRESSED.into();
+                *test = VisionTest::Intro;
+            }
+            Interaction::Hovered => *background_color = theme::BUTTON_HOVERED.into(),
+            Interaction::None => *background_color = theme::BUTTON.into(),
+        }
+    }
+}
+
+pub fn interact_with_vision_back_button(
+    mut button_query: Query<
+        (&Interaction, &mut BackgroundColor),
+        (Changed<Interaction>, With<VisionBackButton>),
+    >,
+    mut transition_to_state_event_writer: MessageWriter<TransitionToStateEvent>,
+) {
+    for (interaction, mut background_color) in button_query.iter_mut() {
+        match *interaction {
+            Interaction::Pressed => {
+                *background_color = theme::BUTTON_PRESSED.into();
+                transition_to_state_event_writer.write(TransitionToStateEvent {
+                    state: AppState::Settings,
+                });
+            }
+            Interaction::Hovered => *background_color = theme::BUTTON_HOVERED.into(),
+            Interaction::None => *background_color = theme::BUTTON.into(),
+        }
+    }
+}
@@ -0,0 +1,255 @@
+//! Builds the vision test screen.
+
+use bevy::prelude::*;
+
+use crate::jnd::{Axis, CELLS};
+use crate::vision_test::components::*;
+use crate::vision_test::resources::{VisionProfile, VisionResult, VisionTest};
+use crate::vision_test::styles::*;
+use crate::{clock, theme};
+
+pub fn spawn_vision_test(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    test: Res<VisionTest>,
+    profile: Res<VisionProfile>,
+    window_query: Query<&Window>,
+) {
+    let width = window_query
+        .single()
+        .map(|window| theme::content_width(window.width()))
+        .unwrap_or(theme::CONTENT_MAX_WIDTH);
+
+    build_vision_test(&mut commands, &asset_server, &test, &profile, width);
+}
+
+pub fn build_vision_test(
+    commands: &mut Commands,
+    asset_server: &Res<AssetServer>,
+    test: &VisionTest,
+    profile: &VisionProfile,
+    width: f32,
+) -> Entity {
+    commands
+        .spawn((
+            (menu_style(), BackgroundColor(theme::BACKGROUND)),
+            VisionTestMenu,
+        ))
+        .with_children(|parent| {
+            parent.spawn(theme::wrapped_text(
+                "TESTE DE VISAO",
+                theme::text_title(asset_server),
+                width,
+            ));
+
+            match test {
+                VisionTest::Intro => {
+                    parent.spawn(theme::wrapped_text(
+                        "UM QUADRADO TEM A COR UM POUCO DIFERENTE. TOQUE NELE. SE NAO VIR, CHUTE.",
+                        theme::text(asset_server, theme::TEXT_XS, theme::MUTED),
+                        width,
+                    ));
+
+                    if let Some(latest) = profile.latest() {
+                        parent.spawn(spacer_style());
+                        parent.spawn(theme::wrapped_text(
+                            format!(
+                                "ULTIMO TESTE {} ({} NO TOTAL)",
+                                clock::date_label(latest.day),
+                                profile.tests()
+                            ),
+                            theme::text(asset_server, theme::TEXT_XS, theme::ACCENT),
+                            width,
+                        ));
+                        spawn_profile(parent, asset_server, latest, None, width);
+                    }
+
+                    parent.spawn(spacer_style());
+                    spawn_button(
+                        parent,
+                        asset_server,
+                        "COMECAR",
+                        width,
+                        theme::BUTTON_PRIMARY,
+                        StartTestButton,
+                    );
+                    spawn_button(
+                        parent,
+                        asset_server,
+                        "VOLTAR",
+                        width,
+                        theme::BUTTON,
+                        VisionBackButton,
+                    );
+                }
+                VisionTest::Running {
+                    calibration, board, ..
+                } => {
+                    parent.spawn(theme::wrapped_text(
+                        format!(
+                            "RODADA {}   {}%",
+                            calibration.trials() + 1,
+                            (calibration.progress() * 100.0).round()
+                        ),
+                        theme::text_label(asset_server),
+                        width,
+                    ));
+
+                    let size = ((width - theme::SPACE_SM * (CELLS - 1) as f32) / CELLS as f32)
+                        .min(MAX_CELL_SIZE);
+                    parent.spawn(board_style()).with_children(|row| {
+                        for (index, color) in board.colors.iter().enumerate() {
+                            row.spawn((
+                                Button,
+                                cell_style(size),
+                                BackgroundColor(*color),
+                                VisionCell { index },
+                            ));
+                        }
+                    });
+
+                    spawn_button(
+                        parent,
+                        asset_server,
+                        "ENCERRAR",
+                        width,
+                        theme::BUTTON,
+                        StopTestButton,
+                    );
+                }
+                VisionTest::Done => {
+                    if let Some(latest) = profile.latest() {
+                        parent.spawn(theme::wrapped_text(
+                            "VOCE DISTINGUE DIFERENCAS DE:",
+                            theme::text(asset_server, theme::TEXT_SM, theme::ACCENT),
+                            width,
+                        ));
+                        spawn_profile(parent, asset_server, latest, profile.previous(), width);
+                        // Oklab units mean nothing to most players, so say
+                        // which way is good, and what makes two results
+                        // comparable.
+                        parent.spawn(theme::wrapped_text(
+                            "MENOR E MELHOR. COMPARE TESTES FEITOS NA MESMA TELA.",
+                            theme::text(asset_server, theme::TEXT_XS, theme::MUTED),
+                            width,
+                        ));
+                    }
+
+                    parent.spawn(spacer_style());
+                    spawn_button(
+                        parent,
+                        asset_server,
+                        "REPETIR",
+                        width,
+                        theme::BUTTON_PRIMARY,
+                        StartTestButton,
+                    );
+                    spawn_button(
+                        parent,
+                        asset_server,
+                        "VOLTAR",
+                        width,
+                        theme::BUTTON,
+                        VisionBackButton,
+                    );
+                }
+            }
+        })
+        .id()
+}
+
+/// A line per axis, with the earlier result alongside when there is one.
+fn spawn_profile(
+    parent: &mut ChildSpawnerCommands,
+    asset_server: &Res<AssetServer>,
+    result: &VisionResult,
+    previous: Option<&VisionResult>,
+    width: f32,
+) {
+    for axis in Axis::ALL {
+        let value = result.thresholds.get(axis);
+        let line = match previous {
+            Some(previous) => format!(
+                "{} {:.3} (ANTES {:.3})",
+                axis.label(),
+                value,
+                previous.thresholds.get(axis)
+            ),
+            None => format!("{} {:.3}", axis.label(), value),
+        };
+        parent.spawn(theme::wrapped_text(
+            line,
+            theme::text(asset_server, theme::TEXT_XS, theme::ON_SURFACE),
+            width,
+        ));
+    }
+}
+
+fn spawn_button<M: Component>(
+    parent: &mut ChildSpawnerCommands,
+    asset_server: &Res<AssetServer>,
+    label: &str,
+    width: f32,
+    color: Color,
+    marker: M,
+) {
+    parent
+        .spawn((
+            (Button, theme::button_style(width), BackgroundColor(color)),
+            marker,
+        ))
+        .with_children(|parent| {
+            parent.spawn(theme::wrapped_text(
+                label,
+                theme::text_button(asset_server),
+                theme::button_text_width(width),
+            ));
+        });
+}
+
+pub fn despawn_vision_test(mut commands: Commands, query: Query<Entity, With<VisionTestMenu>>) {
+    for entity in query.iter() {
+        commands.entity(entity).despawn();
+    }
+}
+
+/// A test left half done is dropped with the screen. Its staircases are only
+/// worth finishing in one sitting: picked up another day, on another screen,
+/// they would be measuring something else.
+pub fn forget_vision_test(mut test: ResMut<VisionTest>) {
+    *test = VisionTest::default();
+}
+
+/// Rebuilds for a window that changed size, and after every pick, which puts
+/// a new board down. Runs in `PostUpdate` for the same reason every other
+/// relayout does: it despawns live `Button` entities.
+pub fn relayout_vision_test(
+    mut commands: Commands,
+    mut relayout_events: MessageReader<crate::layout::RelayoutEvent>,
+    asset_server: Res<AssetServer>,
+    test: Res<VisionTest>,
+    profile: Res<VisionProfile>,
+    window_query: Query<&Window>,
+    menu_query: Query<Entity, With<VisionTestMenu>>,
+) {
+    let resized = relayout_events.read().next().is_some();
+    if !resized && !test.is_changed() {
+        return;
+    }
+
+    let Ok(window) = window_query.single() else {
+        return;
+    };
+
+    for entity in menu_query.iter() {
+        commands.entity(entity).despawn();
+    }
+
+    build_vision_test(
+        &mut commands,
+        &asset_server,
+        &test,
+        &profile,
+        theme::content_width(window.width()),
+    );
+}
@@ -1,4 +1,4 @@
-//! Wave function collapse over a small tile grid, for the `Mosaic` round.
+//! Wave function collapse over the board's honeycomb, for the `Mosaic` round.
 //!
 //! The other modes ask "which color is different". This one asks "which piece
 //! does not fit", which is a different question for the player: not a judgement
@@ -7,192 +7,396 @@
 //! then exactly one piece that does not.
 //!
 //! WFC is the right generator for that and a poor one for most other things.
-//! Its whole value is local coherence: it fills the grid with pieces that are
+//! Its whole value is local coherence: it fills the board with pieces that are
 //! guaranteed to line up. Used to draw shapes for their own sake it would be
 //! an expensive random number generator; used here, the guarantee *is* the
 //! puzzle, because a break in it is only findable if everything else holds.
 //!
+//! ## The lattice
+//!
+//! Mosaic is played on the same honeycomb as every other mode, laid out by
+//! `board::layout`, so a cell has up to six neighbours and a tile six edges.
+//! The solver does not assume any particular shape of board: a [`Lattice`] is
+//! built from whichever cells were dealt, and a cell missing a neighbour is
+//! simply on the border.
+//!
 //! ## The tiles
 //!
-//! Each tile is a piece of pipe: its four edges either carry an arm or do not.
-//! Two neighbours fit when the edges they share agree. The set is deliberately
-//! *incomplete* — there is no dead end (one arm) and no cross (four arms):
+//! Each tile is a piece of pipe: each of its six edges carries an arm, or
+//! nothing. Arms are labelled — a pipe of one width or another, a wire of one
+//! colour or another — and two neighbours fit when the edges they share carry
+//! the same label, or two labels the set says may join. Which tiles there are,
+//! how often each is chosen and which labels join is a [`TileSet`], and the
+//! sets the game plays are data in `assets/tiles.ron` — see `tile_sets`.
 //!
-//! | kind       | arms | rotations |
-//! | ---------- | ---- | --------- |
-//! | `Empty`    | 0    | 1         |
-//! | `Straight` | 2    | 2         |
-//! | `Corner`   | 2    | 4         |
-//! | `Tee`      | 3    | 4         |
+//! A good set is deliberately *incomplete*. The first one the game deals has
+//! eighteen tiles for sixty-four possible edge patterns, with no dead end and
+//! nothing crowded. That gap is what makes the constraints bite: with a
+//! complete set every assignment of edges would be realizable, propagation
+//! would never rule anything out, and "WFC" would be a grand name for rolling
+//! a die per cell.
 //!
-//! Eleven tiles for sixteen possible edge patterns. That gap is what makes the
-//! constraints bite: with a complete set every assignment of edges would be
-//! realizable, propagation would never rule anything out, and "WFC" would be a
-//! grand name for rolling a die per cell.
+//! ## Repair
+//!
+//! `Repair` asks the opposite question of the same tilings: nothing on the
+//! board is the wrong piece, but some are turned the wrong way, and the player
+//! turns them back until every edge agrees. See [`scramble`].
 //!
 //! This module is deliberately free of Bevy types so it can be tested on its
 //! own — which it is, at the bottom of the file.
 
+use std::collections::HashMap;
+
 use rand::prelude::*;
 
-/// Edge indices, clockwise from the top.
-pub const TOP: usize = 0;
-pub const RIGHT: usize = 1;
-pub const BOTTOM: usize = 2;
-pub const LEFT: usize = 3;
+use crate::board;
+
+/// How many edges a tile has. They are indexed clockwise from the upper right
+/// of a pointy-top hexagon: north east, east, south east, south west, west,
+/// north west.
+pub const EDGES: usize = 6;
 
 /// The edge a neighbour in direction `dir` presents back to us.
 fn opposite(dir: usize) -> usize {
-    (dir + 2) % 4
+    (dir + EDGES / 2) % EDGES
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub enum TileKind {
-    Empty,
-    Straight,
-    Corner,
-    Tee,
+/// Where `board::neighbours` lists the cell across each edge.
+///
+/// It orders them west, east, then the row below, then the row above, because
+/// that is how the offset rows are easiest to write down. The solver needs
+/// them by edge, so this is the one place the two orders meet. Getting it
+/// wrong mirrors the tiles — adjacency would still hold, so the solver tests
+/// would pass, but arms would be drawn pointing at the wrong neighbour.
+const NEIGHBOUR_SLOT: [usize; EDGES] = [
+    5, // north east: the row above, right of centre
+    1, // east
+    3, // south east: the row below, right of centre
+    2, // south west: the row below, left of centre
+    0, // west
+    4, // north west: the row above, left of centre
+];
+
+/// What an edge carries: `NO_ARM`, or one of its set's arms, counted from one.
+pub type Label = u8;
+
+pub const NO_ARM: Label = 0;
+
+/// The most distinct tiles, rotations counted, a set may have: a cell's
+/// remaining options are one bit per tile.
+pub const MAX_POOL: usize = 64;
+
+/// How an arm with a given label is drawn. Plain numbers, so this module stays
+/// free of Bevy; `spawn_tile_arms` turns them into shapes.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ArmStyle {
+    pub label: String,
+    /// Width as a share of the distance from a cell's centre to its side.
+    pub width: f32,
+    /// How far the arm's colour moves from the round's towards white, or
+    /// towards black when negative.
+    pub shade: f32,
 }
 
-/// A tile and how far it has been turned, in clockwise quarter turns.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub struct Tile {
-    pub kind: TileKind,
-    pub rotation: u8,
+/// One tile as a set describes it, before rotation.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TileShape {
+    pub name: String,
+    pub edges: [Label; EDGES],
+    /// How often the solver picks it, against the others a cell could take.
+    pub weight: u32,
 }
 
-impl Tile {
-    pub fn new(kind: TileKind, rotation: u8) -> Self {
-        Self {
-            kind,
-            rotation: rotation % 4,
-        }
+/// Everything the generator and the renderer need to know about a family of
+/// tiles.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TileSet {
+    /// Where the set sits among the game's, so a tile kept in a replay can
+    /// find how it is drawn.
+    pub id: u8,
+    pub name: String,
+    /// The arms, by label: `arms[0]` is label 1.
+    pub arms: Vec<ArmStyle>,
+    pub shapes: Vec<TileShape>,
+    /// Pairs of different labels that fit each other. A label always fits
+    /// itself, and `NO_ARM` only fits `NO_ARM`.
+    pub joins: Vec<(Label, Label)>,
+}
+
+impl TileSet {
+    pub fn arm(&self, label: Label) -> Option<&ArmStyle> {
+        (label as usize).checked_sub(1).and_then(|index| self.arms.get(index))
     }
 
-    /// Which edges carry an arm, clockwise from the top.
-    pub fn edges(&self) -> [bool; 4] {
-        let base = match self.kind {
-            TileKind::Empty => [false, false, false, false],
-            // Top to bottom.
-            TileKind::Straight => [true, false, true, false],
-            // Top to right.
-            TileKind::Corner => [true, true, false, false],
-            // Everything but the left.
-            TileKind::Tee => [true, true, true, false],
-        };
+    /// Whether two facing edges agree.
+    pub fn fits(&self, a: Label, b: Label) -> bool {
+        a == b || self.joins.iter().any(|join| *join == (a, b) || *join == (b, a))
+    }
 
-        let rotation = (self.rotation % 4) as usize;
-        let mut edges = [false; 4];
-        for index in 0..4 {
-            // Turning the tile clockwise moves the top edge to the right, so
-            // the edge now at `index` is the one that was `rotation` steps
-            // anticlockwise of it.
-            edges[index] = base[(index + 4 - rotation) % 4];
+    /// Shape `shape` turned `rotation` sixths of a turn clockwise.
+    pub fn tile_of(&self, shape: usize, rotation: u8) -> Tile {
+        let base = self.shapes[shape].edges;
+        let rotation = rotation % EDGES as u8;
+        let mut edges = [NO_ARM; EDGES];
+        for (index, edge) in edges.iter_mut().enumerate() {
+            // Turning the tile clockwise moves each edge to the next one
+            // round, so the edge now at `index` is the one that was
+            // `rotation` steps anticlockwise of it.
+            *edge = base[(index + EDGES - rotation as usize) % EDGES];
         }
 
-        edges
+        Tile {
+            set: self.id,
+            shape: shape as u8,
+            rotation,
+            edges,
+        }
     }
 
-}
+    /// The shape called `name`, turned. For tests and hand-built boards.
+    #[cfg(test)]
+    pub fn tile(&self, name: &str, rotation: u8) -> Option<Tile> {
+        let shape = self.shapes.iter().position(|shape| shape.name == name)?;
+        Some(self.tile_of(shape, rotation))
+    }
 
-/// Every distinct tile. Rotations that produce a tile already in the set are
-/// left out, so each edge pattern appears exactly once.
-pub fn tile_pool() -> Vec<Tile> {
-    let mut pool: Vec<Tile> = Vec::new();
-
-    for kind in [
-        TileKind::Empty,
-        TileKind::Straight,
-        TileKind::Corner,
-        TileKind::Tee,
-    ] {
-        for rotation in 0..4 {
-            let tile = Tile::new(kind, rotation);
-            if !pool.iter().any(|existing| existing.edges() == tile.edges()) {
-                pool.push(tile);
+    /// Every distinct tile. Rotations that produce a tile already in the set
+    /// are left out, so each edge pattern appears exactly once.
+    pub fn pool(&self) -> Vec<Tile> {
+        let mut pool: Vec<Tile> = Vec::new();
+
+        for shape in 0..self.shapes.len() {
+            for rotation in 0..EDGES as u8 {
+                let tile = self.tile_of(shape, rotation);
+                if !pool.iter().any(|existing| existing.edges == tile.edges) {
+                    pool.push(tile);
+                }
             }
         }
+
+        pool
     }
+}
 
-    pool
+/// A tile on the board: which shape of which set, how far it has been turned,
+/// and the edges that leaves it with.
+///
+/// Carries its set and its edges rather than borrowing them, so it stays a
+/// small `Copy` value that a cell on screen, or a round kept for the replay,
+/// can hold on to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Tile {
+    pub set: u8,
+    pub shape: u8,
+    /// In clockwise sixths of a turn.
+    pub rotation: u8,
+    edges: [Label; EDGES],
+}
+
+impl Tile {
+    /// What each edge carries, clockwise from the upper right.
+    pub fn edges(&self) -> [Label; EDGES] {
+        self.edges
+    }
+
+    /// A blank plate: no arm on any edge.
+    pub fn is_empty(&self) -> bool {
+        self.edges.iter().all(|edge| *edge == NO_ARM)
+    }
+}
+
+/// The cells a board is made of, and which of them meet across which edge.
+///
+/// Built from `(column, row)` pairs in `board::layout`'s coordinates, in the
+/// order the board holds them, so index `i` here is piece `i` on screen.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Lattice {
+    neighbours: Vec<[Option<usize>; EDGES]>,
+}
+
+impl Lattice {
+    pub fn new(cells: &[(usize, usize)]) -> Self {
+        let index: HashMap<(usize, usize), usize> = cells
+            .iter()
+            .enumerate()
+            .map(|(index, cell)| (*cell, index))
+            .collect();
+
+        let neighbours = cells
+            .iter()
+            .map(|(column, row)| {
+                let around = board::neighbours(*column, *row);
+                let mut across = [None; EDGES];
+                for (dir, slot) in NEIGHBOUR_SLOT.iter().enumerate() {
+                    let (column, row) = around[*slot];
+                    if column >= 0 && row >= 0 {
+                        across[dir] = index.get(&(column as usize, row as usize)).copied();
+                    }
+                }
+                across
+            })
+            .collect();
+
+        Self { neighbours }
+    }
+
+    pub fn len(&self) -> usize {
+        self.neighbours.len()
+    }
+
+    /// The cell across edge `dir`, or `None` on the border.
+    pub fn neighbour(&self, index: usize, dir: usize) -> Option<usize> {
+        self.neighbours[index][dir]
+    }
 }
 
 /// A generated board: a tiling that fits together everywhere except at one
 /// cell.
 #[derive(Debug, Clone)]
 pub struct Mosaic {
-    pub columns: usize,
-    pub rows: usize,
+    /// The set the tiles come from, which says which edges agree.
+    pub set: TileSet,
+    pub lattice: Lattice,
     pub tiles: Vec<Tile>,
     /// The cell that does not fit — the answer.
     pub broken: usize,
-    /// How many of its four edges disagree with what surrounds them. This is
-    /// the difficulty dial: four is unmissable, one is a single short arm
+    /// How many of its edges disagree with what surrounds them. This is the
+    /// difficulty dial: four or more is unmissable, one is a single short arm
     /// pointing at nothing.
     pub violations: usize,
+    /// What the solver went through to fill the board.
+    pub stats: SolveStats,
 }
 
 impl Mosaic {
-    pub fn tile(&self, column: usize, row: usize) -> Tile {
-        self.tiles[row * self.columns + column]
-    }
-
-    /// The edge value a cell's neighbour presents in direction `dir`.
+    /// The edge a cell's neighbour presents in direction `dir`.
     ///
     /// Off the edge of the board counts as "no arm": the board is a closed
     /// composition, and an arm running off it reads as broken whether or not
     /// there is a neighbour to disagree with.
-    fn expected_edge(&self, index: usize, dir: usize) -> bool {
+    fn expected_edge(&self, index: usize, dir: usize) -> Label {
         match self.neighbour(index, dir) {
             Some(neighbour) => self.tiles[neighbour].edges()[opposite(dir)],
-            None => false,
+            None => NO_ARM,
         }
     }
 
     fn neighbour(&self, index: usize, dir: usize) -> Option<usize> {
-        neighbour_of(index, dir, self.columns, self.rows)
+        self.lattice.neighbour(index, dir)
     }
 
     /// How many of a cell's edges disagree with their surroundings.
     pub fn violations_at(&self, index: usize) -> usize {
         let edges = self.tiles[index].edges();
-        (0..4)
-            .filter(|dir| edges[*dir] != self.expected_edge(index, *dir))
+        (0..EDGES)
+            .filter(|dir| !self.set.fits(edges[*dir], self.expected_edge(index, *dir)))
             .count()
     }
+
+    /// Every cell that could be given some tile of the set that leaves the
+    /// whole board agreeing, with every other cell staying as laid.
+    ///
+    /// "Most bad edges" is the rule the player applies, but it stands in for
+    /// the question they are actually asked: which piece does not belong? On
+    /// a fair board that is one cell, the broken one. This asks the solver
+    /// instead of counting edges, so a board broken in two places, or one
+    /// where swapping a neighbour mends it just as well, shows up however the
+    /// counts happen to fall.
+    pub fn repairs(&self) -> Vec<usize> {
+        let pool = self.set.pool();
+        let mut search = Search::new(&self.set, &self.lattice, &pool);
+        let any = search.domains.first().copied().unwrap_or(0);
+
+        // A tile the set does not have fits nothing; only replacing it can
+        // help.
+        let laid: Vec<Domain> = self
+            .tiles
+            .iter()
+            .map(|tile| {
+                pool.iter()
+                    .position(|other| other.edges() == tile.edges())
+                    .map_or(0, |index| 1 << index)
+            })
+            .collect();
+
+        (0..self.tiles.len())
+            .filter(|index| {
+                search.trail.clear();
+                search.domains.clone_from(&laid);
+                search.domains[*index] = any;
+                search.close().is_some()
+            })
+            .collect()
+    }
 }
 
-/// Bitmask over `tile_pool()` indices. Eleven tiles, so a `u16` is plenty.
-type Domain = u16;
+/// Bitmask over a set's `pool()` indices, which `MAX_POOL` keeps to 64.
+type Domain = u64;
+
+/// What it took to fill a board. Kept with every deal, so the difficulty
+/// report can show a board size getting expensive before a player feels it.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SolveStats {
+    /// Searches started. More than one when a finished board came out too
+    /// sparse to play — see [`MIN_FILLED`] — or a search was given up on —
+    /// see [`STEPS_PER_SEARCH`].
+    pub attempts: usize,
+    /// Cells decided by a choice, rather than by propagation.
+    pub collapses: usize,
+    /// Choices taken back because they led to a contradiction.
+    pub backtracks: usize,
+    /// The step budget ran out, and the board is the best found before it did
+    /// or, failing that, empty.
+    pub out_of_steps: bool,
+}
 
-/// Generates a coherent tiling and then breaks exactly one cell of it.
+/// Generates a coherent tiling of `lattice` and then breaks exactly one cell
+/// of it.
 ///
 /// `violations` is a request, not a promise. Two constraints can get in the
-/// way: the pool has no tile one edge away from `Empty` (that would be a dead
-/// end), and a break has to stay unambiguous — see [`corrupt`]. The generator
+/// way: the set may have no tile that far from what surrounds a cell (the
+/// first set has none one edge away from empty: that would be a dead end),
+/// and a break has to stay unambiguous — see [`corrupt`]. The generator
 /// tries every cell before settling for the closest it can do, and reports
 /// what it actually produced.
-pub fn generate(columns: usize, rows: usize, violations: usize, rng: &mut impl Rng) -> Mosaic {
-    let pool = tile_pool();
-    let tiles = solve(columns, rows, &pool, rng).unwrap_or_else(|| {
-        // An all-empty board satisfies every constraint. Reaching this means
-        // the solver hit contradictions on every attempt, which should not
-        // happen on grids this size — but a boring board beats a panic.
-        vec![Tile::new(TileKind::Empty, 0); columns * rows]
-    });
+pub fn generate(set: &TileSet, lattice: Lattice, violations: usize, rng: &mut impl Rng) -> Mosaic {
+    let pool = set.pool();
+    let (tiles, stats) = tiling(set, &lattice, &pool, rng);
 
     let mut mosaic = Mosaic {
-        columns,
-        rows,
+        set: set.clone(),
+        lattice,
         tiles,
         broken: 0,
         violations: 0,
+        stats,
     };
 
-    corrupt(&mut mosaic, &pool, violations.clamp(1, 4), rng);
+    corrupt(&mut mosaic, &pool, violations.clamp(1, EDGES), rng);
     mosaic
 }
 
+/// A tiling of `lattice` that agrees everywhere, and what it took to find.
+fn tiling(
+    set: &TileSet,
+    lattice: &Lattice,
+    pool: &[Tile],
+    rng: &mut impl Rng,
+) -> (Vec<Tile>, SolveStats) {
+    let (tiles, stats) = solve(set, lattice, pool, rng);
+    let tiles = tiles.unwrap_or_else(|| {
+        // An all-empty board satisfies every constraint, and every set has an
+        // empty tile — `tile_sets` refuses one without. Reaching this means
+        // the step budget ran out before the search found anything, which
+        // should not happen on boards the game deals — but a boring board
+        // beats a panic.
+        let empty = pool.iter().copied().find(Tile::is_empty);
+        vec![empty.expect("every set has an empty tile"); lattice.len()]
+    });
+
+    (tiles, stats)
+}
+
 /// Share of cells that must carry a piece for the board to be worth playing.
 ///
 /// An all-empty tiling satisfies every constraint, so the solver is perfectly
@@ -202,30 +406,69 @@ pub fn generate(columns: usize, rows: usize, violations: usize, rng: &mut impl R
 /// solving until the board has something to say.
 const MIN_FILLED: f32 = 0.6;
 
-/// Fills the grid with tiles that all agree with their neighbours.
+/// Steps the solver may take per cell, across every attempt at a board. A
+/// step is one choice or one choice taken back.
+///
+/// A board that fills without a contradiction takes one step per undecided
+/// cell, so this leaves room for a few dozen attempts and a great deal of
+/// backtracking. What it rules out is the search wandering: the time a deal
+/// takes is bounded by the size of the board, whatever the board turns out
+/// to be.
+const STEPS_PER_CELL: usize = 64;
+
+/// Steps per cell one search may take before it is given up on and the board
+/// started again.
+///
+/// Backtracking only ever takes back the latest choice, and a contradiction
+/// is not always the latest choice's fault: the wires set, where a crossing
+/// fixes both colours at once, can make a mistake near the start that only
+/// shows twenty cells later, and then spend every step it has reshuffling
+/// the twenty cells in between. A fresh search with different choices gets
+/// out of that far faster than the old one does, so no single search gets
+/// more than an eighth of the budget.
+const STEPS_PER_SEARCH: usize = 8;
+
+/// Fills the lattice with tiles that all agree with their neighbours.
 ///
-/// Returns `None` if every attempt ran into a contradiction.
-fn solve(columns: usize, rows: usize, pool: &[Tile], rng: &mut impl Rng) -> Option<Vec<Tile>> {
-    // Grids here are at most a few dozen cells, so restarting costs less than
-    // the bookkeeping a backtracking solver would need — and it doubles as the
-    // retry for a board that came out too sparse.
+/// Returns no tiles if the step budget ran out before a single search
+/// finished, which is then the one thing `out_of_steps` reports: a search
+/// given up on for the next is the solver working as intended.
+fn solve(
+    set: &TileSet,
+    lattice: &Lattice,
+    pool: &[Tile],
+    rng: &mut impl Rng,
+) -> (Option<Vec<Tile>>, SolveStats) {
+    // A contradiction no longer costs a restart, so this is the retry for a
+    // board that came out too sparse or a search that lost its way.
     const ATTEMPTS: usize = 32;
 
-    let cells = (columns * rows) as f32;
+    let cells = lattice.len() as f32;
+    let mut steps = lattice.len() * STEPS_PER_CELL;
+    let mut stats = SolveStats::default();
     let mut best: Option<(Vec<Tile>, usize)> = None;
 
     for _ in 0..ATTEMPTS {
-        let Some(tiles) = attempt(columns, rows, pool, rng) else {
+        if steps == 0 {
+            break;
+        }
+        stats.attempts += 1;
+
+        let granted = steps.min(lattice.len() * STEPS_PER_SEARCH);
+        let mut allowance = granted;
+        let tiles = Search::new(set, lattice, pool).run(rng, &mut allowance, &mut stats);
+        steps -= granted - allowance;
+        let Some(tiles) = tiles else {
             continue;
         };
 
         let filled = tiles
             .iter()
-            .filter(|tile| tile.kind != TileKind::Empty)
+            .filter(|tile| !tile.is_empty())
             .count();
 
         if filled as f32 / cells >= MIN_FILLED {
-            return Some(tiles);
+            return (Some(tiles), stats);
         }
 
         if best.as_ref().map(|(_, most)| filled > *most).unwrap_or(true) {
@@ -233,121 +476,213 @@ fn solve(columns: usize, rows: usize, pool: &[Tile], rng: &mut impl Rng) -> Opti
         }
     }
 
-    best.map(|(tiles, _)| tiles)
+    stats.out_of_steps = best.is_none();
+    (best.map(|(tiles, _)| tiles), stats)
 }
 
-fn attempt(columns: usize, rows: usize, pool: &[Tile], rng: &mut impl Rng) -> Option<Vec<Tile>> {
-    let cells = columns * rows;
-    let full: Domain = (1 << pool.len()) - 1;
-    let mut domains = vec![full; cells];
-
-    // The board is closed: no arm may point off it. This is what gives the
-    // border cells something to satisfy, and it is why the finished mosaic
-    // reads as one object instead of a crop of a larger pattern.
-    for index in 0..cells {
-        let column = index % columns;
-        let row = index / columns;
-
-        for dir in 0..4 {
-            let outside = match dir {
-                TOP => row == 0,
-                BOTTOM => row + 1 == rows,
-                RIGHT => column + 1 == columns,
-                LEFT => column == 0,
-                _ => false,
-            };
+/// A cell decided by a choice, and what it would take to unmake it.
+struct Choice {
+    index: usize,
+    /// Length of the trail before this cell was collapsed: undoing back to it
+    /// puts every domain back as it stood before the choice.
+    mark: usize,
+    /// Tiles still to try here. Each one that led to a contradiction is
+    /// struck off, so backtracking never walks into the same wall twice.
+    untried: Domain,
+}
+
+/// One search for a tiling, with everything it needs to take a choice back.
+///
+/// Every narrowed domain goes on the trail with the value it replaced. Undoing
+/// a choice is popping the trail back to where the choice began, which undoes
+/// its propagation along with it — however far that spread — without copying
+/// the board at every step.
+struct Search<'a> {
+    lattice: &'a Lattice,
+    pool: &'a [Tile],
+    /// How often each tile in the pool is picked, by pool index.
+    weights: Vec<u32>,
+    /// `edge_masks[label][dir]`: the tiles whose edge in `dir` carries
+    /// `label`.
+    edge_masks: Vec<[Domain; EDGES]>,
+    /// `fitting[label][dir]`: the tiles that could sit across edge `dir` from
+    /// an edge carrying `label`.
+    fitting: Vec<[Domain; EDGES]>,
+    domains: Vec<Domain>,
+    trail: Vec<(usize, Domain)>,
+}
+
+impl<'a> Search<'a> {
+    fn new(set: &TileSet, lattice: &'a Lattice, pool: &'a [Tile]) -> Self {
+        let labels = set.arms.len() + 1;
 
-            if outside {
-                domains[index] &= mask_with_edge(pool, dir, false);
+        let mut edge_masks = vec![[0; EDGES]; labels];
+        for (index, tile) in pool.iter().enumerate() {
+            for (dir, label) in tile.edges().iter().enumerate() {
+                edge_masks[*label as usize][dir] |= 1 << index;
             }
         }
 
-        if domains[index] == 0 {
-            return None;
+        let mut fitting = vec![[0; EDGES]; labels];
+        for (label, masks) in fitting.iter_mut().enumerate() {
+            for (dir, mask) in masks.iter_mut().enumerate() {
+                for (other, across) in edge_masks.iter().enumerate() {
+                    if set.fits(label as Label, other as Label) {
+                        *mask |= across[opposite(dir)];
+                    }
+                }
+            }
+        }
+
+        Self {
+            lattice,
+            pool,
+            weights: pool
+                .iter()
+                .map(|tile| set.shapes[tile.shape as usize].weight)
+                .collect(),
+            edge_masks,
+            fitting,
+            domains: vec![Domain::MAX >> (Domain::BITS as usize - pool.len()); lattice.len()],
+            trail: Vec::new(),
         }
     }
 
-    propagate(&mut domains, columns, rows, pool)?;
+    /// Searches until every cell is decided. `None` if the budget ran out, or
+    /// if there is no tiling at all — which cannot happen with an empty tile
+    /// in the pool, but is not the search's to assume.
+    fn run(
+        mut self,
+        rng: &mut impl Rng,
+        steps: &mut usize,
+        stats: &mut SolveStats,
+    ) -> Option<Vec<Tile>> {
+        self.close()?;
+
+        let mut choices: Vec<Choice> = Vec::new();
+        let mut next = self.next_choice(rng);
+
+        while let Some(mut choice) = next.take() {
+            if *steps == 0 {
+                return None;
+            }
+            *steps -= 1;
+
+            let Some(chosen) = choose(choice.untried, &self.weights, rng) else {
+                // Every tile here leads to a contradiction, so the mistake was
+                // made earlier: take back the choice before this one and try
+                // something else there.
+                let previous = choices.pop()?;
+                self.undo(previous.mark);
+                stats.backtracks += 1;
+                next = Some(previous);
+                continue;
+            };
 
-    while let Some(index) = lowest_entropy(&domains, rng) {
-        let chosen = choose(domains[index], pool, rng)?;
-        domains[index] = 1 << chosen;
-        propagate(&mut domains, columns, rows, pool)?;
-    }
+            choice.untried &= !(1 << chosen);
+            stats.collapses += 1;
+
+            self.narrow(choice.index, 1 << chosen);
+            if self.propagate(vec![choice.index]).is_some() {
+                choices.push(choice);
+                next = self.next_choice(rng);
+            } else {
+                self.undo(choice.mark);
+                stats.backtracks += 1;
+                next = Some(choice);
+            }
+        }
 
-    domains
-        .iter()
-        .map(|domain| Some(pool[domain.trailing_zeros() as usize]))
-        .collect()
-}
+        Some(
+            self.domains
+                .iter()
+                .map(|domain| self.pool[domain.trailing_zeros() as usize])
+                .collect(),
+        )
+    }
 
-/// The set of tiles whose edge in `dir` has the given value.
-fn mask_with_edge(pool: &[Tile], dir: usize, value: bool) -> Domain {
-    let mut mask = 0;
-    for (index, tile) in pool.iter().enumerate() {
-        if tile.edges()[dir] == value {
-            mask |= 1 << index;
+    /// Rules out every arm pointing off the board, and whatever that rules
+    /// out in turn.
+    ///
+    /// The board is closed: no arm may point off it. This is what gives the
+    /// border cells something to satisfy, and it is why the finished mosaic
+    /// reads as one object instead of a crop of a larger pattern.
+    fn close(&mut self) -> Option<()> {
+        for index in 0..self.domains.len() {
+            for dir in 0..EDGES {
+                if self.lattice.neighbour(index, dir).is_none() {
+                    let closed = self.domains[index] & self.edge_masks[NO_ARM as usize][dir];
+                    self.narrow(index, closed);
+                }
+            }
         }
+
+        self.propagate((0..self.domains.len()).collect())
     }
-    mask
-}
 
-/// Narrows every domain until nothing more can be ruled out.
-///
-/// Returns `None` on a contradiction — a cell with no tile left.
-fn propagate(
-    domains: &mut [Domain],
-    columns: usize,
-    rows: usize,
-    pool: &[Tile],
-) -> Option<()> {
-    let mut queue: Vec<usize> = (0..domains.len()).collect();
+    /// The next cell to decide, or `None` once every cell is.
+    fn next_choice(&self, rng: &mut impl Rng) -> Option<Choice> {
+        lowest_entropy(&self.domains, rng).map(|index| Choice {
+            index,
+            mark: self.trail.len(),
+            untried: self.domains[index],
+        })
+    }
 
-    while let Some(index) = queue.pop() {
-        for dir in 0..4 {
-            let Some(neighbour) = neighbour_of(index, dir, columns, rows) else {
-                continue;
-            };
+    fn narrow(&mut self, index: usize, domain: Domain) {
+        if domain != self.domains[index] {
+            self.trail.push((index, self.domains[index]));
+            self.domains[index] = domain;
+        }
+    }
 
-            // Whatever this cell ends up being, its edge in `dir` is one of
-            // these values — so the neighbour's facing edge must be one of
-            // them too.
-            let mut allowed: Domain = 0;
-            for value in [true, false] {
-                if domains[index] & mask_with_edge(pool, dir, value) != 0 {
-                    allowed |= mask_with_edge(pool, opposite(dir), value);
-                }
-            }
+    /// Puts every domain back as it was when the trail was `mark` long.
+    fn undo(&mut self, mark: usize) {
+        while self.trail.len() > mark {
+            let (index, domain) = self.trail.pop().expect("trail longer than mark");
+            self.domains[index] = domain;
+        }
+    }
 
-            let narrowed = domains[neighbour] & allowed;
-            if narrowed == 0 {
+    /// Narrows the neighbours of every cell in `queue`, and theirs in turn,
+    /// until nothing more can be ruled out.
+    ///
+    /// Returns `None` on a contradiction — a cell with no tile left. The
+    /// domains are left as they were when it was found; the caller undoes.
+    fn propagate(&mut self, mut queue: Vec<usize>) -> Option<()> {
+        while let Some(index) = queue.pop() {
+            if self.domains[index] == 0 {
                 return None;
             }
 
-            if narrowed != domains[neighbour] {
-                domains[neighbour] = narrowed;
-                queue.push(neighbour);
+            for dir in 0..EDGES {
+                let Some(neighbour) = self.lattice.neighbour(index, dir) else {
+                    continue;
+                };
+
+                // Whatever this cell ends up being, its edge in `dir` carries
+                // one of these labels — so the neighbour's facing edge must
+                // fit one of them.
+                let mut allowed: Domain = 0;
+                for (masks, fitting) in self.edge_masks.iter().zip(&self.fitting) {
+                    if self.domains[index] & masks[dir] != 0 {
+                        allowed |= fitting[dir];
+                    }
+                }
+
+                let narrowed = self.domains[neighbour] & allowed;
+                if narrowed == 0 {
+                    return None;
+                }
+
+                if narrowed != self.domains[neighbour] {
+                    self.narrow(neighbour, narrowed);
+                    queue.push(neighbour);
+                }
             }
         }
-    }
-
-    Some(())
-}
 
-/// Cells are indexed the way `BoardGrid` lays them out: left to right, then
-/// top to bottom, so row 0 is the *top* row. Getting this backwards mirrors
-/// every tile vertically — adjacency still holds, so the tests would pass, but
-/// arms would be drawn pointing the wrong way.
-fn neighbour_of(index: usize, dir: usize, columns: usize, rows: usize) -> Option<usize> {
-    let column = index % columns;
-    let row = index / columns;
-
-    match dir {
-        TOP => (row > 0).then(|| (row - 1) * columns + column),
-        BOTTOM => (row + 1 < rows).then(|| (row + 1) * columns + column),
-        RIGHT => (column + 1 < columns).then(|| row * columns + column + 1),
-        LEFT => (column > 0).then(|| row * columns + column - 1),
-        _ => None,
+        Some(())
     }
 }
 
@@ -375,26 +710,22 @@ fn lowest_entropy(domains: &[Domain], rng: &mut impl Rng) -> Option<usize> {
     candidates.choose(rng).copied()
 }
 
-/// Picks one tile out of a domain.
+/// Picks one tile out of a domain, by the weights its set gave each.
 ///
-/// `Empty` is weighted down: it fits beside anything that presents no arm, so
-/// an unweighted choice fills most of the board with blanks and leaves nothing
-/// for the eye to check.
-fn choose(domain: Domain, pool: &[Tile], rng: &mut impl Rng) -> Option<usize> {
+/// The sets weight their empty tile down, and should: it fits beside anything
+/// that presents no arm, so an unweighted choice snowballs — one blank cell
+/// makes blank neighbours cheap, and the board drains.
+fn choose(domain: Domain, weights: &[u32], rng: &mut impl Rng) -> Option<usize> {
     let mut options: Vec<(usize, u32)> = Vec::new();
     let mut total = 0;
 
-    for (index, tile) in pool.iter().enumerate() {
+    for (index, weight) in weights.iter().enumerate() {
         if domain & (1 << index) == 0 {
             continue;
         }
 
-        // Empty fits beside anything that presents no arm, so an unweighted
-        // choice snowballs: one blank cell makes blank neighbours cheap, and
-        // the board drains.
-        let weight = if tile.kind == TileKind::Empty { 1 } else { 6 };
         total += weight;
-        options.push((index, weight));
+        options.push((index, *weight));
     }
 
     if options.is_empty() {
@@ -438,7 +769,9 @@ fn corrupt(mosaic: &mut Mosaic, pool: &[Tile], wanted: usize, rng: &mut impl Rng
     let mut fallback: Option<(usize, Tile, usize)> = None;
 
     for index in cells {
-        let expected: Vec<bool> = (0..4).map(|dir| mosaic.expected_edge(index, dir)).collect();
+        let expected: Vec<Label> = (0..EDGES)
+            .map(|dir| mosaic.expected_edge(index, dir))
+            .collect();
         let original = mosaic.tiles[index];
 
         let mut candidates: Vec<Tile> = pool
@@ -450,8 +783,9 @@ fn corrupt(mosaic: &mut Mosaic, pool: &[Tile], wanted: usize, rng: &mut impl Rng
 
         for candidate in candidates {
             let edges = candidate.edges();
-            let broken_dirs: Vec<usize> =
-                (0..4).filter(|dir| edges[*dir] != expected[*dir]).collect();
+            let broken_dirs: Vec<usize> = (0..EDGES)
+                .filter(|dir| !mosaic.set.fits(edges[*dir], expected[*dir]))
+                .collect();
             let mismatches = broken_dirs.len();
 
             let unambiguous = mismatches >= 2
@@ -490,44 +824,202 @@ fn corrupt(mosaic: &mut Mosaic, pool: &[Tile], wanted: usize, rng: &mut impl Rng
     }
 }
 
+/// A `Repair` board: a tiling with some of its pieces turned out of true.
+#[derive(Debug, Clone)]
+pub struct Scramble {
+    pub tiles: Vec<Tile>,
+    /// Pieces turned. Only the tests read it; the game goes by the par.
+    #[cfg(test)]
+    pub turned: usize,
+    /// Taps that put every turned piece back as it was cut. Another
+    /// arrangement that fits may take fewer; this one is always there.
+    pub par: usize,
+    pub stats: SolveStats,
+}
+
+/// Edges of a board that disagree: each pair of neighbours counted once, and
+/// every arm that runs off the board.
+pub fn bad_edges(set: &TileSet, lattice: &Lattice, tiles: &[Tile]) -> usize {
+    let mut bad = 0;
+    for (index, tile) in tiles.iter().enumerate() {
+        for (dir, edge) in tile.edges().iter().enumerate() {
+            bad += match lattice.neighbour(index, dir) {
+                // Met from both sides; counted from the lower index.
+                Some(other) if other > index => {
+                    usize::from(!set.fits(*edge, tiles[other].edges()[opposite(dir)]))
+                }
+                Some(_) => 0,
+                None => usize::from(!set.fits(*edge, NO_ARM)),
+            };
+        }
+    }
+    bad
+}
+
+/// Generates a coherent tiling of `lattice` and turns `pieces` of it.
+///
+/// Only a turn that changes a piece's edges counts: a straight turned half
+/// way round is the same straight, and a blank is the same at every angle. So
+/// fewer than `pieces` are turned when the board has fewer that can be, and a
+/// piece turned to where it happens to fit still counts toward the par,
+/// because the player has no way to tell it was ever moved. If every turn
+/// landed that way the board would already be repaired; the turning goes on
+/// past `pieces` until something is out of place, or nothing left can be.
+pub fn scramble(set: &TileSet, lattice: Lattice, pieces: usize, rng: &mut impl Rng) -> Scramble {
+    let pool = set.pool();
+    let (mut tiles, stats) = tiling(set, &lattice, &pool, rng);
+
+    let mut cells: Vec<usize> = (0..tiles.len()).collect();
+    cells.shuffle(rng);
+
+    let mut turned = 0;
+    let mut par = 0;
+
+    for index in cells {
+        if turned >= pieces && bad_edges(set, &lattice, &tiles) > 0 {
+            break;
+        }
+
+        let original = tiles[index];
+        let turns: Vec<u8> = (1..EDGES as u8)
+            .filter(|turn| {
+                set.tile_of(original.shape as usize, original.rotation + turn).edges()
+                    != original.edges()
+            })
+            .collect();
+        let Some(turn) = turns.choose(rng).copied() else {
+            continue;
+        };
+
+        let scrambled = set.tile_of(original.shape as usize, original.rotation + turn);
+        // Taps only ever turn clockwise, so the way back is the rest of the
+        // way round — or less, for a piece that repeats itself on the way.
+        let back = (1..=EDGES as u8)
+            .find(|taps| {
+                set.tile_of(original.shape as usize, scrambled.rotation + taps).edges()
+                    == original.edges()
+            })
+            .expect("a full turn brings any piece back");
+
+        tiles[index] = scrambled;
+        turned += 1;
+        par += back as usize;
+    }
+
+    Scramble {
+        tiles,
+        #[cfg(test)]
+        turned,
+        par,
+        stats,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::tile_sets;
 
     fn rng() -> StdRng {
         StdRng::seed_from_u64(20_260_808)
     }
 
+    const NORTH_EAST: usize = 0;
+    const EAST: usize = 1;
+    const SOUTH_EAST: usize = 2;
+    const SOUTH_WEST: usize = 3;
+    const WEST: usize = 4;
+    const NORTH_WEST: usize = 5;
+
+    /// The cells `board::layout` deals for `columns` and `rows`: odd rows sit
+    /// half a cell in and hold one fewer.
+    fn honeycomb(columns: usize, rows: usize) -> Lattice {
+        let cells: Vec<(usize, usize)> = (0..rows)
+            .flat_map(|row| {
+                let width = if row % 2 == 0 { columns } else { columns - 1 };
+                (0..width).map(move |column| (column, row))
+            })
+            .collect();
+        Lattice::new(&cells)
+    }
+
+    const BOARDS: [(usize, usize); 5] = [(4, 3), (4, 4), (5, 4), (5, 5), (6, 6)];
+
+    /// Every set the game deals. The guarantees below are about the sets as
+    /// much as the solver, so they are checked against each one.
+    fn sets() -> impl Iterator<Item = &'static TileSet> {
+        tile_sets::table().sets()
+    }
+
+    /// The set every run starts on.
+    fn pipes() -> &'static TileSet {
+        sets().find(|set| set.name == "pipes").unwrap()
+    }
+
+    fn arms(tile: &Tile) -> [bool; EDGES] {
+        tile.edges().map(|label| label != NO_ARM)
+    }
+
     #[test]
     fn every_edge_pattern_appears_at_most_once() {
-        let pool = tile_pool();
-        for (index, tile) in pool.iter().enumerate() {
-            for other in pool.iter().skip(index + 1) {
-                assert_ne!(tile.edges(), other.edges(), "duplicate edge pattern");
+        for set in sets() {
+            let pool = set.pool();
+            for (index, tile) in pool.iter().enumerate() {
+                for other in pool.iter().skip(index + 1) {
+                    assert_ne!(tile.edges(), other.edges(), "duplicate edge pattern");
+                }
             }
         }
     }
 
     #[test]
     fn the_pool_is_incomplete_on_purpose() {
-        // Dead ends and crosses are excluded; without that gap the constraints
-        // would never rule anything out. If this fails, propagation has quietly
-        // become a no-op.
-        let pool = tile_pool();
-        assert_eq!(pool.len(), 11);
-        assert!(!pool.iter().any(|tile| tile.edges() == [true; 4]));
-        assert!(!pool
+        // Dead ends and crowded junctions are excluded; without that gap the
+        // constraints would never rule anything out. If this fails,
+        // propagation has quietly become a no-op.
+        let pool = pipes().pool();
+        let counts: Vec<usize> = pool
             .iter()
-            .any(|tile| tile.edges().iter().filter(|edge| **edge).count() == 1));
+            .map(|tile| arms(tile).iter().filter(|arm| **arm).count())
+            .collect();
+        assert_eq!(pool.len(), 18);
+        assert!(!counts.contains(&1));
+        assert!(counts.iter().all(|count| *count <= 3));
     }
 
     #[test]
     fn rotation_turns_clockwise() {
-        let corner = Tile::new(TileKind::Corner, 0);
-        assert_eq!(corner.edges(), [true, true, false, false]);
+        let bend = pipes().tile("bend", 0).unwrap();
+        assert_eq!(arms(&bend), [true, true, false, false, false, false]);
+
+        let turned = pipes().tile("bend", 1).unwrap();
+        assert_eq!(arms(&turned), [false, true, true, false, false, false]);
+
+        let round = pipes().tile("bend", 5).unwrap();
+        assert_eq!(arms(&round), [true, false, false, false, false, true]);
+    }
+
+    /// Neighbours agree with each other about which edge they share, on both
+    /// the even rows and the indented odd ones.
+    #[test]
+    fn the_lattice_is_symmetric() {
+        let lattice = honeycomb(5, 4);
+
+        for index in 0..lattice.len() {
+            for dir in 0..EDGES {
+                if let Some(neighbour) = lattice.neighbour(index, dir) {
+                    assert_eq!(lattice.neighbour(neighbour, opposite(dir)), Some(index));
+                }
+            }
+        }
 
-        let turned = Tile::new(TileKind::Corner, 1);
-        assert_eq!(turned.edges(), [false, true, true, false]);
+        // The bottom-left cell touches only the cell east of it and the one
+        // up and to the right; everything else is off the board.
+        assert_eq!(lattice.neighbour(0, EAST), Some(1));
+        assert_eq!(lattice.neighbour(0, NORTH_EAST), Some(5));
+        for dir in [SOUTH_EAST, SOUTH_WEST, WEST, NORTH_WEST] {
+            assert_eq!(lattice.neighbour(0, dir), None);
+        }
     }
 
     /// The round has exactly one defensible answer.
@@ -541,9 +1033,9 @@ mod tests {
     fn the_broken_cell_is_the_only_defensible_answer() {
         let mut rng = rng();
 
-        for (columns, rows) in [(2, 3), (3, 3), (3, 4), (4, 4), (4, 5)] {
-            for wanted in 1..=4 {
-                let mosaic = generate(columns, rows, wanted, &mut rng);
+        for (set, (columns, rows)) in sets().flat_map(|set| BOARDS.map(|board| (set, board))) {
+            for wanted in 1..=EDGES {
+                let mosaic = generate(set, honeycomb(columns, rows), wanted, &mut rng);
                 let broken = mosaic.violations_at(mosaic.broken);
 
                 assert!(broken > 0, "the broken cell must actually be broken");
@@ -556,7 +1048,8 @@ mod tests {
 
                     assert!(
                         mosaic.violations_at(index) < broken,
-                        "{}x{} cell {} is as suspect as the answer",
+                        "{} {}x{} cell {} is as suspect as the answer",
+                        set.name,
                         columns,
                         rows,
                         index
@@ -566,28 +1059,88 @@ mod tests {
         }
     }
 
+    /// And the solver agrees: of every cell on the board, swapping the broken
+    /// one's tile is the only swap that mends it.
+    #[test]
+    fn only_the_broken_cell_mends_the_board() {
+        let mut rng = rng();
+
+        for (set, (columns, rows)) in sets().flat_map(|set| BOARDS.map(|board| (set, board))) {
+            for wanted in 1..=EDGES {
+                let mosaic = generate(set, honeycomb(columns, rows), wanted, &mut rng);
+                assert_eq!(
+                    mosaic.repairs(),
+                    vec![mosaic.broken],
+                    "{} {}x{} asking for {}",
+                    set.name,
+                    columns,
+                    rows,
+                    wanted
+                );
+            }
+        }
+    }
+
+    /// On a dealt `Mosaic` every disagreement is at the broken cell, so the
+    /// board's bad edges are exactly the break's.
+    #[test]
+    fn bad_edges_counts_each_disagreement_once() {
+        let mut rng = rng();
+
+        for set in sets() {
+            for wanted in 1..=EDGES {
+                let mosaic = generate(set, honeycomb(5, 4), wanted, &mut rng);
+                assert_eq!(
+                    bad_edges(set, &mosaic.lattice, &mosaic.tiles),
+                    mosaic.violations
+                );
+            }
+        }
+    }
+
+    /// A scrambled board has something to repair, and the par covers every
+    /// piece that was turned.
+    #[test]
+    fn a_scrambled_board_needs_repairing() {
+        let mut rng = rng();
+
+        for (set, (columns, rows)) in sets().flat_map(|set| BOARDS.map(|board| (set, board))) {
+            for pieces in [1, 3, 5] {
+                let lattice = honeycomb(columns, rows);
+                let scramble = scramble(set, lattice.clone(), pieces, &mut rng);
+
+                assert!(bad_edges(set, &lattice, &scramble.tiles) > 0);
+                assert!(scramble.turned >= pieces);
+                assert!(scramble.par >= scramble.turned);
+                assert!(scramble.par < scramble.turned * EDGES);
+            }
+        }
+    }
+
     /// A single violation is only fair against the edge of the board, where
     /// the other party to the disagreement is the void.
     #[test]
     fn a_lone_violation_points_off_the_board() {
         let mut rng = rng();
 
-        for _ in 0..40 {
-            let mosaic = generate(4, 4, 1, &mut rng);
-            if mosaic.violations != 1 {
-                continue;
-            }
+        for set in sets() {
+            for _ in 0..40 {
+                let mosaic = generate(set, honeycomb(5, 5), 1, &mut rng);
+                if mosaic.violations != 1 {
+                    continue;
+                }
 
-            let index = mosaic.broken;
-            let edges = mosaic.tiles[index].edges();
-            let broken_dir = (0..4)
-                .find(|dir| edges[*dir] != mosaic.expected_edge(index, *dir))
-                .expect("a broken cell has a broken edge");
+                let index = mosaic.broken;
+                let edges = mosaic.tiles[index].edges();
+                let broken_dir = (0..EDGES)
+                    .find(|dir| !set.fits(edges[*dir], mosaic.expected_edge(index, *dir)))
+                    .expect("a broken cell has a broken edge");
 
-            assert!(
-                mosaic.neighbour(index, broken_dir).is_none(),
-                "a lone violation between two cells is ambiguous"
-            );
+                assert!(
+                    mosaic.neighbour(index, broken_dir).is_none(),
+                    "a lone violation between two cells is ambiguous"
+                );
+            }
         }
     }
 
@@ -596,97 +1149,171 @@ mod tests {
     fn the_board_is_not_mostly_empty() {
         let mut rng = rng();
 
-        for (columns, rows) in [(2, 3), (3, 3), (3, 4), (4, 4), (4, 5)] {
-            let mosaic = generate(columns, rows, 2, &mut rng);
-            let filled = mosaic
-                .tiles
-                .iter()
-                .filter(|tile| tile.kind != TileKind::Empty)
-                .count();
-
-            assert!(
-                filled * 2 >= mosaic.tiles.len(),
-                "{}x{} board has only {} pieces on {} cells",
-                columns,
-                rows,
-                filled,
-                mosaic.tiles.len()
-            );
+        for set in sets() {
+            for (columns, rows) in BOARDS {
+                let mosaic = generate(set, honeycomb(columns, rows), 2, &mut rng);
+                let filled = mosaic.tiles.iter().filter(|tile| !tile.is_empty()).count();
+
+                assert!(
+                    filled * 2 >= mosaic.tiles.len(),
+                    "{} {}x{} board has only {} pieces on {} cells",
+                    set.name,
+                    columns,
+                    rows,
+                    filled,
+                    mosaic.tiles.len()
+                );
+            }
         }
     }
 
     /// The impostor must not be recognisable by its shape alone.
     ///
-    /// At one broken edge, and at three, the tile set leaves exactly one
-    /// reachable answer — a three-armed piece — so the round became "find the
-    /// T" and the pattern stopped mattering. The game asks for two or four; if
-    /// a future change reintroduces the others, this fails.
+    /// At an odd number of broken edges the pipes all but force the impostor
+    /// to be a fork — the only piece with an odd number of arms — so the round
+    /// became "find the fork" and the pattern stopped mattering. The game asks
+    /// for two or four; if a future change, or a new set, brings the tell
+    /// back, this fails.
     #[test]
     fn the_impostor_is_not_always_the_same_shape() {
         let mut rng = rng();
 
-        for wanted in [2, 4] {
-            let mut kinds = std::collections::BTreeSet::new();
+        for set in sets() {
+            for wanted in [2, 4] {
+                let mut shapes = std::collections::BTreeSet::new();
+
+                for _ in 0..150 {
+                    let mosaic = generate(set, honeycomb(4, 4), wanted, &mut rng);
+                    shapes.insert(mosaic.tiles[mosaic.broken].shape);
+                }
+
+                assert!(
+                    shapes.len() >= 2,
+                    "{} broken edges in {} always produces {:?}",
+                    wanted,
+                    set.name,
+                    shapes
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn the_requested_difficulty_is_usually_met() {
+        let mut rng = rng();
+        let attempts = 40;
 
-            for _ in 0..150 {
-                let mosaic = generate(3, 4, wanted, &mut rng);
-                kinds.insert(format!("{:?}", mosaic.tiles[mosaic.broken].kind));
+        for set in sets() {
+            let mut met = 0;
+            for _ in 0..attempts {
+                let mosaic = generate(set, honeycomb(4, 4), 2, &mut rng);
+                if mosaic.violations == 2 {
+                    met += 1;
+                }
             }
 
+            // Not all boards can be broken by a single edge — see `generate`.
+            // This guards against the weaker claim silently becoming the
+            // common case.
             assert!(
-                kinds.len() >= 2,
-                "{} broken edges always produces {:?}",
-                wanted,
-                kinds
+                met > attempts / 2,
+                "only {} of {} {} boards hit the target",
+                met,
+                attempts,
+                set.name
             );
         }
     }
 
+    /// Taking a choice back has to take back everything it caused, however
+    /// far its propagation spread. Anything left narrowed would quietly rule
+    /// out tiles the search never actually refuted.
     #[test]
-    fn the_requested_difficulty_is_usually_met() {
+    fn undoing_a_choice_restores_every_domain_it_narrowed() {
+        let lattice = honeycomb(5, 4);
+        let set = pipes();
+        let pool = set.pool();
+        let mut search = Search::new(set, &lattice, &pool);
+        search.close().expect("an empty board always fits");
+
+        let before = search.domains.clone();
+        let mark = search.trail.len();
+
+        // A fork in the middle of the board forces arms into three of its
+        // neighbours, and blanks out the other three edges.
+        let fork = set.tile("fork", 0).unwrap();
+        let fork = pool.iter().position(|tile| *tile == fork).unwrap();
+        search.narrow(7, 1 << fork);
+        search.propagate(vec![7]).expect("a fork fits in the middle");
+        assert!(
+            (0..lattice.len()).filter(|index| search.domains[*index] != before[*index]).count() > 1,
+            "the choice should have spread past its own cell"
+        );
+
+        search.undo(mark);
+        assert_eq!(search.domains, before);
+    }
+
+    /// The restarting solver this replaced was fine at a couple of dozen
+    /// cells and got less predictable with every row. Boards ten times the
+    /// size of the biggest the game deals have to fill, full, inside the
+    /// budget, with the contradictions on the way recovered from rather than
+    /// restarted over.
+    #[test]
+    fn boards_far_beyond_the_games_fill_reliably() {
         let mut rng = rng();
-        let mut met = 0;
-        let attempts = 40;
+        let mut backtracks = 0;
+
+        for set in sets() {
+            for (columns, rows) in [(10, 10), (16, 16), (16, 30)] {
+                for _ in 0..3 {
+                    let mosaic = generate(set, honeycomb(columns, rows), 2, &mut rng);
+                    let filled = mosaic.tiles.iter().filter(|tile| !tile.is_empty()).count();
+
+                    assert!(
+                        !mosaic.stats.out_of_steps,
+                        "{} {}x{} ran out of steps",
+                        set.name,
+                        columns,
+                        rows
+                    );
+                    assert!(
+                        filled as f32 >= mosaic.tiles.len() as f32 * MIN_FILLED,
+                        "{} {}x{} board has only {} pieces on {} cells",
+                        set.name,
+                        columns,
+                        rows,
+                        filled,
+                        mosaic.tiles.len()
+                    );
+                    assert_eq!(mosaic.violations_at(mosaic.broken), mosaic.violations);
 
-        for _ in 0..attempts {
-            let mosaic = generate(3, 4, 2, &mut rng);
-            if mosaic.violations == 2 {
-                met += 1;
+                    backtracks += mosaic.stats.backtracks;
+                }
             }
         }
 
-        // Not all boards can be broken by a single edge — see `generate`. This
-        // guards against the weaker claim silently becoming the common case.
-        assert!(met > attempts / 2, "only {} of {} boards hit the target", met, attempts);
+        assert!(backtracks > 0, "no contradiction was ever recovered from");
     }
 
     #[test]
     fn no_arm_leaves_the_board_except_at_the_break() {
         let mut rng = rng();
-        let mosaic = generate(3, 4, 2, &mut rng);
 
-        for index in 0..mosaic.tiles.len() {
-            if index == mosaic.broken {
-                continue;
-            }
+        for set in sets() {
+            let mosaic = generate(set, honeycomb(5, 4), 2, &mut rng);
 
-            let column = index % mosaic.columns;
-            let row = index / mosaic.columns;
-            let edges = mosaic.tiles[index].edges();
+            for index in 0..mosaic.tiles.len() {
+                if index == mosaic.broken {
+                    continue;
+                }
 
-            if row == 0 {
-                assert!(!edges[TOP]);
-            }
-            if row + 1 == mosaic.rows {
-                assert!(!edges[BOTTOM]);
-            }
-            if column == 0 {
-                assert!(!edges[LEFT]);
-            }
-            if column + 1 == mosaic.columns {
-                assert!(!edges[RIGHT]);
+                for (dir, edge) in mosaic.tiles[index].edges().iter().enumerate() {
+                    if mosaic.neighbour(index, dir).is_none() {
+                        assert_eq!(*edge, NO_ARM, "cell {} points off the board", index);
+                    }
+                }
             }
         }
     }
 }
-